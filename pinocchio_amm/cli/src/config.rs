@@ -0,0 +1,175 @@
+use pinocchio_amm::state::Config;
+use serde_json::{json, Value};
+use solana_pubkey::Pubkey;
+
+/// Decode the data of a pool config account into JSON.
+///
+/// `u128` values are written as strings, since JSON numbers lose precision past 2^53.
+pub fn decode(data: &[u8]) -> Result<Value, String> {
+    if data.len() != Config::LEN {
+        return Err(format!(
+            "expected {} bytes of config data, got {}",
+            Config::LEN,
+            data.len()
+        ));
+    }
+
+    // Config has an alignment of 1 and the length was checked above
+    let config = unsafe { Config::from_bytes_unchecked(data) };
+    let pubkey = |key: &[u8; 32]| Pubkey::new_from_array(*key).to_string();
+
+    Ok(json!({
+        "state": config.state(),
+        "seed": config.seed(),
+        "authority": pubkey(config.authority()),
+        "mint_x": pubkey(config.mint_x()),
+        "mint_y": pubkey(config.mint_y()),
+        "fee": config.fee(),
+        "config_bump": config.config_bump()[0],
+        "oracle": pubkey(config.oracle()),
+        "oracle_max_deviation_bps": config.oracle_max_deviation_bps(),
+        "oracle_max_age": config.oracle_max_age(),
+        "max_slot_move_bps": config.max_slot_move_bps(),
+        "last_slot": config.last_slot(),
+        "slot_start_price": config.slot_start_price().to_string(),
+        "slot_move_bps": config.slot_move_bps(),
+        "max_fee": config.max_fee(),
+        "volatility_fee_factor": config.volatility_fee_factor(),
+        "volatility_half_life": config.volatility_half_life(),
+        "volatility": config.volatility(),
+        "volatility_updated_at": config.volatility_updated_at(),
+        "curve": config.curve(),
+        "start_weight_x": config.start_weight_x(),
+        "end_weight_x": config.end_weight_x(),
+        "weights_start_time": config.weights_start_time(),
+        "weights_end_time": config.weights_end_time(),
+        "max_referral_bps": config.max_referral_bps(),
+        "allowlist": pubkey(config.allowlist()),
+        "guardian": pubkey(config.guardian()),
+        "lp_supply": config.lp_supply(),
+        "positioned_lp": config.positioned_lp(),
+        "fee_growth_x": config.fee_growth_x().to_string(),
+        "fee_growth_y": config.fee_growth_y().to_string(),
+        "fees_x": config.fees_x(),
+        "fees_y": config.fees_y(),
+        "orders_x": config.orders_x(),
+        "orders_y": config.orders_y(),
+        "volume_x": config.volume_x().to_string(),
+        "volume_y": config.volume_y().to_string(),
+        "cumulative_fees_x": config.cumulative_fees_x().to_string(),
+        "cumulative_fees_y": config.cumulative_fees_y().to_string(),
+        "trade_count": config.trade_count(),
+        "last_trade_slot": config.last_trade_slot(),
+        "day_start": config.day_start(),
+        "day_high_price": config.day_high_price().to_string(),
+        "day_low_price": config.day_low_price().to_string(),
+        "batch_window": config.batch_window(),
+        "open_batches": config.open_batches(),
+        "swap_hook": pubkey(config.swap_hook()),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pinocchio_amm::state::{AmmState, CurveType};
+
+    const AUTHORITY: [u8; 32] = [1; 32];
+    const MINT_X: [u8; 32] = [2; 32];
+    const MINT_Y: [u8; 32] = [3; 32];
+    const ORACLE: [u8; 32] = [4; 32];
+    const ALLOWLIST: [u8; 32] = [5; 32];
+    const GUARDIAN: [u8; 32] = [6; 32];
+    const SWAP_HOOK: [u8; 32] = [7; 32];
+
+    fn pubkey(key: [u8; 32]) -> Value {
+        Pubkey::new_from_array(key).to_string().into()
+    }
+
+    /// Build a config through the program setters and decode its bytes.
+    fn decoded(build: impl FnOnce(&mut Config)) -> Value {
+        let mut bytes = vec![0; Config::LEN];
+        build(unsafe { Config::from_bytes_unchecked_mut(&mut bytes) });
+        decode(&bytes).unwrap()
+    }
+
+    #[test]
+    fn decode_reads_every_field_where_the_program_writes_it() {
+        let config = decoded(|config| {
+            config
+                .set_inner(7, AUTHORITY, MINT_X, MINT_Y, 30, [254])
+                .unwrap();
+            config.set_oracle(ORACLE, 150, 60).unwrap();
+            config.set_max_slot_move_bps(500);
+            config.set_dynamic_fee(100, 5_000, 600).unwrap();
+            config.set_max_referral_bps(2_500).unwrap();
+            config.set_allowlist(ALLOWLIST);
+            config.set_guardian(GUARDIAN);
+            config.set_lp_supply(1_000_000);
+            config.set_positioned_lp(250_000);
+            config.accrue_fee(true, 40).unwrap();
+            config.add_orders(false, 900).unwrap();
+            config.record_swap(true, 10_000, 9_000, 30);
+            config.record_trade(42, 86_400 + 5, Some(1 << 100));
+            config.set_batch_window(10).unwrap();
+            config.open_batch().unwrap();
+            config.set_swap_hook(SWAP_HOOK).unwrap();
+        });
+
+        assert_eq!(config["state"], AmmState::Initialized as u8);
+        assert_eq!(config["seed"], 7);
+        assert_eq!(config["authority"], pubkey(AUTHORITY));
+        assert_eq!(config["mint_x"], pubkey(MINT_X));
+        assert_eq!(config["mint_y"], pubkey(MINT_Y));
+        assert_eq!(config["fee"], 30);
+        assert_eq!(config["config_bump"], 254);
+        assert_eq!(config["oracle"], pubkey(ORACLE));
+        assert_eq!(config["oracle_max_deviation_bps"], 150);
+        assert_eq!(config["oracle_max_age"], 60);
+        assert_eq!(config["max_slot_move_bps"], 500);
+        assert_eq!(config["max_fee"], 100);
+        assert_eq!(config["volatility_fee_factor"], 5_000);
+        assert_eq!(config["volatility_half_life"], 600);
+        assert_eq!(config["curve"], CurveType::ConstantProduct as u8);
+        assert_eq!(config["max_referral_bps"], 2_500);
+        assert_eq!(config["allowlist"], pubkey(ALLOWLIST));
+        assert_eq!(config["guardian"], pubkey(GUARDIAN));
+        assert_eq!(config["lp_supply"], 1_000_000);
+        assert_eq!(config["positioned_lp"], 250_000);
+        // 40 over a million LP, a quarter of which is positioned
+        assert_eq!(config["fee_growth_x"], "40000000");
+        assert_eq!(config["fee_growth_y"], "0");
+        assert_eq!(config["fees_x"], 10);
+        assert_eq!(config["orders_y"], 900);
+        assert_eq!(config["volume_x"], "10000");
+        assert_eq!(config["volume_y"], "9000");
+        assert_eq!(config["cumulative_fees_x"], "30");
+        assert_eq!(config["trade_count"], 1);
+        assert_eq!(config["last_trade_slot"], 42);
+        assert_eq!(config["day_start"], 86_400);
+        assert_eq!(config["day_high_price"], (1u128 << 100).to_string());
+        assert_eq!(config["day_low_price"], (1u128 << 100).to_string());
+        assert_eq!(config["batch_window"], 10);
+        assert_eq!(config["open_batches"], 1);
+        assert_eq!(config["swap_hook"], pubkey(SWAP_HOOK));
+    }
+
+    #[test]
+    fn decode_reads_the_weighted_curve() {
+        let config = decoded(|config| {
+            config.set_weights(8_000, 5_000, -10, 3_600).unwrap();
+        });
+
+        assert_eq!(config["curve"], CurveType::Weighted as u8);
+        assert_eq!(config["start_weight_x"], 8_000);
+        assert_eq!(config["end_weight_x"], 5_000);
+        assert_eq!(config["weights_start_time"], -10);
+        assert_eq!(config["weights_end_time"], 3_600);
+    }
+
+    #[test]
+    fn decode_rejects_data_of_another_length() {
+        assert!(decode(&vec![0; Config::LEN - 1]).is_err());
+        assert!(decode(&vec![0; Config::LEN + 1]).is_err());
+    }
+}
@@ -0,0 +1,306 @@
+use crate::pool::{associated_token_address, program_id, PoolAddresses};
+use pinocchio_amm::{Deadline, Deposit, Initialize, Swap, Withdraw};
+use serde_json::{json, Value};
+use solana_pubkey::Pubkey;
+
+pub struct AccountMeta {
+    pub pubkey: Pubkey,
+    pub is_signer: bool,
+    pub is_writable: bool,
+}
+
+impl AccountMeta {
+    pub fn writable(pubkey: Pubkey, is_signer: bool) -> Self {
+        Self {
+            pubkey,
+            is_signer,
+            is_writable: true,
+        }
+    }
+
+    pub fn readonly(pubkey: Pubkey, is_signer: bool) -> Self {
+        Self {
+            pubkey,
+            is_signer,
+            is_writable: false,
+        }
+    }
+}
+
+/// An unsigned AMM instruction, ready to be placed in a transaction.
+pub struct Instruction {
+    pub program_id: Pubkey,
+    pub accounts: Vec<AccountMeta>,
+    pub data: Vec<u8>,
+}
+
+impl Instruction {
+    fn new(discriminator: u8, accounts: Vec<AccountMeta>, data: &[u8]) -> Self {
+        let mut bytes = Vec::with_capacity(1 + data.len());
+        bytes.push(discriminator);
+        bytes.extend_from_slice(data);
+
+        Self {
+            program_id: program_id(),
+            accounts,
+            data: bytes,
+        }
+    }
+
+    pub fn to_json(&self) -> Value {
+        let accounts: Vec<Value> = self
+            .accounts
+            .iter()
+            .map(|meta| {
+                json!({
+                    "pubkey": meta.pubkey.to_string(),
+                    "is_signer": meta.is_signer,
+                    "is_writable": meta.is_writable,
+                })
+            })
+            .collect();
+
+        json!({
+            "program_id": self.program_id.to_string(),
+            "accounts": accounts,
+            "data": to_hex(&self.data),
+        })
+    }
+}
+
+/// Accounts a pool instruction is built against.
+pub struct Pool {
+    pub seed: u64,
+    pub mint_x: Pubkey,
+    pub mint_y: Pubkey,
+    pub token_program: Pubkey,
+    pub addresses: PoolAddresses,
+}
+
+impl Pool {
+    pub fn new(seed: u64, mint_x: Pubkey, mint_y: Pubkey, token_program: Pubkey) -> Self {
+        let addresses = PoolAddresses::derive(seed, &mint_x, &mint_y, &token_program);
+
+        Self {
+            seed,
+            mint_x,
+            mint_y,
+            token_program,
+            addresses,
+        }
+    }
+
+    fn user_ata(&self, user: &Pubkey, mint: &Pubkey) -> Pubkey {
+        associated_token_address(user, mint, &self.token_program)
+    }
+}
+
+/// Create the pool, with an optional authority and no LP metadata.
+pub fn initialize(
+    pool: &Pool,
+    initializer: Pubkey,
+    fee: u16,
+    lp_decimals: u8,
+    authority: Option<Pubkey>,
+) -> Instruction {
+    let mut data = Vec::new();
+    data.extend_from_slice(&pool.seed.to_le_bytes());
+    data.extend_from_slice(&fee.to_le_bytes());
+    data.extend_from_slice(pool.mint_x.as_ref());
+    data.extend_from_slice(pool.mint_y.as_ref());
+    data.push(pool.addresses.config_bump);
+    data.push(pool.addresses.lp_bump);
+    data.push(lp_decimals);
+    if let Some(authority) = authority {
+        data.extend_from_slice(authority.as_ref());
+    }
+
+    let accounts = vec![
+        AccountMeta::writable(initializer, true),
+        AccountMeta::writable(pool.addresses.mint_lp, false),
+        AccountMeta::writable(pool.addresses.config, false),
+        AccountMeta::readonly(Pubkey::new_from_array(pinocchio_system::ID), false),
+        AccountMeta::readonly(pool.token_program, false),
+    ];
+
+    Instruction::new(*Initialize::DISCRIMINATOR, accounts, &data)
+}
+
+/// Accounts shared by deposits and withdrawals, followed by the allowlist when given.
+fn liquidity_accounts(pool: &Pool, user: Pubkey, allowlist: Option<Pubkey>) -> Vec<AccountMeta> {
+    let mut accounts = vec![
+        AccountMeta::writable(user, true),
+        AccountMeta::writable(pool.addresses.mint_lp, false),
+        AccountMeta::writable(pool.addresses.vault_x, false),
+        AccountMeta::writable(pool.addresses.vault_y, false),
+        AccountMeta::writable(pool.user_ata(&user, &pool.mint_x), false),
+        AccountMeta::writable(pool.user_ata(&user, &pool.mint_y), false),
+        AccountMeta::writable(pool.user_ata(&user, &pool.addresses.mint_lp), false),
+        AccountMeta::writable(pool.addresses.config, false),
+        AccountMeta::readonly(pool.token_program, false),
+    ];
+    accounts.extend(allowlist.map(|allowlist| AccountMeta::readonly(allowlist, false)));
+    accounts
+}
+
+/// Version 2 liquidity data: `amount`, two token bounds, the deadline and the proof.
+fn liquidity_data(amount: u64, x: u64, y: u64, deadline: Deadline, proof: &[[u8; 32]]) -> Vec<u8> {
+    let mut data = Vec::new();
+    data.extend_from_slice(&amount.to_le_bytes());
+    data.extend_from_slice(&x.to_le_bytes());
+    data.extend_from_slice(&y.to_le_bytes());
+    data.extend_from_slice(&deadline.to_bytes());
+    data.extend(proof.iter().flatten());
+    data
+}
+
+/// Mint `amount` LP tokens for at most `max_x` and `max_y`.
+#[allow(clippy::too_many_arguments)]
+pub fn deposit(
+    pool: &Pool,
+    user: Pubkey,
+    amount: u64,
+    max_x: u64,
+    max_y: u64,
+    deadline: Deadline,
+    allowlist: Option<Pubkey>,
+    proof: &[[u8; 32]],
+) -> Instruction {
+    Instruction::new(
+        *Deposit::DISCRIMINATOR_V2,
+        liquidity_accounts(pool, user, allowlist),
+        &liquidity_data(amount, max_x, max_y, deadline, proof),
+    )
+}
+
+/// Burn `amount` LP tokens for at least `min_x` and `min_y`.
+#[allow(clippy::too_many_arguments)]
+pub fn withdraw(
+    pool: &Pool,
+    user: Pubkey,
+    amount: u64,
+    min_x: u64,
+    min_y: u64,
+    deadline: Deadline,
+    allowlist: Option<Pubkey>,
+    proof: &[[u8; 32]],
+) -> Instruction {
+    Instruction::new(
+        *Withdraw::DISCRIMINATOR_V2,
+        liquidity_accounts(pool, user, allowlist),
+        &liquidity_data(amount, min_x, min_y, deadline, proof),
+    )
+}
+
+/// Trailing swap accounts, in the order the program reads them.
+#[derive(Default)]
+pub struct SwapRemaining {
+    pub oracle: Option<Pubkey>,
+    pub referrer: Option<(Pubkey, u16)>,
+    pub allowlist: Option<Pubkey>,
+    pub hook: Option<Pubkey>,
+    pub range_orders: Vec<Pubkey>,
+}
+
+/// Swap `amount` of x (or y) for at least `min` of the other token.
+#[allow(clippy::too_many_arguments)]
+pub fn swap(
+    pool: &Pool,
+    user: Pubkey,
+    is_x: bool,
+    amount: u64,
+    min: u64,
+    deadline: Deadline,
+    remaining: SwapRemaining,
+    proof: &[[u8; 32]],
+) -> Instruction {
+    let mut data = Vec::new();
+    data.push(is_x as u8);
+    data.extend_from_slice(&amount.to_le_bytes());
+    data.extend_from_slice(&min.to_le_bytes());
+    data.extend_from_slice(&deadline.to_bytes());
+    if let Some((_, referral_bps)) = remaining.referrer {
+        data.extend_from_slice(&referral_bps.to_le_bytes());
+    }
+    data.extend(proof.iter().flatten());
+
+    let mut accounts = vec![
+        AccountMeta::writable(user, true),
+        AccountMeta::writable(pool.user_ata(&user, &pool.mint_x), false),
+        AccountMeta::writable(pool.user_ata(&user, &pool.mint_y), false),
+        AccountMeta::writable(pool.addresses.vault_x, false),
+        AccountMeta::writable(pool.addresses.vault_y, false),
+        AccountMeta::writable(pool.addresses.config, false),
+        AccountMeta::readonly(pool.token_program, false),
+    ];
+    accounts.extend(
+        remaining
+            .oracle
+            .map(|oracle| AccountMeta::readonly(oracle, false)),
+    );
+    if let Some((referrer, _)) = remaining.referrer.filter(|(_, bps)| *bps != 0) {
+        // Referral fees are taken from the input token
+        let mint = if is_x { &pool.mint_x } else { &pool.mint_y };
+        accounts.push(AccountMeta::writable(pool.user_ata(&referrer, mint), false));
+    }
+    accounts.extend(
+        remaining
+            .allowlist
+            .map(|allowlist| AccountMeta::readonly(allowlist, false)),
+    );
+    if let Some(hook) = remaining.hook {
+        // The hook program is followed by the address signing the hook call
+        let (authority, _) =
+            Pubkey::find_program_address(&[b"hook", pool.addresses.config.as_ref()], &program_id());
+        accounts.push(AccountMeta::readonly(hook, false));
+        accounts.push(AccountMeta::readonly(authority, false));
+    }
+    accounts.extend(
+        remaining
+            .range_orders
+            .into_iter()
+            .map(|order| AccountMeta::writable(order, false)),
+    );
+
+    Instruction::new(*Swap::DISCRIMINATOR_V2, accounts, &data)
+}
+
+/// An administration instruction signed by the pool authority, or by the guardian
+/// for `Pause`.
+pub fn admin(pool: &Pool, signer: Pubkey, discriminator: u8, data: &[u8]) -> Instruction {
+    let accounts = vec![
+        AccountMeta::readonly(signer, true),
+        AccountMeta::writable(pool.addresses.config, false),
+    ];
+
+    Instruction::new(discriminator, accounts, data)
+}
+
+/// `SetWeights` also reads the LP mint.
+pub fn set_weights(pool: &Pool, authority: Pubkey, data: &[u8]) -> Instruction {
+    let mut instruction = admin(
+        pool,
+        authority,
+        *pinocchio_amm::SetWeights::DISCRIMINATOR,
+        data,
+    );
+    instruction
+        .accounts
+        .push(AccountMeta::readonly(pool.addresses.mint_lp, false));
+    instruction
+}
+
+pub fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+pub fn from_hex(hex: &str) -> Result<Vec<u8>, String> {
+    if !hex.len().is_multiple_of(2) {
+        return Err(format!("odd length hex string: {hex}"));
+    }
+
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).map_err(|_| format!("invalid hex: {hex}")))
+        .collect()
+}
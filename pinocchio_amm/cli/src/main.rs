@@ -0,0 +1,853 @@
+//! Offline transaction builder for the AMM.
+//!
+//! Every command only derives addresses and serializes data, nothing is read from or sent to
+//! a cluster, so instructions can be built and signed on an air-gapped machine. Instructions
+//! are printed as JSON with the program id, the account metas and the hex encoded data.
+
+// The config decoder builds one large `json!` object
+#![recursion_limit = "256"]
+
+mod config;
+mod instruction;
+mod pool;
+
+use clap::{Args, Parser, Subcommand};
+use instruction::{from_hex, Instruction, Pool, SwapRemaining};
+use pinocchio_amm::Deadline;
+use serde_json::json;
+use solana_pubkey::Pubkey;
+use std::process::ExitCode;
+
+#[derive(Parser)]
+#[command(name = "amm-cli", about = "Build AMM instructions offline")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Args)]
+struct PoolArgs {
+    /// Pool seed
+    #[arg(long)]
+    seed: u64,
+    /// Mint of token x
+    #[arg(long)]
+    mint_x: Pubkey,
+    /// Mint of token y
+    #[arg(long)]
+    mint_y: Pubkey,
+    /// Token program owning both mints
+    #[arg(long, default_value_t = Pubkey::new_from_array(pinocchio_token::ID))]
+    token_program: Pubkey,
+}
+
+impl PoolArgs {
+    fn pool(&self) -> Pool {
+        Pool::new(self.seed, self.mint_x, self.mint_y, self.token_program)
+    }
+}
+
+#[derive(Args)]
+#[group(required = true, multiple = false)]
+struct DeadlineArgs {
+    /// Unix timestamp after which the instruction fails
+    #[arg(long)]
+    expires_at: Option<i64>,
+    /// Slot after which the instruction fails
+    #[arg(long)]
+    expires_at_slot: Option<u64>,
+}
+
+impl DeadlineArgs {
+    fn deadline(&self) -> Deadline {
+        match (self.expires_at, self.expires_at_slot) {
+            (Some(timestamp), _) => Deadline::UnixTimestamp(timestamp),
+            (_, Some(slot)) => Deadline::Slot(slot),
+            // Enforced by the argument group
+            (None, None) => unreachable!(),
+        }
+    }
+}
+
+#[derive(Args)]
+struct AllowlistArgs {
+    /// Pool allowlist, when the pool has one
+    #[arg(long)]
+    allowlist: Option<Pubkey>,
+    /// Merkle proof hashes, hex encoded, from the leaf up
+    #[arg(long, value_parser = parse_hash)]
+    proof: Vec<[u8; 32]>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Print the config, LP mint and vault addresses of a pool
+    Derive {
+        #[command(flatten)]
+        pool: PoolArgs,
+    },
+    /// Build an Initialize instruction
+    Initialize {
+        #[command(flatten)]
+        pool: PoolArgs,
+        /// Payer creating the pool
+        #[arg(long)]
+        initializer: Pubkey,
+        /// Swap fee in basis points
+        #[arg(long)]
+        fee: u16,
+        #[arg(long, default_value_t = 6)]
+        lp_decimals: u8,
+        /// Pool authority, the pool is immutable without one
+        #[arg(long)]
+        authority: Option<Pubkey>,
+    },
+    /// Build a Deposit instruction
+    Deposit {
+        #[command(flatten)]
+        pool: PoolArgs,
+        #[arg(long)]
+        user: Pubkey,
+        /// LP tokens to mint
+        #[arg(long)]
+        amount: u64,
+        #[arg(long)]
+        max_x: u64,
+        #[arg(long)]
+        max_y: u64,
+        #[command(flatten)]
+        deadline: DeadlineArgs,
+        #[command(flatten)]
+        allowlist: AllowlistArgs,
+    },
+    /// Build a Withdraw instruction
+    Withdraw {
+        #[command(flatten)]
+        pool: PoolArgs,
+        #[arg(long)]
+        user: Pubkey,
+        /// LP tokens to burn
+        #[arg(long)]
+        amount: u64,
+        #[arg(long)]
+        min_x: u64,
+        #[arg(long)]
+        min_y: u64,
+        #[command(flatten)]
+        deadline: DeadlineArgs,
+        #[command(flatten)]
+        allowlist: AllowlistArgs,
+    },
+    /// Build a Swap instruction
+    Swap {
+        #[command(flatten)]
+        pool: PoolArgs,
+        #[arg(long)]
+        user: Pubkey,
+        /// Swap y for x instead of x for y
+        #[arg(long)]
+        y_to_x: bool,
+        #[arg(long)]
+        amount: u64,
+        /// Minimum amount out
+        #[arg(long)]
+        min: u64,
+        #[command(flatten)]
+        deadline: DeadlineArgs,
+        /// Pool oracle, when the pool has one
+        #[arg(long)]
+        oracle: Option<Pubkey>,
+        /// Owner of the token account receiving the referral share
+        #[arg(long, requires = "referral_bps")]
+        referrer: Option<Pubkey>,
+        #[arg(long, requires = "referrer")]
+        referral_bps: Option<u16>,
+        #[command(flatten)]
+        allowlist: AllowlistArgs,
+        /// Pool swap hook program, when the pool has one
+        #[arg(long)]
+        hook: Option<Pubkey>,
+        /// Range orders to fill
+        #[arg(long)]
+        range_order: Vec<Pubkey>,
+    },
+    /// Build a SetState instruction
+    SetState {
+        #[command(flatten)]
+        pool: PoolArgs,
+        #[arg(long)]
+        authority: Pubkey,
+        #[arg(long)]
+        state: u8,
+    },
+    /// Build a Pause instruction, signed by the guardian
+    Pause {
+        #[command(flatten)]
+        pool: PoolArgs,
+        #[arg(long)]
+        guardian: Pubkey,
+        #[arg(long)]
+        state: u8,
+    },
+    /// Build a SetOracle instruction
+    SetOracle {
+        #[command(flatten)]
+        pool: PoolArgs,
+        #[arg(long)]
+        authority: Pubkey,
+        #[arg(long)]
+        oracle: Pubkey,
+        #[arg(long)]
+        max_deviation_bps: u16,
+        /// Maximum oracle age in seconds
+        #[arg(long)]
+        max_age: i64,
+    },
+    /// Build a SetCircuitBreaker instruction
+    SetCircuitBreaker {
+        #[command(flatten)]
+        pool: PoolArgs,
+        #[arg(long)]
+        authority: Pubkey,
+        #[arg(long)]
+        max_slot_move_bps: u16,
+    },
+    /// Build a SetDynamicFee instruction
+    SetDynamicFee {
+        #[command(flatten)]
+        pool: PoolArgs,
+        #[arg(long)]
+        authority: Pubkey,
+        #[arg(long)]
+        max_fee: u16,
+        #[arg(long)]
+        volatility_fee_factor: u16,
+        #[arg(long)]
+        volatility_half_life: i64,
+    },
+    /// Build a SetWeights instruction
+    SetWeights {
+        #[command(flatten)]
+        pool: PoolArgs,
+        #[arg(long)]
+        authority: Pubkey,
+        #[arg(long)]
+        start_weight_x: u16,
+        #[arg(long)]
+        end_weight_x: u16,
+        #[arg(long)]
+        start_time: i64,
+        #[arg(long)]
+        end_time: i64,
+    },
+    /// Build a SetReferralFee instruction
+    SetReferralFee {
+        #[command(flatten)]
+        pool: PoolArgs,
+        #[arg(long)]
+        authority: Pubkey,
+        #[arg(long)]
+        max_referral_bps: u16,
+    },
+    /// Build a SetGuardian instruction
+    SetGuardian {
+        #[command(flatten)]
+        pool: PoolArgs,
+        #[arg(long)]
+        authority: Pubkey,
+        #[arg(long)]
+        guardian: Pubkey,
+    },
+    /// Build a SetBatchWindow instruction
+    SetBatchWindow {
+        #[command(flatten)]
+        pool: PoolArgs,
+        #[arg(long)]
+        authority: Pubkey,
+        /// Batch length in slots, 0 turns batching off
+        #[arg(long)]
+        batch_window: u64,
+    },
+    /// Build a SetSwapHook instruction
+    SetSwapHook {
+        #[command(flatten)]
+        pool: PoolArgs,
+        #[arg(long)]
+        authority: Pubkey,
+        #[arg(long)]
+        swap_hook: Pubkey,
+    },
+    /// Decode a raw config account dump into JSON
+    DecodeConfig {
+        /// File holding the account data
+        path: std::path::PathBuf,
+    },
+}
+
+fn parse_hash(hex: &str) -> Result<[u8; 32], String> {
+    from_hex(hex)?
+        .try_into()
+        .map_err(|_| format!("expected a 32 byte hash: {hex}"))
+}
+
+fn run(command: Command) -> Result<serde_json::Value, String> {
+    let instruction: Instruction = match command {
+        Command::Derive { pool } => {
+            let addresses = pool.pool().addresses;
+
+            return Ok(json!({
+                "program_id": pool::program_id().to_string(),
+                "config": addresses.config.to_string(),
+                "config_bump": addresses.config_bump,
+                "mint_lp": addresses.mint_lp.to_string(),
+                "lp_bump": addresses.lp_bump,
+                "vault_x": addresses.vault_x.to_string(),
+                "vault_y": addresses.vault_y.to_string(),
+            }));
+        }
+        Command::DecodeConfig { path } => {
+            let data = std::fs::read(&path).map_err(|e| format!("{}: {e}", path.display()))?;
+
+            return config::decode(&data);
+        }
+        Command::Initialize {
+            pool,
+            initializer,
+            fee,
+            lp_decimals,
+            authority,
+        } => instruction::initialize(&pool.pool(), initializer, fee, lp_decimals, authority),
+        Command::Deposit {
+            pool,
+            user,
+            amount,
+            max_x,
+            max_y,
+            deadline,
+            allowlist,
+        } => instruction::deposit(
+            &pool.pool(),
+            user,
+            amount,
+            max_x,
+            max_y,
+            deadline.deadline(),
+            allowlist.allowlist,
+            &allowlist.proof,
+        ),
+        Command::Withdraw {
+            pool,
+            user,
+            amount,
+            min_x,
+            min_y,
+            deadline,
+            allowlist,
+        } => instruction::withdraw(
+            &pool.pool(),
+            user,
+            amount,
+            min_x,
+            min_y,
+            deadline.deadline(),
+            allowlist.allowlist,
+            &allowlist.proof,
+        ),
+        Command::Swap {
+            pool,
+            user,
+            y_to_x,
+            amount,
+            min,
+            deadline,
+            oracle,
+            referrer,
+            referral_bps,
+            allowlist,
+            hook,
+            range_order,
+        } => instruction::swap(
+            &pool.pool(),
+            user,
+            !y_to_x,
+            amount,
+            min,
+            deadline.deadline(),
+            SwapRemaining {
+                oracle,
+                referrer: referrer.zip(referral_bps),
+                allowlist: allowlist.allowlist,
+                hook,
+                range_orders: range_order,
+            },
+            &allowlist.proof,
+        ),
+        Command::SetState {
+            pool,
+            authority,
+            state,
+        } => instruction::admin(
+            &pool.pool(),
+            authority,
+            *pinocchio_amm::SetState::DISCRIMINATOR,
+            &[state],
+        ),
+        Command::Pause {
+            pool,
+            guardian,
+            state,
+        } => instruction::admin(
+            &pool.pool(),
+            guardian,
+            *pinocchio_amm::Pause::DISCRIMINATOR,
+            &[state],
+        ),
+        Command::SetOracle {
+            pool,
+            authority,
+            oracle,
+            max_deviation_bps,
+            max_age,
+        } => {
+            let mut data = oracle.to_bytes().to_vec();
+            data.extend_from_slice(&max_deviation_bps.to_le_bytes());
+            data.extend_from_slice(&max_age.to_le_bytes());
+
+            instruction::admin(
+                &pool.pool(),
+                authority,
+                *pinocchio_amm::SetOracle::DISCRIMINATOR,
+                &data,
+            )
+        }
+        Command::SetCircuitBreaker {
+            pool,
+            authority,
+            max_slot_move_bps,
+        } => instruction::admin(
+            &pool.pool(),
+            authority,
+            *pinocchio_amm::SetCircuitBreaker::DISCRIMINATOR,
+            &max_slot_move_bps.to_le_bytes(),
+        ),
+        Command::SetDynamicFee {
+            pool,
+            authority,
+            max_fee,
+            volatility_fee_factor,
+            volatility_half_life,
+        } => {
+            let mut data = max_fee.to_le_bytes().to_vec();
+            data.extend_from_slice(&volatility_fee_factor.to_le_bytes());
+            data.extend_from_slice(&volatility_half_life.to_le_bytes());
+
+            instruction::admin(
+                &pool.pool(),
+                authority,
+                *pinocchio_amm::SetDynamicFee::DISCRIMINATOR,
+                &data,
+            )
+        }
+        Command::SetWeights {
+            pool,
+            authority,
+            start_weight_x,
+            end_weight_x,
+            start_time,
+            end_time,
+        } => {
+            let mut data = start_weight_x.to_le_bytes().to_vec();
+            data.extend_from_slice(&end_weight_x.to_le_bytes());
+            data.extend_from_slice(&start_time.to_le_bytes());
+            data.extend_from_slice(&end_time.to_le_bytes());
+
+            instruction::set_weights(&pool.pool(), authority, &data)
+        }
+        Command::SetReferralFee {
+            pool,
+            authority,
+            max_referral_bps,
+        } => instruction::admin(
+            &pool.pool(),
+            authority,
+            *pinocchio_amm::SetReferralFee::DISCRIMINATOR,
+            &max_referral_bps.to_le_bytes(),
+        ),
+        Command::SetGuardian {
+            pool,
+            authority,
+            guardian,
+        } => instruction::admin(
+            &pool.pool(),
+            authority,
+            *pinocchio_amm::SetGuardian::DISCRIMINATOR,
+            guardian.as_ref(),
+        ),
+        Command::SetBatchWindow {
+            pool,
+            authority,
+            batch_window,
+        } => instruction::admin(
+            &pool.pool(),
+            authority,
+            *pinocchio_amm::SetBatchWindow::DISCRIMINATOR,
+            &batch_window.to_le_bytes(),
+        ),
+        Command::SetSwapHook {
+            pool,
+            authority,
+            swap_hook,
+        } => instruction::admin(
+            &pool.pool(),
+            authority,
+            *pinocchio_amm::SetSwapHook::DISCRIMINATOR,
+            swap_hook.as_ref(),
+        ),
+    };
+
+    Ok(instruction.to_json())
+}
+
+fn main() -> ExitCode {
+    match run(Cli::parse().command) {
+        Ok(output) => {
+            println!("{}", serde_json::to_string_pretty(&output).unwrap());
+            ExitCode::SUCCESS
+        }
+        Err(error) => {
+            eprintln!("error: {error}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pinocchio_amm::*;
+
+    const MINT_X: Pubkey = Pubkey::new_from_array([1; 32]);
+    const MINT_Y: Pubkey = Pubkey::new_from_array([2; 32]);
+    const SIGNER: Pubkey = Pubkey::new_from_array([3; 32]);
+    const OTHER: Pubkey = Pubkey::new_from_array([4; 32]);
+
+    /// Run `command` with `args` against a pool of seed 7, returning the discriminator
+    /// and the rest of the instruction data.
+    fn build(command: &str, args: &[String]) -> (u8, Vec<u8>) {
+        let pool = [
+            "--seed",
+            "7",
+            "--mint-x",
+            &MINT_X.to_string(),
+            "--mint-y",
+            &MINT_Y.to_string(),
+        ];
+        let cli = Cli::try_parse_from(
+            ["amm-cli", command]
+                .into_iter()
+                .chain(pool)
+                .chain(args.iter().map(String::as_str)),
+        )
+        .unwrap();
+
+        let output = run(cli.command).unwrap();
+        assert_eq!(output["program_id"], pool::program_id().to_string());
+
+        let data = from_hex(output["data"].as_str().unwrap()).unwrap();
+        (data[0], data[1..].to_vec())
+    }
+
+    fn args(args: &[(&str, &dyn ToString)]) -> Vec<String> {
+        // Joined with `=` so that negative values are not read as flags
+        args.iter()
+            .map(|(flag, value)| format!("--{flag}={}", value.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn initialize_matches_the_program_layout() {
+        let (discriminator, data) = build(
+            "initialize",
+            &args(&[
+                ("initializer", &SIGNER),
+                ("fee", &30),
+                ("authority", &OTHER),
+            ]),
+        );
+        let parsed = InitializeInstructionData::try_from(data.as_slice()).unwrap();
+        let addresses = pool::PoolAddresses::derive(
+            7,
+            &MINT_X,
+            &MINT_Y,
+            &Pubkey::new_from_array(pinocchio_token::ID),
+        );
+
+        assert_eq!(discriminator, *Initialize::DISCRIMINATOR);
+        // The data is packed, fields are copied out before comparing
+        assert_eq!({ parsed.seed }, 7);
+        assert_eq!({ parsed.fee }, 30);
+        assert_eq!(parsed.mint_x, MINT_X.to_bytes());
+        assert_eq!(parsed.mint_y, MINT_Y.to_bytes());
+        assert_eq!(parsed.config_bump, [addresses.config_bump]);
+        assert_eq!(parsed.lp_bump, [addresses.lp_bump]);
+        assert_eq!(parsed.lp_decimals, 6);
+        assert_eq!(parsed.authority, OTHER.to_bytes());
+    }
+
+    #[test]
+    fn deposit_and_withdraw_match_the_program_layout() {
+        let proof = "ab".repeat(32);
+
+        let (discriminator, data) = build(
+            "deposit",
+            &args(&[
+                ("user", &SIGNER),
+                ("amount", &100),
+                ("max-x", &200),
+                ("max-y", &300),
+                ("expires-at-slot", &400),
+                ("allowlist", &OTHER),
+                ("proof", &proof),
+            ]),
+        );
+        let parsed = DepositInstructionData::try_from_v2(&data).unwrap();
+
+        assert_eq!(discriminator, *Deposit::DISCRIMINATOR_V2);
+        assert_eq!((parsed.amount, parsed.max_x, parsed.max_y), (100, 200, 300));
+        assert_eq!(parsed.deadline, Deadline::Slot(400));
+        assert_eq!(parsed.proof, [0xab; 32]);
+
+        let (discriminator, data) = build(
+            "withdraw",
+            &args(&[
+                ("user", &SIGNER),
+                ("amount", &100),
+                ("min-x", &200),
+                ("min-y", &300),
+                ("expires-at", &-400),
+            ]),
+        );
+        let parsed = WithdrawInstructionData::try_from_v2(&data).unwrap();
+
+        assert_eq!(discriminator, *Withdraw::DISCRIMINATOR_V2);
+        assert_eq!((parsed.amount, parsed.min_x, parsed.min_y), (100, 200, 300));
+        assert_eq!(parsed.deadline, Deadline::UnixTimestamp(-400));
+        assert!(parsed.proof.is_empty());
+    }
+
+    #[test]
+    fn swap_matches_the_program_layout() {
+        let (discriminator, data) = build(
+            "swap",
+            &args(&[
+                ("user", &SIGNER),
+                ("amount", &100),
+                ("min", &90),
+                ("expires-at-slot", &400),
+                ("referrer", &OTHER),
+                ("referral-bps", &25),
+            ]),
+        );
+        let parsed = SwapInstructionData::try_from_v2(&data).unwrap();
+
+        assert_eq!(discriminator, *Swap::DISCRIMINATOR_V2);
+        assert!(parsed.is_x);
+        assert_eq!((parsed.amount, parsed.min), (100, 90));
+        assert_eq!(parsed.deadline, Deadline::Slot(400));
+        assert_eq!(parsed.referral_bps, 25);
+        assert!(parsed.proof.is_empty());
+
+        let mut y_to_x = args(&[("user", &SIGNER), ("amount", &100), ("min", &90)]);
+        y_to_x.extend(args(&[("expires-at", &500)]));
+        y_to_x.push("--y-to-x".to_string());
+        let (_, data) = build("swap", &y_to_x);
+        let parsed = SwapInstructionData::try_from_v2(&data).unwrap();
+
+        assert!(!parsed.is_x);
+        assert_eq!(parsed.referral_bps, 0);
+    }
+
+    #[test]
+    fn swap_accounts_follow_the_program_order() {
+        let cli = Cli::try_parse_from([
+            "amm-cli",
+            "swap",
+            "--seed",
+            "7",
+            "--mint-x",
+            &MINT_X.to_string(),
+            "--mint-y",
+            &MINT_Y.to_string(),
+            "--user",
+            &SIGNER.to_string(),
+            "--amount",
+            "100",
+            "--min",
+            "90",
+            "--expires-at-slot",
+            "400",
+            "--oracle",
+            &OTHER.to_string(),
+            "--hook",
+            &MINT_Y.to_string(),
+            "--range-order",
+            &MINT_X.to_string(),
+        ])
+        .unwrap();
+        let output = run(cli.command).unwrap();
+        let accounts = output["accounts"].as_array().unwrap();
+        let config = pool::PoolAddresses::derive(
+            7,
+            &MINT_X,
+            &MINT_Y,
+            &Pubkey::new_from_array(pinocchio_token::ID),
+        )
+        .config;
+        let (hook_authority, _) =
+            Pubkey::find_program_address(&[b"hook", config.as_ref()], &pool::program_id());
+
+        // Seven fixed accounts, then the oracle, the hook and its signer, the range order
+        let keys: Vec<&str> = accounts[7..]
+            .iter()
+            .map(|meta| meta["pubkey"].as_str().unwrap())
+            .collect();
+        assert_eq!(
+            keys,
+            [
+                OTHER.to_string(),
+                MINT_Y.to_string(),
+                hook_authority.to_string(),
+                MINT_X.to_string()
+            ]
+        );
+        assert_eq!(accounts[5]["pubkey"], config.to_string());
+    }
+
+    #[test]
+    fn admin_instructions_match_the_program_layout() {
+        let authority = ("authority", &SIGNER as &dyn ToString);
+
+        let (discriminator, data) = build("set-state", &args(&[authority, ("state", &2)]));
+        assert_eq!(discriminator, *SetState::DISCRIMINATOR);
+        assert_eq!(
+            SetStateInstructionData::try_from(&data[..]).unwrap().state,
+            2
+        );
+
+        let (discriminator, data) = build("pause", &args(&[("guardian", &SIGNER), ("state", &3)]));
+        assert_eq!(discriminator, *Pause::DISCRIMINATOR);
+        assert_eq!(PauseInstructionData::try_from(&data[..]).unwrap().state, 3);
+
+        let (discriminator, data) = build(
+            "set-oracle",
+            &args(&[
+                authority,
+                ("oracle", &OTHER),
+                ("max-deviation-bps", &150),
+                ("max-age", &60),
+            ]),
+        );
+        let parsed = SetOracleInstructionData::try_from(&data[..]).unwrap();
+        assert_eq!(discriminator, *SetOracle::DISCRIMINATOR);
+        assert_eq!(parsed.oracle, OTHER.to_bytes());
+        assert_eq!((parsed.max_deviation_bps, parsed.max_age), (150, 60));
+
+        let (discriminator, data) = build(
+            "set-circuit-breaker",
+            &args(&[authority, ("max-slot-move-bps", &500)]),
+        );
+        assert_eq!(discriminator, *SetCircuitBreaker::DISCRIMINATOR);
+        assert_eq!(
+            SetCircuitBreakerInstructionData::try_from(&data[..])
+                .unwrap()
+                .max_slot_move_bps,
+            500
+        );
+
+        let (discriminator, data) = build(
+            "set-dynamic-fee",
+            &args(&[
+                authority,
+                ("max-fee", &100),
+                ("volatility-fee-factor", &5_000),
+                ("volatility-half-life", &60),
+            ]),
+        );
+        let parsed = SetDynamicFeeInstructionData::try_from(&data[..]).unwrap();
+        assert_eq!(discriminator, *SetDynamicFee::DISCRIMINATOR);
+        assert_eq!(
+            (
+                parsed.max_fee,
+                parsed.volatility_fee_factor,
+                parsed.volatility_half_life
+            ),
+            (100, 5_000, 60)
+        );
+
+        let (discriminator, data) = build(
+            "set-weights",
+            &args(&[
+                authority,
+                ("start-weight-x", &8_000),
+                ("end-weight-x", &2_000),
+                ("start-time", &10),
+                ("end-time", &20),
+            ]),
+        );
+        let parsed = SetWeightsInstructionData::try_from(&data[..]).unwrap();
+        assert_eq!(discriminator, *SetWeights::DISCRIMINATOR);
+        assert_eq!(
+            (
+                parsed.start_weight_x,
+                parsed.end_weight_x,
+                parsed.start_time,
+                parsed.end_time
+            ),
+            (8_000, 2_000, 10, 20)
+        );
+
+        let (discriminator, data) = build(
+            "set-referral-fee",
+            &args(&[authority, ("max-referral-bps", &40)]),
+        );
+        assert_eq!(discriminator, *SetReferralFee::DISCRIMINATOR);
+        assert_eq!(
+            SetReferralFeeInstructionData::try_from(&data[..])
+                .unwrap()
+                .max_referral_bps,
+            40
+        );
+
+        let (discriminator, data) =
+            build("set-guardian", &args(&[authority, ("guardian", &OTHER)]));
+        assert_eq!(discriminator, *SetGuardian::DISCRIMINATOR);
+        assert_eq!(
+            SetGuardianInstructionData::try_from(&data[..])
+                .unwrap()
+                .guardian,
+            OTHER.to_bytes()
+        );
+
+        let (discriminator, data) = build(
+            "set-batch-window",
+            &args(&[authority, ("batch-window", &10)]),
+        );
+        assert_eq!(discriminator, *SetBatchWindow::DISCRIMINATOR);
+        assert_eq!(
+            SetBatchWindowInstructionData::try_from(&data[..])
+                .unwrap()
+                .batch_window,
+            10
+        );
+
+        let (discriminator, data) =
+            build("set-swap-hook", &args(&[authority, ("swap-hook", &OTHER)]));
+        assert_eq!(discriminator, *SetSwapHook::DISCRIMINATOR);
+        assert_eq!(
+            SetSwapHookInstructionData::try_from(&data[..])
+                .unwrap()
+                .swap_hook,
+            OTHER.to_bytes()
+        );
+    }
+}
@@ -0,0 +1,74 @@
+use solana_pubkey::Pubkey;
+
+/// Addresses of a constant product pool, derived the way `Initialize` checks them.
+pub struct PoolAddresses {
+    pub config: Pubkey,
+    pub config_bump: u8,
+    pub mint_lp: Pubkey,
+    pub lp_bump: u8,
+    pub vault_x: Pubkey,
+    pub vault_y: Pubkey,
+}
+
+impl PoolAddresses {
+    pub fn derive(seed: u64, mint_x: &Pubkey, mint_y: &Pubkey, token_program: &Pubkey) -> Self {
+        let (config, config_bump) = Pubkey::find_program_address(
+            &[
+                b"config",
+                &seed.to_le_bytes(),
+                mint_x.as_ref(),
+                mint_y.as_ref(),
+            ],
+            &program_id(),
+        );
+        let (mint_lp, lp_bump) =
+            Pubkey::find_program_address(&[b"mint_lp", config.as_ref()], &program_id());
+
+        Self {
+            config,
+            config_bump,
+            mint_lp,
+            lp_bump,
+            vault_x: associated_token_address(&config, mint_x, token_program),
+            vault_y: associated_token_address(&config, mint_y, token_program),
+        }
+    }
+}
+
+/// The AMM program id.
+pub fn program_id() -> Pubkey {
+    Pubkey::new_from_array(pinocchio_amm::ID)
+}
+
+/// Associated token account of `owner` for `mint`.
+pub fn associated_token_address(owner: &Pubkey, mint: &Pubkey, token_program: &Pubkey) -> Pubkey {
+    let (address, _) = Pubkey::find_program_address(
+        &[owner.as_ref(), token_program.as_ref(), mint.as_ref()],
+        &Pubkey::new_from_array(pinocchio_associated_token_account::ID),
+    );
+    address
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn associated_token_address_matches_the_associated_token_program() {
+        let (owner, mint) = (Pubkey::new_unique(), Pubkey::new_unique());
+
+        for token_program in [
+            Pubkey::new_from_array(pinocchio_token::ID),
+            Pubkey::new_unique(),
+        ] {
+            assert_eq!(
+                associated_token_address(&owner, &mint, &token_program),
+                spl_associated_token_account::get_associated_token_address_with_program_id(
+                    &owner,
+                    &mint,
+                    &token_program
+                )
+            );
+        }
+    }
+}
@@ -0,0 +1,308 @@
+//! Cross-program invocation helpers for programs trading through AMM pools.
+//!
+//! Each helper builds the version 2 instruction, so deadlines may be slots, and forwards
+//! `remaining_accounts` after the fixed accounts in the order the pool expects them:
+//! oracle, referrer, allowlist, the wSOL accounts, the hook program, then the range
+//! orders to fill.
+
+use crate::helpers::Deadline;
+use core::mem::size_of;
+use pinocchio::{
+    account_info::AccountInfo,
+    cpi::invoke_signed_with_bounds,
+    instruction::{AccountMeta, Instruction, Signer},
+    program_error::ProgramError,
+    ProgramResult,
+};
+
+/// Most accounts a pool instruction takes, fixed and remaining ones together.
+pub const MAX_ACCOUNTS: usize = 16;
+
+/// Swap through a pool.
+///
+/// ### Accounts:
+///   0. `[WRITE, SIGNER]` User
+///   1. `[WRITE]` User token account of x
+///   2. `[WRITE]` User token account of y
+///   3. `[WRITE]` Pool vault of x
+///   4. `[WRITE]` Pool vault of y
+///   5. `[WRITE]` Pool config
+///   6. `[]` Token program
+pub struct Swap<'a> {
+    pub user: &'a AccountInfo,
+    pub user_x: &'a AccountInfo,
+    pub user_y: &'a AccountInfo,
+    pub vault_x: &'a AccountInfo,
+    pub vault_y: &'a AccountInfo,
+    pub config: &'a AccountInfo,
+    pub token_program: &'a AccountInfo,
+    pub remaining_accounts: &'a [&'a AccountInfo],
+    pub is_x: bool,
+    pub amount: u64,
+    pub min: u64,
+    pub deadline: Deadline,
+    pub referral_bps: u16,
+}
+
+impl Swap<'_> {
+    #[inline(always)]
+    pub fn invoke(&self) -> ProgramResult {
+        self.invoke_signed(&[])
+    }
+
+    pub fn invoke_signed(&self, signers: &[Signer]) -> ProgramResult {
+        let account_metas = [
+            AccountMeta::writable_signer(self.user.key()),
+            AccountMeta::writable(self.user_x.key()),
+            AccountMeta::writable(self.user_y.key()),
+            AccountMeta::writable(self.vault_x.key()),
+            AccountMeta::writable(self.vault_y.key()),
+            AccountMeta::writable(self.config.key()),
+            AccountMeta::readonly(self.token_program.key()),
+        ];
+
+        // discriminator + is_x + amount + min + deadline + referral share
+        let mut data = [0u8; 1 + 1 + size_of::<u64>() * 2 + Deadline::LEN + size_of::<u16>()];
+        data[0] = *crate::Swap::DISCRIMINATOR_V2;
+        data[1] = self.is_x as u8;
+        data[2..10].copy_from_slice(&self.amount.to_le_bytes());
+        data[10..18].copy_from_slice(&self.min.to_le_bytes());
+        data[18..27].copy_from_slice(&self.deadline.to_bytes());
+        data[27..29].copy_from_slice(&self.referral_bps.to_le_bytes());
+
+        // The referral share is left out when there is none
+        let data_len = match self.referral_bps {
+            0 => data.len() - size_of::<u16>(),
+            _ => data.len(),
+        };
+
+        invoke_pool(
+            &account_metas,
+            &[
+                self.user,
+                self.user_x,
+                self.user_y,
+                self.vault_x,
+                self.vault_y,
+                self.config,
+                self.token_program,
+            ],
+            self.remaining_accounts,
+            &data[..data_len],
+            signers,
+        )
+    }
+}
+
+/// Deposit into a pool.
+///
+/// ### Accounts:
+///   0. `[WRITE, SIGNER]` User
+///   1. `[WRITE]` LP mint
+///   2. `[WRITE]` Pool vault of x
+///   3. `[WRITE]` Pool vault of y
+///   4. `[WRITE]` User token account of x
+///   5. `[WRITE]` User token account of y
+///   6. `[WRITE]` User LP token account
+///   7. `[WRITE]` Pool config
+///   8. `[]` Token program
+pub struct Deposit<'a> {
+    pub user: &'a AccountInfo,
+    pub mint_lp: &'a AccountInfo,
+    pub vault_x: &'a AccountInfo,
+    pub vault_y: &'a AccountInfo,
+    pub user_x_ata: &'a AccountInfo,
+    pub user_y_ata: &'a AccountInfo,
+    pub user_lp_ata: &'a AccountInfo,
+    pub config: &'a AccountInfo,
+    pub token_program: &'a AccountInfo,
+    pub remaining_accounts: &'a [&'a AccountInfo],
+    pub amount: u64,
+    pub max_x: u64,
+    pub max_y: u64,
+    pub deadline: Deadline,
+}
+
+impl Deposit<'_> {
+    #[inline(always)]
+    pub fn invoke(&self) -> ProgramResult {
+        self.invoke_signed(&[])
+    }
+
+    pub fn invoke_signed(&self, signers: &[Signer]) -> ProgramResult {
+        let data = liquidity_data(
+            *crate::Deposit::DISCRIMINATOR_V2,
+            self.amount,
+            self.max_x,
+            self.max_y,
+            &self.deadline,
+        );
+
+        let accounts = [
+            self.user,
+            self.mint_lp,
+            self.vault_x,
+            self.vault_y,
+            self.user_x_ata,
+            self.user_y_ata,
+            self.user_lp_ata,
+            self.config,
+            self.token_program,
+        ];
+
+        invoke_pool(
+            &liquidity_metas(&accounts),
+            &accounts,
+            self.remaining_accounts,
+            &data,
+            signers,
+        )
+    }
+}
+
+/// Withdraw from a pool.
+///
+/// ### Accounts:
+///   0. `[WRITE, SIGNER]` User
+///   1. `[WRITE]` LP mint
+///   2. `[WRITE]` Pool vault of x
+///   3. `[WRITE]` Pool vault of y
+///   4. `[WRITE]` User token account of x
+///   5. `[WRITE]` User token account of y
+///   6. `[WRITE]` User LP token account
+///   7. `[WRITE]` Pool config
+///   8. `[]` Token program
+pub struct Withdraw<'a> {
+    pub user: &'a AccountInfo,
+    pub mint_lp: &'a AccountInfo,
+    pub vault_x: &'a AccountInfo,
+    pub vault_y: &'a AccountInfo,
+    pub user_x_ata: &'a AccountInfo,
+    pub user_y_ata: &'a AccountInfo,
+    pub user_lp_ata: &'a AccountInfo,
+    pub config: &'a AccountInfo,
+    pub token_program: &'a AccountInfo,
+    pub remaining_accounts: &'a [&'a AccountInfo],
+    pub amount: u64,
+    pub min_x: u64,
+    pub min_y: u64,
+    pub deadline: Deadline,
+}
+
+impl Withdraw<'_> {
+    #[inline(always)]
+    pub fn invoke(&self) -> ProgramResult {
+        self.invoke_signed(&[])
+    }
+
+    pub fn invoke_signed(&self, signers: &[Signer]) -> ProgramResult {
+        let data = liquidity_data(
+            *crate::Withdraw::DISCRIMINATOR_V2,
+            self.amount,
+            self.min_x,
+            self.min_y,
+            &self.deadline,
+        );
+
+        let accounts = [
+            self.user,
+            self.mint_lp,
+            self.vault_x,
+            self.vault_y,
+            self.user_x_ata,
+            self.user_y_ata,
+            self.user_lp_ata,
+            self.config,
+            self.token_program,
+        ];
+
+        invoke_pool(
+            &liquidity_metas(&accounts),
+            &accounts,
+            self.remaining_accounts,
+            &data,
+            signers,
+        )
+    }
+}
+
+/// Account metas shared by `Deposit` and `Withdraw`.
+#[inline(always)]
+fn liquidity_metas<'a>(accounts: &[&'a AccountInfo; 9]) -> [AccountMeta<'a>; 9] {
+    let [user, mint_lp, vault_x, vault_y, user_x_ata, user_y_ata, user_lp_ata, config, token_program] =
+        accounts;
+
+    [
+        AccountMeta::writable_signer(user.key()),
+        AccountMeta::writable(mint_lp.key()),
+        AccountMeta::writable(vault_x.key()),
+        AccountMeta::writable(vault_y.key()),
+        AccountMeta::writable(user_x_ata.key()),
+        AccountMeta::writable(user_y_ata.key()),
+        AccountMeta::writable(user_lp_ata.key()),
+        AccountMeta::writable(config.key()),
+        AccountMeta::readonly(token_program.key()),
+    ]
+}
+
+/// Instruction data shared by `Deposit` and `Withdraw`.
+#[inline(always)]
+fn liquidity_data(
+    discriminator: u8,
+    amount: u64,
+    x: u64,
+    y: u64,
+    deadline: &Deadline,
+) -> [u8; 1 + size_of::<u64>() * 3 + Deadline::LEN] {
+    let mut data = [0u8; 1 + size_of::<u64>() * 3 + Deadline::LEN];
+    data[0] = discriminator;
+    data[1..9].copy_from_slice(&amount.to_le_bytes());
+    data[9..17].copy_from_slice(&x.to_le_bytes());
+    data[17..25].copy_from_slice(&y.to_le_bytes());
+    data[25..34].copy_from_slice(&deadline.to_bytes());
+    data
+}
+
+/// Invoke the AMM with `accounts` followed by `remaining_accounts`, the remaining ones
+/// keeping the signer and writable flags they were passed with.
+fn invoke_pool(
+    account_metas: &[AccountMeta],
+    accounts: &[&AccountInfo],
+    remaining_accounts: &[&AccountInfo],
+    data: &[u8],
+    signers: &[Signer],
+) -> ProgramResult {
+    let len = accounts.len() + remaining_accounts.len();
+    if len > MAX_ACCOUNTS {
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    let account_metas: [AccountMeta; MAX_ACCOUNTS] = core::array::from_fn(|i| {
+        match (
+            account_metas.get(i),
+            remaining_accounts.get(i.wrapping_sub(accounts.len())),
+        ) {
+            (Some(meta), _) => meta.clone(),
+            (None, Some(account)) => {
+                AccountMeta::new(account.key(), account.is_writable(), account.is_signer())
+            }
+            // Past the end, never read
+            (None, None) => AccountMeta::readonly(accounts[0].key()),
+        }
+    });
+    let account_infos: [&AccountInfo; MAX_ACCOUNTS] = core::array::from_fn(|i| {
+        accounts
+            .get(i)
+            .or(remaining_accounts.get(i.wrapping_sub(accounts.len())))
+            .copied()
+            .unwrap_or(accounts[0])
+    });
+
+    let instruction = Instruction {
+        program_id: &crate::ID,
+        accounts: &account_metas[..len],
+        data,
+    };
+
+    invoke_signed_with_bounds::<MAX_ACCOUNTS>(&instruction, &account_infos[..len], signers)
+}
@@ -0,0 +1,25 @@
+use pinocchio::program_error::ProgramError;
+
+/// Custom error codes for the AMM program.
+#[repr(u32)]
+pub enum AmmError {
+    InvalidOracle,         // 0
+    StaleOracle,           // 1
+    OracleDeviation,       // 2
+    SlotMoveExceeded,      // 3
+    SlippageExceeded,      // 4
+    PoolNotEmpty,          // 5
+    DepositRestricted,     // 6
+    NotAllowlisted,        // 7
+    MathOverflow,          // 8
+    ZeroLiquidity,         // 9
+    ZeroAmount,            // 10
+    InsufficientLiquidity, // 11
+}
+
+// Convert our error enum into a ProgramError::Custom(code).
+impl From<AmmError> for ProgramError {
+    fn from(e: AmmError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
@@ -0,0 +1,32 @@
+use core::mem::size_of;
+use pinocchio::{log::sol_log_data, pubkey::Pubkey};
+
+/// Emitted by `Swap` through `sol_log_data` as `[SwapEvent::DISCRIMINATOR, event bytes]`.
+#[repr(C)]
+pub struct SwapEvent {
+    pub config: Pubkey,
+    pub user: Pubkey,
+    pub is_x: u8,
+    pub amount_in: [u8; 8],
+    pub amount_out: [u8; 8],
+    pub fee_amount: [u8; 8],
+    pub referral_amount: [u8; 8],
+    pub fee_bps: [u8; 2],
+}
+
+impl SwapEvent {
+    pub const LEN: usize =
+        size_of::<Pubkey>() * 2 + size_of::<u8>() + size_of::<u64>() * 4 + size_of::<u16>();
+
+    pub const DISCRIMINATOR: &'static [u8] = b"swap";
+
+    #[inline(always)]
+    pub fn as_bytes(&self) -> &[u8] {
+        unsafe { core::slice::from_raw_parts(self as *const Self as *const u8, Self::LEN) }
+    }
+
+    #[inline(always)]
+    pub fn emit(&self) {
+        sol_log_data(&[Self::DISCRIMINATOR, self.as_bytes()]);
+    }
+}
@@ -0,0 +1,67 @@
+use crate::events::SwapEvent;
+use pinocchio::{
+    account_info::AccountInfo,
+    cpi::invoke_signed,
+    instruction::{AccountMeta, Instruction, Signer},
+    ProgramResult,
+};
+
+/// Instruction `Swap` sends to the hook program of a pool once the trade settled.
+///
+/// The hook authority of the pool, the address derived from `[b"hook", config]`, signs
+/// so the hook can check the call comes from a pool it trusts. The config itself does
+/// not sign, as it owns the pool vaults and the LP mint. The data is
+/// `SwapHook::DISCRIMINATOR` followed by the `SwapEvent` of the trade, all integers
+/// little-endian:
+///
+/// | offset | size | field             | description                               |
+/// |--------|------|-------------------|-------------------------------------------|
+/// | 0      | 8    | `discriminator`   | `SwapHook::DISCRIMINATOR`                 |
+/// | 8      | 32   | `config`          | pool config                               |
+/// | 40     | 32   | `user`            | trader                                    |
+/// | 72     | 1    | `is_x`            | `1` when x was sold for y                 |
+/// | 73     | 8    | `amount_in`       | `u64` tokens sold, referral share included |
+/// | 81     | 8    | `amount_out`      | `u64` tokens bought                       |
+/// | 89     | 8    | `fee_amount`      | `u64` fee charged on the input            |
+/// | 97     | 8    | `referral_amount` | `u64` share of the fee paid to a referrer |
+/// | 105    | 2    | `fee_bps`         | `u16` fee rate applied                    |
+///
+/// ### Accounts:
+///   0. `[SIGNER]` Hook authority of the pool
+///   1. `[]` Pool config
+///   2. `[]` User
+pub struct SwapHook<'a> {
+    pub program: &'a AccountInfo,
+    pub authority: &'a AccountInfo,
+    pub config: &'a AccountInfo,
+    pub user: &'a AccountInfo,
+    pub event: &'a SwapEvent,
+}
+
+impl SwapHook<'_> {
+    pub const DISCRIMINATOR: [u8; 8] = *b"AMMSWAPH";
+
+    pub fn invoke_signed(&self, signers: &[Signer]) -> ProgramResult {
+        let account_metas = [
+            AccountMeta::readonly_signer(self.authority.key()),
+            AccountMeta::readonly(self.config.key()),
+            AccountMeta::readonly(self.user.key()),
+        ];
+
+        let mut data = [0u8; 8 + SwapEvent::LEN];
+        data[..8].copy_from_slice(&Self::DISCRIMINATOR);
+        data[8..].copy_from_slice(self.event.as_bytes());
+
+        let instruction = Instruction {
+            program_id: self.program.key(),
+            accounts: &account_metas,
+            data: &data,
+        };
+
+        invoke_signed(
+            &instruction,
+            &[self.authority, self.config, self.user],
+            signers,
+        )
+    }
+}
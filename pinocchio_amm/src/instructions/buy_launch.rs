@@ -0,0 +1,135 @@
+use crate::helpers::check_vaults;
+use crate::state::{Launch, LaunchState};
+use core::mem::size_of;
+use pinocchio::{
+    account_info::AccountInfo,
+    instruction::{Seed, Signer},
+    program_error::ProgramError,
+    ProgramResult,
+};
+use pinocchio_token::instructions::Transfer;
+
+pub struct LaunchTradeAccounts<'a> {
+    pub user: &'a AccountInfo,
+    pub launch: &'a AccountInfo,
+    pub user_quote_ata: &'a AccountInfo,
+    pub user_token_ata: &'a AccountInfo,
+    pub quote_vault: &'a AccountInfo,
+    pub token_vault: &'a AccountInfo,
+    pub token_program: &'a AccountInfo,
+}
+
+impl<'a> TryFrom<&'a [AccountInfo]> for LaunchTradeAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountInfo]) -> Result<Self, Self::Error> {
+        let [user, launch, user_quote_ata, user_token_ata, quote_vault, token_vault, token_program] =
+            accounts
+        else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        Ok(Self {
+            user,
+            launch,
+            user_quote_ata,
+            user_token_ata,
+            quote_vault,
+            token_vault,
+            token_program,
+        })
+    }
+}
+
+/// Amount and slippage bound of a launch trade, shared by `BuyLaunch` and `SellLaunch`.
+pub struct LaunchTradeInstructionData {
+    pub amount: u64,
+    pub min: u64,
+}
+
+impl<'a> TryFrom<&'a [u8]> for LaunchTradeInstructionData {
+    type Error = ProgramError;
+
+    fn try_from(data: &'a [u8]) -> Result<Self, Self::Error> {
+        if data.len().ne(&(size_of::<u64>() * 2)) {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let amount = u64::from_le_bytes(data[0..8].try_into().unwrap());
+        let min = u64::from_le_bytes(data[8..16].try_into().unwrap());
+
+        if amount == 0 {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        Ok(Self { amount, min })
+    }
+}
+
+/// Buy launched tokens with up to `amount` quote tokens, capped at what is left to raise.
+pub struct BuyLaunch<'a> {
+    pub accounts: LaunchTradeAccounts<'a>,
+    pub instruction_data: LaunchTradeInstructionData,
+}
+
+impl<'a> TryFrom<(&'a [u8], &'a [AccountInfo])> for BuyLaunch<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountInfo])) -> Result<Self, Self::Error> {
+        let accounts = LaunchTradeAccounts::try_from(accounts)?;
+        let instruction_data = LaunchTradeInstructionData::try_from(data)?;
+
+        Ok(Self {
+            accounts,
+            instruction_data,
+        })
+    }
+}
+
+impl<'a> BuyLaunch<'a> {
+    pub const DISCRIMINATOR: &'a u8 = &36;
+
+    pub fn process(&mut self) -> ProgramResult {
+        if !self.accounts.user.is_signer() {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        let mut launch = Launch::load_mut(self.accounts.launch)?;
+
+        if launch.state().ne(&(LaunchState::Trading as u8)) {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        check_vaults(
+            self.accounts.launch,
+            self.accounts.token_program,
+            &[*launch.quote_mint(), *launch.mint()],
+            &[self.accounts.quote_vault, self.accounts.token_vault],
+        )?;
+
+        let result = launch.buy(self.instruction_data.amount, self.instruction_data.min)?;
+
+        let mint = *launch.mint();
+        let bump = launch.bump();
+
+        drop(launch);
+
+        Transfer {
+            from: self.accounts.user_quote_ata,
+            to: self.accounts.quote_vault,
+            authority: self.accounts.user,
+            amount: result.deposit,
+        }
+        .invoke()?;
+
+        let launch_seeds = [Seed::from(b"launch"), Seed::from(&mint), Seed::from(&bump)];
+
+        Transfer {
+            from: self.accounts.token_vault,
+            to: self.accounts.user_token_ata,
+            authority: self.accounts.launch,
+            amount: result.withdraw,
+        }
+        .invoke_signed(&[Signer::from(&launch_seeds)])
+    }
+}
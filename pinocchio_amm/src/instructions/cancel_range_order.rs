@@ -0,0 +1,44 @@
+use crate::helpers::close_account;
+use crate::instructions::RangeOrderAccounts;
+use crate::state::RangeOrder;
+use pinocchio::{account_info::AccountInfo, program_error::ProgramError, ProgramResult};
+
+/// Return the unfilled deposit of a range order and close it, its proceeds must have
+/// been claimed first.
+pub struct CancelRangeOrder<'a> {
+    pub accounts: RangeOrderAccounts<'a>,
+}
+
+impl<'a> TryFrom<(&'a [u8], &'a [AccountInfo])> for CancelRangeOrder<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountInfo])) -> Result<Self, Self::Error> {
+        if !data.is_empty() {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let accounts = RangeOrderAccounts::try_from(accounts)?;
+
+        Ok(Self { accounts })
+    }
+}
+
+impl<'a> CancelRangeOrder<'a> {
+    pub const DISCRIMINATOR: &'a u8 = &41;
+
+    pub fn process(&mut self) -> ProgramResult {
+        self.accounts.check_order()?;
+
+        let order = RangeOrder::load(self.accounts.order)?;
+
+        if order.amount() == 0 || order.filled() != 0 {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        let (is_x, amount) = (order.is_x(), order.amount());
+        drop(order);
+
+        self.accounts.pay_out(is_x, amount)?;
+        close_account(self.accounts.order, self.accounts.owner)
+    }
+}
@@ -0,0 +1,163 @@
+use crate::helpers::{check_vaults, close_account};
+use crate::state::{AmmState, Batch, BatchState, Config, BATCH_ORDER_DEPOSIT};
+use pinocchio::{
+    account_info::AccountInfo,
+    instruction::{Seed, Signer},
+    program_error::ProgramError,
+    sysvars::{clock::Clock, Sysvar},
+    ProgramResult,
+};
+use pinocchio_token::instructions::Transfer;
+
+pub struct ClaimBatchOrderAccounts<'a> {
+    pub user: &'a AccountInfo,
+    pub config: &'a AccountInfo,
+    pub batch: &'a AccountInfo,
+    pub batch_vault: &'a AccountInfo,
+    pub user_ata: &'a AccountInfo,
+    pub token_program: &'a AccountInfo,
+    pub payer: &'a AccountInfo,
+}
+
+impl<'a> TryFrom<&'a [AccountInfo]> for ClaimBatchOrderAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountInfo]) -> Result<Self, Self::Error> {
+        let [user, config, batch, batch_vault, user_ata, token_program, payer] = accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        Ok(Self {
+            user,
+            config,
+            batch,
+            batch_vault,
+            user_ata,
+            token_program,
+            payer,
+        })
+    }
+}
+
+pub struct ClaimBatchOrderInstructionData {
+    pub index: u8,
+}
+
+impl<'a> TryFrom<&'a [u8]> for ClaimBatchOrderInstructionData {
+    type Error = ProgramError;
+
+    fn try_from(data: &'a [u8]) -> Result<Self, Self::Error> {
+        let [index] = data else {
+            return Err(ProgramError::InvalidInstructionData);
+        };
+
+        Ok(Self { index: *index })
+    }
+}
+
+/// Pay out the share of a settled batch owed to the order at `index`, in the token it
+/// bought, or pay back a refunded order in the token it sold.
+///
+/// Orders of an open batch are cancelled and paid back once the pool is paused or
+/// disabled, as `SettleBatch` only settles in an initialized pool, or once
+/// `BATCH_GRACE_SLOTS` passed since `end_slot` without a settlement, as a stale oracle or
+/// the circuit breaker can hold it off.
+///
+/// The order deposit goes back to the user, and the rent of the batch to `payer`, the
+/// user who opened it, with the last claim.
+pub struct ClaimBatchOrder<'a> {
+    pub accounts: ClaimBatchOrderAccounts<'a>,
+    pub instruction_data: ClaimBatchOrderInstructionData,
+}
+
+impl<'a> TryFrom<(&'a [u8], &'a [AccountInfo])> for ClaimBatchOrder<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountInfo])) -> Result<Self, Self::Error> {
+        let accounts = ClaimBatchOrderAccounts::try_from(accounts)?;
+        let instruction_data = ClaimBatchOrderInstructionData::try_from(data)?;
+
+        Ok(Self {
+            accounts,
+            instruction_data,
+        })
+    }
+}
+
+impl<'a> ClaimBatchOrder<'a> {
+    pub const DISCRIMINATOR: &'a u8 = &48;
+
+    pub fn process(&mut self) -> ProgramResult {
+        if !self.accounts.user.is_signer() {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        let mut config = Config::load_mut(self.accounts.config)?;
+        let mut batch = Batch::load_mut(self.accounts.batch)?;
+
+        if batch.config().ne(self.accounts.config.key())
+            || batch.payer().ne(self.accounts.payer.key())
+        {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        let index = self.instruction_data.index as usize;
+        let (is_x, amount) = match batch.is_stranded(
+            config.state().eq(&(AmmState::Initialized as u8)),
+            Clock::get()?.slot,
+        ) {
+            true => {
+                let cancelled = batch.cancel(index, self.accounts.user.key())?;
+
+                // The last cancellation closes the batch
+                if batch.state() == BatchState::Settled as u8 {
+                    config.close_batch()?;
+                }
+                cancelled
+            }
+            false => batch.claim(index, self.accounts.user.key())?,
+        };
+
+        let mint = match is_x {
+            true => *config.mint_x(),
+            false => *config.mint_y(),
+        };
+
+        check_vaults(
+            self.accounts.batch,
+            self.accounts.token_program,
+            &[mint],
+            core::slice::from_ref(self.accounts.batch_vault),
+        )?;
+
+        let epoch_binding = batch.epoch().to_le_bytes();
+        let batch_bump = batch.bump();
+        let is_claimed = batch.is_claimed();
+
+        drop(batch);
+        drop(config);
+
+        let seeds = [
+            Seed::from(b"batch"),
+            Seed::from(self.accounts.config.key()),
+            Seed::from(&epoch_binding),
+            Seed::from(&batch_bump),
+        ];
+
+        Transfer {
+            from: self.accounts.batch_vault,
+            to: self.accounts.user_ata,
+            authority: self.accounts.batch,
+            amount,
+        }
+        .invoke_signed(&[Signer::from(&seeds)])?;
+
+        *self.accounts.batch.try_borrow_mut_lamports()? -= BATCH_ORDER_DEPOSIT;
+        *self.accounts.user.try_borrow_mut_lamports()? += BATCH_ORDER_DEPOSIT;
+
+        match is_claimed {
+            true => close_account(self.accounts.batch, self.accounts.payer),
+            false => Ok(()),
+        }
+    }
+}
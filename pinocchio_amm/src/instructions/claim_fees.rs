@@ -0,0 +1,127 @@
+use crate::helpers::check_vaults;
+use crate::state::{Config, Position};
+use pinocchio::{
+    account_info::AccountInfo,
+    instruction::{Seed, Signer},
+    program_error::ProgramError,
+    ProgramResult,
+};
+use pinocchio_token::instructions::Transfer;
+
+pub struct ClaimFeesAccounts<'a> {
+    pub user: &'a AccountInfo,
+    pub config: &'a AccountInfo,
+    pub position: &'a AccountInfo,
+    pub user_x_ata: &'a AccountInfo,
+    pub user_y_ata: &'a AccountInfo,
+    pub vault_x: &'a AccountInfo,
+    pub vault_y: &'a AccountInfo,
+    pub token_program: &'a AccountInfo,
+}
+
+impl<'a> TryFrom<&'a [AccountInfo]> for ClaimFeesAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountInfo]) -> Result<Self, Self::Error> {
+        let [user, config, position, user_x_ata, user_y_ata, vault_x, vault_y, token_program] =
+            accounts
+        else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        Ok(Self {
+            user,
+            config,
+            position,
+            user_x_ata,
+            user_y_ata,
+            vault_x,
+            vault_y,
+            token_program,
+        })
+    }
+}
+
+/// Pay out the swap fees a position earned, without touching the escrowed LP.
+pub struct ClaimFees<'a> {
+    pub accounts: ClaimFeesAccounts<'a>,
+}
+
+impl<'a> TryFrom<(&'a [u8], &'a [AccountInfo])> for ClaimFees<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountInfo])) -> Result<Self, Self::Error> {
+        if !data.is_empty() {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let accounts = ClaimFeesAccounts::try_from(accounts)?;
+
+        Ok(Self { accounts })
+    }
+}
+
+impl<'a> ClaimFees<'a> {
+    pub const DISCRIMINATOR: &'a u8 = &34;
+
+    pub fn process(&mut self) -> ProgramResult {
+        if !self.accounts.user.is_signer() {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        let mut config = Config::load_mut(self.accounts.config)?;
+
+        check_vaults(
+            self.accounts.config,
+            self.accounts.token_program,
+            &[*config.mint_x(), *config.mint_y()],
+            &[self.accounts.vault_x, self.accounts.vault_y],
+        )?;
+
+        let mut position = Position::load_mut(self.accounts.position)?;
+        if position.config().ne(self.accounts.config.key())
+            || position.owner().ne(self.accounts.user.key())
+        {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        let amount = position.amount();
+        position.settle(config.fee_growth_x(), config.fee_growth_y(), amount)?;
+        let (fees_x, fees_y) = position.take_fees();
+        config.take_fees(fees_x, fees_y)?;
+
+        let seed_binding = config.seed().to_le_bytes();
+        let config_bump = config.config_bump();
+        let mint_x = *config.mint_x();
+        let mint_y = *config.mint_y();
+
+        drop(position);
+        drop(config);
+
+        let seeds = [
+            Seed::from(b"config"),
+            Seed::from(&seed_binding),
+            Seed::from(&mint_x),
+            Seed::from(&mint_y),
+            Seed::from(&config_bump),
+        ];
+        let signer_seeds = [Signer::from(&seeds)];
+
+        for (vault, user_ata, amount) in [
+            (self.accounts.vault_x, self.accounts.user_x_ata, fees_x),
+            (self.accounts.vault_y, self.accounts.user_y_ata, fees_y),
+        ] {
+            if amount != 0 {
+                Transfer {
+                    from: vault,
+                    to: user_ata,
+                    authority: self.accounts.config,
+                    amount,
+                }
+                .invoke_signed(&signer_seeds)?;
+            }
+        }
+
+        Ok(())
+    }
+}
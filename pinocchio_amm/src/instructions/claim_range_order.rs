@@ -0,0 +1,143 @@
+use crate::helpers::{check_vaults, close_account};
+use crate::state::{Config, RangeOrder};
+use pinocchio::{
+    account_info::AccountInfo,
+    instruction::{Seed, Signer},
+    program_error::ProgramError,
+    ProgramResult,
+};
+use pinocchio_token::instructions::Transfer;
+
+/// Accounts shared by `ClaimRangeOrder` and `CancelRangeOrder`, the token accounts being
+/// of the token paid out.
+pub struct RangeOrderAccounts<'a> {
+    pub owner: &'a AccountInfo,
+    pub config: &'a AccountInfo,
+    pub order: &'a AccountInfo,
+    pub owner_ata: &'a AccountInfo,
+    pub vault: &'a AccountInfo,
+    pub token_program: &'a AccountInfo,
+}
+
+impl<'a> TryFrom<&'a [AccountInfo]> for RangeOrderAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountInfo]) -> Result<Self, Self::Error> {
+        let [owner, config, order, owner_ata, vault, token_program] = accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        Ok(Self {
+            owner,
+            config,
+            order,
+            owner_ata,
+            vault,
+            token_program,
+        })
+    }
+}
+
+impl RangeOrderAccounts<'_> {
+    /// Pay out `amount` of x or y of the order.
+    pub(crate) fn pay_out(&self, is_x: bool, amount: u64) -> ProgramResult {
+        let mut config = Config::load_mut(self.config)?;
+
+        let mint = match is_x {
+            true => *config.mint_x(),
+            false => *config.mint_y(),
+        };
+
+        check_vaults(
+            self.config,
+            self.token_program,
+            &[mint],
+            core::slice::from_ref(self.vault),
+        )?;
+
+        config.take_orders(is_x, amount)?;
+
+        let seed_binding = config.seed().to_le_bytes();
+        let config_bump = config.config_bump();
+        let mint_x = *config.mint_x();
+        let mint_y = *config.mint_y();
+
+        drop(config);
+
+        let seeds = [
+            Seed::from(b"config"),
+            Seed::from(&seed_binding),
+            Seed::from(&mint_x),
+            Seed::from(&mint_y),
+            Seed::from(&config_bump),
+        ];
+
+        Transfer {
+            from: self.vault,
+            to: self.owner_ata,
+            authority: self.config,
+            amount,
+        }
+        .invoke_signed(&[Signer::from(&seeds)])
+    }
+
+    /// Check that the owner signs and that the order is theirs in the pool.
+    pub(crate) fn check_order(&self) -> Result<(), ProgramError> {
+        if !self.owner.is_signer() {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        let order = RangeOrder::load(self.order)?;
+        if order.config().ne(self.config.key()) || order.owner().ne(self.owner.key()) {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        Ok(())
+    }
+}
+
+/// Pay out the proceeds a range order has collected so far, closing it once it is
+/// wholly filled.
+pub struct ClaimRangeOrder<'a> {
+    pub accounts: RangeOrderAccounts<'a>,
+}
+
+impl<'a> TryFrom<(&'a [u8], &'a [AccountInfo])> for ClaimRangeOrder<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountInfo])) -> Result<Self, Self::Error> {
+        if !data.is_empty() {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let accounts = RangeOrderAccounts::try_from(accounts)?;
+
+        Ok(Self { accounts })
+    }
+}
+
+impl<'a> ClaimRangeOrder<'a> {
+    pub const DISCRIMINATOR: &'a u8 = &40;
+
+    pub fn process(&mut self) -> ProgramResult {
+        self.accounts.check_order()?;
+
+        let mut order = RangeOrder::load_mut(self.accounts.order)?;
+
+        if order.filled() == 0 {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        let (is_x, filled, unfilled) = (!order.is_x(), order.filled(), order.amount());
+        order.clear_filled();
+        drop(order);
+
+        self.accounts.pay_out(is_x, filled)?;
+
+        // A partly filled order keeps resting for the rest
+        match unfilled {
+            0 => close_account(self.accounts.order, self.accounts.owner),
+            _ => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,113 @@
+use crate::helpers::check_vaults;
+use crate::state::{Farm, UserStake};
+use pinocchio::{
+    account_info::AccountInfo,
+    instruction::{Seed, Signer},
+    program_error::ProgramError,
+    sysvars::{clock::Clock, Sysvar},
+    ProgramResult,
+};
+use pinocchio_token::instructions::Transfer;
+
+pub struct ClaimRewardsAccounts<'a> {
+    pub user: &'a AccountInfo,
+    pub farm: &'a AccountInfo,
+    pub user_stake: &'a AccountInfo,
+    pub user_reward_ata: &'a AccountInfo,
+    pub reward_vault: &'a AccountInfo,
+    pub token_program: &'a AccountInfo,
+}
+
+impl<'a> TryFrom<&'a [AccountInfo]> for ClaimRewardsAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountInfo]) -> Result<Self, Self::Error> {
+        let [user, farm, user_stake, user_reward_ata, reward_vault, token_program] = accounts
+        else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        Ok(Self {
+            user,
+            farm,
+            user_stake,
+            user_reward_ata,
+            reward_vault,
+            token_program,
+        })
+    }
+}
+
+/// Claim the pending farm rewards while keeping the LP staked.
+pub struct ClaimRewards<'a> {
+    pub accounts: ClaimRewardsAccounts<'a>,
+}
+
+impl<'a> TryFrom<(&'a [u8], &'a [AccountInfo])> for ClaimRewards<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountInfo])) -> Result<Self, Self::Error> {
+        if !data.is_empty() {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let accounts = ClaimRewardsAccounts::try_from(accounts)?;
+
+        Ok(Self { accounts })
+    }
+}
+
+impl<'a> ClaimRewards<'a> {
+    pub const DISCRIMINATOR: &'a u8 = &17;
+
+    pub fn process(&mut self) -> ProgramResult {
+        if !self.accounts.user.is_signer() {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        let mut farm = Farm::load_mut(self.accounts.farm)?;
+        farm.update(Clock::get()?.unix_timestamp)?;
+
+        check_vaults(
+            self.accounts.farm,
+            self.accounts.token_program,
+            &[*farm.reward_mint()],
+            core::slice::from_ref(self.accounts.reward_vault),
+        )?;
+
+        let mut user_stake = UserStake::load_mut(self.accounts.user_stake)?;
+        if user_stake.farm().ne(self.accounts.farm.key())
+            || user_stake.owner().ne(self.accounts.user.key())
+        {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        let rewards = farm.unstake(&mut user_stake, 0)?;
+
+        let config = *farm.config();
+        let seed_binding = farm.seed().to_le_bytes();
+        let bump = farm.bump();
+
+        drop(user_stake);
+        drop(farm);
+
+        if rewards == 0 {
+            return Ok(());
+        }
+
+        let farm_seeds = [
+            Seed::from(b"farm"),
+            Seed::from(&config),
+            Seed::from(&seed_binding),
+            Seed::from(&bump),
+        ];
+
+        Transfer {
+            from: self.accounts.reward_vault,
+            to: self.accounts.user_reward_ata,
+            authority: self.accounts.farm,
+            amount: rewards,
+        }
+        .invoke_signed(&[Signer::from(&farm_seeds)])
+    }
+}
@@ -0,0 +1,141 @@
+use crate::math::HASH_LEN;
+use crate::state::{AmmState, Config, SwapCommit, MIN_REVEAL_DELAY};
+use core::mem::size_of;
+use pinocchio::{
+    account_info::AccountInfo,
+    instruction::{Seed, Signer},
+    program_error::ProgramError,
+    sysvars::{clock::Clock, rent::Rent, Sysvar},
+    ProgramResult,
+};
+use pinocchio_system::instructions::CreateAccount;
+
+pub struct CommitSwapAccounts<'a> {
+    pub user: &'a AccountInfo,
+    pub config: &'a AccountInfo,
+    pub commit: &'a AccountInfo,
+}
+
+impl<'a> TryFrom<&'a [AccountInfo]> for CommitSwapAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountInfo]) -> Result<Self, Self::Error> {
+        let [user, config, commit, _system_program] = accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        Ok(Self {
+            user,
+            config,
+            commit,
+        })
+    }
+}
+
+pub struct CommitSwapInstructionData {
+    pub hash: [u8; HASH_LEN],
+    pub reveal_delay: u64,
+    pub commit_bump: [u8; 1],
+}
+
+impl<'a> TryFrom<&'a [u8]> for CommitSwapInstructionData {
+    type Error = ProgramError;
+
+    fn try_from(data: &'a [u8]) -> Result<Self, Self::Error> {
+        if data
+            .len()
+            .ne(&(HASH_LEN + size_of::<u64>() + size_of::<u8>()))
+        {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let reveal_delay = u64::from_le_bytes(data[HASH_LEN..HASH_LEN + 8].try_into().unwrap());
+
+        if reveal_delay < MIN_REVEAL_DELAY {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        Ok(Self {
+            hash: data[0..HASH_LEN].try_into().unwrap(),
+            reveal_delay,
+            commit_bump: [data[HASH_LEN + 8]],
+        })
+    }
+}
+
+/// Commit to a swap without revealing it, replacing the previous commit of the user in
+/// the pool if there is one.
+///
+/// The user picks how many slots must pass before the reveal, no fewer than
+/// `MIN_REVEAL_DELAY`.
+pub struct CommitSwap<'a> {
+    pub accounts: CommitSwapAccounts<'a>,
+    pub instruction_data: CommitSwapInstructionData,
+}
+
+impl<'a> TryFrom<(&'a [u8], &'a [AccountInfo])> for CommitSwap<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountInfo])) -> Result<Self, Self::Error> {
+        let accounts = CommitSwapAccounts::try_from(accounts)?;
+        let instruction_data = CommitSwapInstructionData::try_from(data)?;
+
+        Ok(Self {
+            accounts,
+            instruction_data,
+        })
+    }
+}
+
+impl<'a> CommitSwap<'a> {
+    pub const DISCRIMINATOR: &'a u8 = &43;
+
+    pub fn process(&mut self) -> ProgramResult {
+        if !self.accounts.user.is_signer() {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        if Config::load(self.accounts.config)?
+            .state()
+            .ne(&(AmmState::Initialized as u8))
+        {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        if self.accounts.commit.data_is_empty() {
+            let commit_seeds = [
+                Seed::from(b"commit"),
+                Seed::from(self.accounts.config.key()),
+                Seed::from(self.accounts.user.key()),
+                Seed::from(&self.instruction_data.commit_bump),
+            ];
+
+            CreateAccount {
+                from: self.accounts.user,
+                to: self.accounts.commit,
+                lamports: Rent::get()?.minimum_balance(SwapCommit::LEN),
+                space: SwapCommit::LEN as u64,
+                owner: &crate::ID,
+            }
+            .invoke_signed(&[Signer::from(&commit_seeds)])?;
+        } else {
+            let commit = SwapCommit::load(self.accounts.commit)?;
+            if commit.config().ne(self.accounts.config.key())
+                || commit.user().ne(self.accounts.user.key())
+            {
+                return Err(ProgramError::InvalidAccountData);
+            }
+        }
+
+        SwapCommit::load_mut(self.accounts.commit)?.set_inner(
+            *self.accounts.config.key(),
+            *self.accounts.user.key(),
+            self.instruction_data.hash,
+            Clock::get()?.slot,
+            self.instruction_data.reveal_delay,
+            self.instruction_data.commit_bump,
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,140 @@
+use crate::helpers::check_vaults;
+use crate::state::{Config, Position};
+use core::mem::size_of;
+use pinocchio::{
+    account_info::AccountInfo,
+    instruction::{Seed, Signer},
+    program_error::ProgramError,
+    pubkey::find_program_address,
+    ProgramResult,
+};
+use pinocchio_token::instructions::Transfer;
+
+pub struct DecreasePositionAccounts<'a> {
+    pub user: &'a AccountInfo,
+    pub config: &'a AccountInfo,
+    pub position: &'a AccountInfo,
+    pub user_lp_ata: &'a AccountInfo,
+    pub lp_vault: &'a AccountInfo,
+    pub token_program: &'a AccountInfo,
+}
+
+impl<'a> TryFrom<&'a [AccountInfo]> for DecreasePositionAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountInfo]) -> Result<Self, Self::Error> {
+        let [user, config, position, user_lp_ata, lp_vault, token_program] = accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        Ok(Self {
+            user,
+            config,
+            position,
+            user_lp_ata,
+            lp_vault,
+            token_program,
+        })
+    }
+}
+
+pub struct DecreasePositionInstructionData {
+    pub amount: u64,
+}
+
+impl<'a> TryFrom<&'a [u8]> for DecreasePositionInstructionData {
+    type Error = ProgramError;
+
+    fn try_from(data: &'a [u8]) -> Result<Self, Self::Error> {
+        if data.len().ne(&size_of::<u64>()) {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let amount = u64::from_le_bytes(data[0..8].try_into().unwrap());
+
+        if amount == 0 {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        Ok(Self { amount })
+    }
+}
+
+/// Take LP tokens back out of a fee position, the fees it earned stay claimable.
+pub struct DecreasePosition<'a> {
+    pub accounts: DecreasePositionAccounts<'a>,
+    pub instruction_data: DecreasePositionInstructionData,
+}
+
+impl<'a> TryFrom<(&'a [u8], &'a [AccountInfo])> for DecreasePosition<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountInfo])) -> Result<Self, Self::Error> {
+        let accounts = DecreasePositionAccounts::try_from(accounts)?;
+        let instruction_data = DecreasePositionInstructionData::try_from(data)?;
+
+        Ok(Self {
+            accounts,
+            instruction_data,
+        })
+    }
+}
+
+impl<'a> DecreasePosition<'a> {
+    pub const DISCRIMINATOR: &'a u8 = &33;
+
+    pub fn process(&mut self) -> ProgramResult {
+        if !self.accounts.user.is_signer() {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        let mut config = Config::load_mut(self.accounts.config)?;
+
+        let (mint_lp, _) =
+            find_program_address(&[b"mint_lp", self.accounts.config.key()], &crate::ID);
+
+        check_vaults(
+            self.accounts.position,
+            self.accounts.token_program,
+            &[mint_lp],
+            core::slice::from_ref(self.accounts.lp_vault),
+        )?;
+
+        let mut position = Position::load_mut(self.accounts.position)?;
+        if position.config().ne(self.accounts.config.key())
+            || position.owner().ne(self.accounts.user.key())
+        {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        let amount = position
+            .amount()
+            .checked_sub(self.instruction_data.amount)
+            .ok_or(ProgramError::InsufficientFunds)?;
+
+        let positioned_lp = config.positioned_lp() - self.instruction_data.amount;
+
+        position.settle(config.fee_growth_x(), config.fee_growth_y(), amount)?;
+        config.set_positioned_lp(positioned_lp);
+
+        let bump = position.bump();
+
+        drop(position);
+        drop(config);
+
+        let position_seeds = [
+            Seed::from(b"position"),
+            Seed::from(self.accounts.config.key()),
+            Seed::from(self.accounts.user.key()),
+            Seed::from(&bump),
+        ];
+
+        Transfer {
+            from: self.accounts.lp_vault,
+            to: self.accounts.user_lp_ata,
+            authority: self.accounts.position,
+            amount: self.instruction_data.amount,
+        }
+        .invoke_signed(&[Signer::from(&position_seeds)])
+    }
+}
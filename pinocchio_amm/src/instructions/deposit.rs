@@ -1,6 +1,9 @@
-use crate::state::Config;
+use crate::errors::AmmError;
+use crate::helpers::{check_allowlist, spot_price, Deadline};
+use crate::math::{constant_product, HASH_LEN};
+use crate::native::{WrappedSol, NATIVE_MINT};
+use crate::state::{Config, CurveType};
 use crate::AmmState;
-use constant_product_curve::ConstantProduct;
 use core::mem::size_of;
 use pinocchio::instruction::{Seed, Signer};
 use pinocchio::pubkey::find_program_address;
@@ -19,13 +22,14 @@ pub struct DepositAccounts<'a> {
     pub user_lp_ata: &'a AccountInfo,
     pub config: &'a AccountInfo,
     pub token_program: &'a AccountInfo,
+    pub remaining_accounts: &'a [AccountInfo],
 }
 
 impl<'a> TryFrom<&'a [AccountInfo]> for DepositAccounts<'a> {
     type Error = ProgramError;
 
     fn try_from(accounts: &'a [AccountInfo]) -> Result<Self, Self::Error> {
-        let [user, mint_lp, vault_x, vault_y, user_x_ata, user_y_ata, user_lp_ata, config, token_program] =
+        let [user, mint_lp, vault_x, vault_y, user_x_ata, user_y_ata, user_lp_ata, config, token_program, remaining_accounts @ ..] =
             accounts
         else {
             return Err(ProgramError::NotEnoughAccountKeys);
@@ -41,31 +45,65 @@ impl<'a> TryFrom<&'a [AccountInfo]> for DepositAccounts<'a> {
             user_lp_ata,
             config,
             token_program,
+            remaining_accounts,
         })
     }
 }
 
-pub struct DepositInstructionData {
+pub struct DepositInstructionData<'a> {
     pub amount: u64,
     pub max_x: u64,
     pub max_y: u64,
-    pub expiration: i64,
+    pub deadline: Deadline,
+    pub proof: &'a [u8],
 }
 
-impl<'a> TryFrom<&'a [u8]> for DepositInstructionData {
+impl<'a> TryFrom<&'a [u8]> for DepositInstructionData<'a> {
     type Error = ProgramError;
 
     fn try_from(data: &'a [u8]) -> Result<Self, Self::Error> {
-        if data.len().ne(&size_of::<DepositInstructionData>()) {
+        let instruction_data = Self::parse(data, size_of::<i64>(), |deadline| {
+            Ok(Deadline::UnixTimestamp(i64::from_le_bytes(
+                deadline.try_into().unwrap(),
+            )))
+        })?;
+
+        // Allowlist proofs only fit in version 2 data
+        if !instruction_data.proof.is_empty() {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        Ok(instruction_data)
+    }
+}
+
+impl<'a> DepositInstructionData<'a> {
+    /// Parse version 2 data, whose deadline may be a slot and which may end with an
+    /// allowlist proof.
+    pub fn try_from_v2(data: &'a [u8]) -> Result<Self, ProgramError> {
+        Self::parse(data, Deadline::LEN, |deadline| Deadline::try_from(deadline))
+    }
+
+    fn parse(
+        data: &'a [u8],
+        deadline_len: usize,
+        read_deadline: impl Fn(&[u8]) -> Result<Deadline, ProgramError>,
+    ) -> Result<Self, ProgramError> {
+        const HEADER_LEN: usize = size_of::<u64>() * 3;
+
+        let data_len = HEADER_LEN + deadline_len;
+
+        // Proofs are made of whole hashes
+        if data.len() < data_len || !(data.len() - data_len).is_multiple_of(HASH_LEN) {
             return Err(ProgramError::InvalidInstructionData);
         }
 
         let amount = u64::from_le_bytes(data[0..8].try_into().unwrap());
         let max_x = u64::from_le_bytes(data[8..16].try_into().unwrap());
         let max_y = u64::from_le_bytes(data[16..24].try_into().unwrap());
-        let expiration = i64::from_le_bytes(data[24..32].try_into().unwrap());
+        let deadline = read_deadline(&data[HEADER_LEN..data_len])?;
 
-        if amount == 0 || max_x == 0 || max_y == 0 || expiration < Clock::get()?.unix_timestamp {
+        if amount == 0 || max_x == 0 || max_y == 0 {
             return Err(ProgramError::InvalidInstructionData);
         }
 
@@ -73,14 +111,15 @@ impl<'a> TryFrom<&'a [u8]> for DepositInstructionData {
             amount,
             max_x,
             max_y,
-            expiration,
+            deadline,
+            proof: &data[data_len..],
         })
     }
 }
 
 pub struct Deposit<'a> {
     pub accounts: DepositAccounts<'a>,
-    pub instruction_data: DepositInstructionData,
+    pub instruction_data: DepositInstructionData<'a>,
 }
 
 impl<'a> TryFrom<(&'a [u8], &'a [AccountInfo])> for Deposit<'a> {
@@ -101,13 +140,53 @@ impl<'a> TryFrom<(&'a [u8], &'a [AccountInfo])> for Deposit<'a> {
 impl<'a> Deposit<'a> {
     pub const DISCRIMINATOR: &'a u8 = &1;
 
+    pub const DISCRIMINATOR_V2: &'a u8 = &20;
+
+    /// Build the instruction from version 2 data.
+    pub fn try_from_v2(
+        (data, accounts): (&'a [u8], &'a [AccountInfo]),
+    ) -> Result<Self, ProgramError> {
+        let accounts = DepositAccounts::try_from(accounts)?;
+        let instruction_data = DepositInstructionData::try_from_v2(data)?;
+
+        Ok(Self {
+            accounts,
+            instruction_data,
+        })
+    }
+
     pub fn process(&mut self) -> ProgramResult {
+        self.process_signed(&[])
+    }
+
+    /// Deposit from token accounts owned by a program address, `signers` signing for
+    /// `user`.
+    pub fn process_signed(&mut self, signers: &[Signer]) -> ProgramResult {
+        self.instruction_data.deadline.check()?;
+
         let config = Config::load(self.accounts.config)?;
 
         if config.state().ne(&(AmmState::Initialized as u8)) {
             return Err(ProgramError::InvalidAccountData);
         }
 
+        // Only the authority provides liquidity until a bootstrapping sale ends
+        if config.curve().eq(&(CurveType::Weighted as u8))
+            && Clock::get()?.unix_timestamp < config.weights_end_time()
+            && config.has_authority().ne(&Some(*self.accounts.user.key()))
+        {
+            return Err(AmmError::DepositRestricted.into());
+        }
+
+        let mut remaining_accounts = self.accounts.remaining_accounts.iter();
+
+        check_allowlist(
+            &config,
+            self.accounts.user,
+            &mut remaining_accounts,
+            self.instruction_data.proof,
+        )?;
+
         // Check if vault_x is valid
         let (vault_x, _) = find_program_address(
             &[
@@ -140,43 +219,68 @@ impl<'a> Deposit<'a> {
         let vault_x = unsafe { TokenAccount::from_account_info_unchecked(self.accounts.vault_x)? };
         let vault_y = unsafe { TokenAccount::from_account_info_unchecked(self.accounts.vault_y)? };
 
-        let (x, y) = match mint_lp.supply() == 0 && vault_x.amount() == 0 && vault_y.amount() == 0 {
+        let (reserve_x, reserve_y) = config.reserves(vault_x.amount(), vault_y.amount());
+
+        let (x, y) = match mint_lp.supply() == 0 && reserve_x == 0 && reserve_y == 0 {
             true => (self.instruction_data.max_x, self.instruction_data.max_y),
-            false => {
-                let amounts = ConstantProduct::xy_deposit_amounts_from_l(
-                    vault_x.amount(),
-                    vault_y.amount(),
-                    mint_lp.supply(),
-                    self.instruction_data.amount,
-                    6,
-                )
-                .map_err(|_| ProgramError::InvalidArgument)?;
-
-                (amounts.x, amounts.y)
-            }
+            false => constant_product::deposit_amounts(
+                reserve_x,
+                reserve_y,
+                mint_lp.supply(),
+                self.instruction_data.amount,
+            )?,
         };
 
         // Check for slippage
-        if !(x <= self.instruction_data.max_x && y <= self.instruction_data.max_y) {
-            return Err(ProgramError::InvalidArgument);
+        if x > self.instruction_data.max_x || y > self.instruction_data.max_y {
+            return Err(AmmError::SlippageExceeded.into());
         }
 
+        // Users depositing native SOL pass their wallet in place of the wSOL token account
+        let wrap_x = self.accounts.user_x_ata.key().eq(self.accounts.user.key());
+        let wrap_y = self.accounts.user_y_ata.key().eq(self.accounts.user.key());
+
+        if (wrap_x && config.mint_x().ne(&NATIVE_MINT))
+            || (wrap_y && config.mint_y().ne(&NATIVE_MINT))
+        {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        let wsol = match wrap_x || wrap_y {
+            true => {
+                let wsol = WrappedSol::next(self.accounts.user, &mut remaining_accounts)?;
+                wsol.open(if wrap_x { x } else { y })?;
+                Some(wsol)
+            }
+            false => None,
+        };
+
         Transfer {
-            from: self.accounts.user_x_ata,
+            from: match &wsol {
+                Some(wsol) if wrap_x => wsol.account,
+                _ => self.accounts.user_x_ata,
+            },
             to: self.accounts.vault_x,
             authority: self.accounts.user,
             amount: x,
         }
-        .invoke()?;
+        .invoke_signed(signers)?;
 
         Transfer {
-            from: self.accounts.user_y_ata,
+            from: match &wsol {
+                Some(wsol) if wrap_y => wsol.account,
+                _ => self.accounts.user_y_ata,
+            },
             to: self.accounts.vault_y,
             authority: self.accounts.user,
             amount: y,
         }
-        .invoke()?;
-    
+        .invoke_signed(signers)?;
+
+        if let Some(wsol) = wsol {
+            wsol.close()?;
+        }
+
         let seeds_binding = config.seed().to_le_bytes();
         let config_bump = config.config_bump();
         let seeds = [
@@ -195,6 +299,20 @@ impl<'a> Deposit<'a> {
         }
         .invoke_signed(&[Signer::from(&seeds)])?;
 
+        drop(config);
+
+        // Keep the supply the fee growth is spread over in step with the mint
+        let mut config = Config::load_mut(self.accounts.config)?;
+        config.set_lp_supply(mint_lp.supply());
+
+        let clock = Clock::get()?;
+        let (weight_x, weight_y) = config.weights(clock.unix_timestamp);
+        let price = reserve_x
+            .checked_add(x)
+            .zip(reserve_y.checked_add(y))
+            .and_then(|(x, y)| spot_price(x, weight_x, y, weight_y).ok());
+        config.record_trade(clock.slot, clock.unix_timestamp, price);
+
         Ok(())
     }
 }
@@ -0,0 +1,138 @@
+use crate::helpers::{check_vaults, spot_price};
+use crate::state::Config;
+use core::mem::size_of;
+use pinocchio::{
+    account_info::AccountInfo,
+    cpi::set_return_data,
+    program_error::ProgramError,
+    sysvars::{clock::Clock, Sysvar},
+    ProgramResult,
+};
+use pinocchio_token::state::TokenAccount;
+
+pub struct GetPoolStateAccounts<'a> {
+    pub config: &'a AccountInfo,
+    pub vault_x: &'a AccountInfo,
+    pub vault_y: &'a AccountInfo,
+    pub token_program: &'a AccountInfo,
+}
+
+impl<'a> TryFrom<&'a [AccountInfo]> for GetPoolStateAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountInfo]) -> Result<Self, Self::Error> {
+        let [config, vault_x, vault_y, token_program] = accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        Ok(Self {
+            config,
+            vault_x,
+            vault_y,
+            token_program,
+        })
+    }
+}
+
+/// Set as return data by `GetPoolState`, little endian like the account state.
+///
+/// `price` is the spot price of x in y scaled by `PRICE_SCALE`, `0` for an empty pool.
+#[repr(C)]
+pub struct PoolState {
+    pub state: u8,
+    pub reserve_x: [u8; 8],
+    pub reserve_y: [u8; 8],
+    pub lp_supply: [u8; 8],
+    pub fee_bps: [u8; 2],
+    pub price: [u8; 16],
+    pub volume_x: [u8; 16],
+    pub volume_y: [u8; 16],
+    pub cumulative_fees_x: [u8; 16],
+    pub cumulative_fees_y: [u8; 16],
+    pub trade_count: [u8; 8],
+    pub last_trade_slot: [u8; 8],
+    pub day_start: [u8; 8],
+    pub day_high_price: [u8; 16],
+    pub day_low_price: [u8; 16],
+}
+
+impl PoolState {
+    pub const LEN: usize = size_of::<u8>()
+        + size_of::<u64>() * 3
+        + size_of::<u16>()
+        + size_of::<u128>() * 5
+        + size_of::<u64>() * 2
+        + size_of::<i64>()
+        + size_of::<u128>() * 2;
+
+    #[inline(always)]
+    pub fn as_bytes(&self) -> &[u8] {
+        unsafe { core::slice::from_raw_parts(self as *const Self as *const u8, Self::LEN) }
+    }
+}
+
+/// Return the reserves, price and trading statistics of a pool, for clients and programs
+/// that read them through simulation or CPI.
+pub struct GetPoolState<'a> {
+    pub accounts: GetPoolStateAccounts<'a>,
+}
+
+impl<'a> TryFrom<(&'a [u8], &'a [AccountInfo])> for GetPoolState<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountInfo])) -> Result<Self, Self::Error> {
+        if !data.is_empty() {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let accounts = GetPoolStateAccounts::try_from(accounts)?;
+
+        Ok(Self { accounts })
+    }
+}
+
+impl<'a> GetPoolState<'a> {
+    pub const DISCRIMINATOR: &'a u8 = &42;
+
+    pub fn process(&mut self) -> ProgramResult {
+        let config = Config::load(self.accounts.config)?;
+
+        check_vaults(
+            self.accounts.config,
+            self.accounts.token_program,
+            &[*config.mint_x(), *config.mint_y()],
+            &[self.accounts.vault_x, self.accounts.vault_y],
+        )?;
+
+        let vault_x = unsafe { TokenAccount::from_account_info_unchecked(self.accounts.vault_x)? };
+        let vault_y = unsafe { TokenAccount::from_account_info_unchecked(self.accounts.vault_y)? };
+        let (reserve_x, reserve_y) = config.reserves(vault_x.amount(), vault_y.amount());
+
+        let now = Clock::get()?.unix_timestamp;
+        let (weight_x, weight_y) = config.weights(now);
+        let price = spot_price(reserve_x, weight_x, reserve_y, weight_y).unwrap_or(0);
+
+        let pool_state = PoolState {
+            state: config.state(),
+            reserve_x: reserve_x.to_le_bytes(),
+            reserve_y: reserve_y.to_le_bytes(),
+            lp_supply: config.lp_supply().to_le_bytes(),
+            // The fee the next swap pays, with the volatility decayed to now
+            fee_bps: config.effective_fee_at(now).to_le_bytes(),
+            price: price.to_le_bytes(),
+            volume_x: config.volume_x().to_le_bytes(),
+            volume_y: config.volume_y().to_le_bytes(),
+            cumulative_fees_x: config.cumulative_fees_x().to_le_bytes(),
+            cumulative_fees_y: config.cumulative_fees_y().to_le_bytes(),
+            trade_count: config.trade_count().to_le_bytes(),
+            last_trade_slot: config.last_trade_slot().to_le_bytes(),
+            day_start: config.day_start().to_le_bytes(),
+            day_high_price: config.day_high_price().to_le_bytes(),
+            day_low_price: config.day_low_price().to_le_bytes(),
+        };
+
+        set_return_data(pool_state.as_bytes());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,274 @@
+use crate::helpers::{check_vaults, Deadline};
+use crate::instructions::{
+    Deposit, DepositAccounts, DepositInstructionData, Initialize, InitializeAccounts,
+    InitializeInstructionData,
+};
+use crate::state::{Launch, LaunchState, Lock};
+use core::mem::size_of;
+use pinocchio::{
+    account_info::AccountInfo,
+    instruction::{Seed, Signer},
+    program_error::ProgramError,
+    sysvars::{clock::Clock, rent::Rent, Sysvar},
+    ProgramResult,
+};
+use pinocchio_associated_token_account::instructions::Create;
+use pinocchio_system::instructions::CreateAccount;
+use pinocchio_token::state::TokenAccount;
+
+pub struct GraduateAccounts<'a> {
+    pub payer: &'a AccountInfo,
+    pub launch: &'a AccountInfo,
+    pub token_vault: &'a AccountInfo,
+    pub quote_vault: &'a AccountInfo,
+    pub config: &'a AccountInfo,
+    pub mint_lp: &'a AccountInfo,
+    pub vault_x: &'a AccountInfo,
+    pub vault_y: &'a AccountInfo,
+    pub lock: &'a AccountInfo,
+    pub lock_vault: &'a AccountInfo,
+    pub system_program: &'a AccountInfo,
+    pub token_program: &'a AccountInfo,
+    pub associated_token_program: &'a AccountInfo,
+}
+
+impl<'a> TryFrom<&'a [AccountInfo]> for GraduateAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountInfo]) -> Result<Self, Self::Error> {
+        let [payer, launch, token_vault, quote_vault, config, mint_lp, vault_x, vault_y, lock, lock_vault, system_program, token_program, associated_token_program] =
+            accounts
+        else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        Ok(Self {
+            payer,
+            launch,
+            token_vault,
+            quote_vault,
+            config,
+            mint_lp,
+            vault_x,
+            vault_y,
+            lock,
+            lock_vault,
+            system_program,
+            token_program,
+            associated_token_program,
+        })
+    }
+}
+
+pub struct GraduateInstructionData {
+    pub seed: u64,
+    pub config_bump: [u8; 1],
+    pub lp_bump: [u8; 1],
+    pub lp_decimals: u8,
+    pub lock_bump: [u8; 1],
+}
+
+impl<'a> TryFrom<&'a [u8]> for GraduateInstructionData {
+    type Error = ProgramError;
+
+    fn try_from(data: &'a [u8]) -> Result<Self, Self::Error> {
+        if data.len().ne(&(size_of::<u64>() + size_of::<u8>() * 4)) {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        Ok(Self {
+            seed: u64::from_le_bytes(data[0..8].try_into().unwrap()),
+            config_bump: [data[8]],
+            lp_bump: [data[9]],
+            lp_decimals: data[10],
+            lock_bump: [data[11]],
+        })
+    }
+}
+
+/// Seed a pool with the raise and the unsold supply of a completed launch.
+///
+/// The pool is created through `Initialize`, without an authority, with the launched
+/// token as x and the quote token as y, and seeded through the first deposit logic of
+/// `Deposit`. As a first deposit leaves the LP amount to the depositor, the geometric
+/// mean of the seeded amounts is minted. The LP is locked for the creator until the
+/// lock duration of the launch has passed.
+///
+/// The pool vaults can be created beforehand, the lock vault cannot as the LP mint
+/// only exists from here on, so it is created along the way.
+pub struct Graduate<'a> {
+    pub accounts: GraduateAccounts<'a>,
+    pub instruction_data: GraduateInstructionData,
+}
+
+impl<'a> TryFrom<(&'a [u8], &'a [AccountInfo])> for Graduate<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountInfo])) -> Result<Self, Self::Error> {
+        let accounts = GraduateAccounts::try_from(accounts)?;
+        let instruction_data = GraduateInstructionData::try_from(data)?;
+
+        Ok(Self {
+            accounts,
+            instruction_data,
+        })
+    }
+}
+
+impl<'a> Graduate<'a> {
+    pub const DISCRIMINATOR: &'a u8 = &38;
+
+    pub fn process(&mut self) -> ProgramResult {
+        if !self.accounts.payer.is_signer() {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        let launch = Launch::load(self.accounts.launch)?;
+
+        if launch.state().ne(&(LaunchState::Completed as u8)) {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        let mint = *launch.mint();
+        let quote_mint = *launch.quote_mint();
+        let creator = *launch.creator();
+        let pool_fee = launch.pool_fee();
+        let lp_lock_duration = launch.lp_lock_duration();
+        let launch_bump = launch.bump();
+
+        drop(launch);
+
+        check_vaults(
+            self.accounts.launch,
+            self.accounts.token_program,
+            &[mint, quote_mint],
+            &[self.accounts.token_vault, self.accounts.quote_vault],
+        )?;
+
+        Initialize {
+            accounts: InitializeAccounts {
+                initializer: self.accounts.payer,
+                mint_lp: self.accounts.mint_lp,
+                config: self.accounts.config,
+                system_program: self.accounts.system_program,
+                remaining_accounts: &[],
+            },
+            instruction_data: InitializeInstructionData {
+                seed: self.instruction_data.seed,
+                fee: pool_fee,
+                mint_x: mint,
+                mint_y: quote_mint,
+                config_bump: self.instruction_data.config_bump,
+                lp_bump: self.instruction_data.lp_bump,
+                lp_decimals: self.instruction_data.lp_decimals,
+                authority: [0u8; 32],
+            },
+            metadata: None,
+        }
+        .process()?;
+
+        check_vaults(
+            self.accounts.config,
+            self.accounts.token_program,
+            &[mint, quote_mint],
+            &[self.accounts.vault_x, self.accounts.vault_y],
+        )?;
+
+        check_vaults(
+            self.accounts.lock,
+            self.accounts.token_program,
+            &[*self.accounts.mint_lp.key()],
+            core::slice::from_ref(self.accounts.lock_vault),
+        )?;
+
+        let x = unsafe { TokenAccount::from_account_info_unchecked(self.accounts.token_vault)? }
+            .amount();
+        let y = unsafe { TokenAccount::from_account_info_unchecked(self.accounts.quote_vault)? }
+            .amount();
+
+        let lp = u64::try_from((x as u128 * y as u128).isqrt())
+            .map_err(|_| ProgramError::ArithmeticOverflow)?;
+        if lp == 0 {
+            return Err(ProgramError::InsufficientFunds);
+        }
+
+        let lock_seed_binding = 0u64.to_le_bytes();
+        let lock_seeds = [
+            Seed::from(b"lock"),
+            Seed::from(self.accounts.config.key()),
+            Seed::from(&creator),
+            Seed::from(&lock_seed_binding),
+            Seed::from(&self.instruction_data.lock_bump),
+        ];
+
+        CreateAccount {
+            from: self.accounts.payer,
+            to: self.accounts.lock,
+            lamports: Rent::get()?.minimum_balance(Lock::LEN),
+            space: Lock::LEN as u64,
+            owner: &crate::ID,
+        }
+        .invoke_signed(&[Signer::from(&lock_seeds)])?;
+
+        let now = Clock::get()?.unix_timestamp;
+        let unlock_time = now
+            .checked_add(lp_lock_duration)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+
+        Lock::load_mut(self.accounts.lock)?.set_inner(
+            *self.accounts.config.key(),
+            creator,
+            *self.accounts.mint_lp.key(),
+            0,
+            lp,
+            now,
+            unlock_time,
+            unlock_time,
+            self.instruction_data.lock_bump,
+        )?;
+
+        Create {
+            funding_account: self.accounts.payer,
+            account: self.accounts.lock_vault,
+            wallet: self.accounts.lock,
+            mint: self.accounts.mint_lp,
+            system_program: self.accounts.system_program,
+            token_program: self.accounts.token_program,
+        }
+        .invoke()?;
+
+        let launch_seeds = [
+            Seed::from(b"launch"),
+            Seed::from(&mint),
+            Seed::from(&launch_bump),
+        ];
+
+        // The launch vaults fund the deposit, and the lock vault receives the LP
+        Deposit {
+            accounts: DepositAccounts {
+                user: self.accounts.launch,
+                mint_lp: self.accounts.mint_lp,
+                vault_x: self.accounts.vault_x,
+                vault_y: self.accounts.vault_y,
+                user_x_ata: self.accounts.token_vault,
+                user_y_ata: self.accounts.quote_vault,
+                user_lp_ata: self.accounts.lock_vault,
+                config: self.accounts.config,
+                token_program: self.accounts.token_program,
+                remaining_accounts: &[],
+            },
+            instruction_data: DepositInstructionData {
+                amount: lp,
+                max_x: x,
+                max_y: y,
+                deadline: Deadline::Slot(u64::MAX),
+                proof: &[],
+            },
+        }
+        .process_signed(&[Signer::from(&launch_seeds)])?;
+
+        Launch::load_mut(self.accounts.launch)?.graduate(*self.accounts.config.key());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,227 @@
+use crate::errors::AmmError;
+use crate::{
+    oracle::{PriceFeed, PRICE_SCALE},
+    state::{Allowlist, Config},
+};
+use core::{borrow::Borrow, mem::size_of, slice::Iter};
+use pinocchio::{
+    account_info::AccountInfo,
+    program_error::ProgramError,
+    pubkey::{find_program_address, Pubkey},
+    sysvars::{clock::Clock, Sysvar},
+    ProgramResult,
+};
+
+/// Last moment an instruction may land, as a unix timestamp or as a slot.
+///
+/// Version 1 instruction data only carries a timestamp. Version 2 encodes the deadline
+/// as a `u8` kind, `0` for a timestamp and `1` for a slot, followed by the `u64` value.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Deadline {
+    UnixTimestamp(i64),
+    Slot(u64),
+}
+
+impl Deadline {
+    pub const LEN: usize = size_of::<u8>() + size_of::<u64>();
+
+    /// Fail once the deadline has passed.
+    #[inline(always)]
+    pub fn check(&self) -> ProgramResult {
+        let clock = Clock::get()?;
+
+        let expired = match *self {
+            Deadline::UnixTimestamp(timestamp) => timestamp < clock.unix_timestamp,
+            Deadline::Slot(slot) => slot < clock.slot,
+        };
+
+        match expired {
+            true => Err(ProgramError::InvalidInstructionData),
+            false => Ok(()),
+        }
+    }
+
+    /// Encode the deadline the way version 2 instruction data carries it.
+    #[inline(always)]
+    pub fn to_bytes(&self) -> [u8; Self::LEN] {
+        let (kind, value) = match *self {
+            Deadline::UnixTimestamp(timestamp) => (0, timestamp.to_le_bytes()),
+            Deadline::Slot(slot) => (1, slot.to_le_bytes()),
+        };
+
+        let mut bytes = [kind; Self::LEN];
+        bytes[1..].copy_from_slice(&value);
+        bytes
+    }
+}
+
+impl TryFrom<&[u8]> for Deadline {
+    type Error = ProgramError;
+
+    fn try_from(data: &[u8]) -> Result<Self, Self::Error> {
+        if data.len().ne(&Self::LEN) {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let value: [u8; 8] = data[1..9].try_into().unwrap();
+
+        match data[0] {
+            0 => Ok(Deadline::UnixTimestamp(i64::from_le_bytes(value))),
+            1 => Ok(Deadline::Slot(u64::from_le_bytes(value))),
+            _ => Err(ProgramError::InvalidInstructionData),
+        }
+    }
+}
+
+/// Check that `authority` signed and matches the pool authority.
+///
+/// Pools initialized without an authority are immutable.
+#[inline(always)]
+pub fn check_authority(config: &Config, authority: &AccountInfo) -> ProgramResult {
+    if !authority.is_signer() {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    match config.has_authority() {
+        Some(key) if key.eq(authority.key()) => Ok(()),
+        _ => Err(ProgramError::IncorrectAuthority),
+    }
+}
+
+/// Check that `user` signed and is on the pool allowlist, when the pool has one.
+///
+/// The allowlist account is the next one in `accounts`, `proof` is only read by merkle
+/// allowlists.
+#[inline(always)]
+pub fn check_allowlist(
+    config: &Config,
+    user: &AccountInfo,
+    accounts: &mut Iter<AccountInfo>,
+    proof: &[u8],
+) -> ProgramResult {
+    let Some(allowlist) = config.has_allowlist() else {
+        return Ok(());
+    };
+
+    let allowlist_account = accounts.next().ok_or(ProgramError::NotEnoughAccountKeys)?;
+
+    if allowlist.ne(allowlist_account.key()) {
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    if !user.is_signer() || !Allowlist::load(allowlist_account)?.is_allowed(user.key(), proof) {
+        return Err(AmmError::NotAllowlisted.into());
+    }
+
+    Ok(())
+}
+
+/// Spot price of `x` in `y` for the given weights, scaled by `PRICE_SCALE`.
+#[inline(always)]
+pub fn spot_price(x: u64, weight_x: u16, y: u64, weight_y: u16) -> Result<u128, ProgramError> {
+    crate::math::spot_price(x, weight_x, y, weight_y, PRICE_SCALE)
+        .ok_or(ProgramError::ArithmeticOverflow)
+}
+
+/// Distance between `price` and `reference` in basis points of `reference`.
+#[inline(always)]
+pub fn deviation_bps(price: u128, reference: u128) -> Result<u128, ProgramError> {
+    if reference == 0 {
+        return Err(ProgramError::ArithmeticOverflow);
+    }
+
+    price
+        .abs_diff(reference)
+        .checked_mul(10_000)
+        .map(|d| d / reference)
+        .ok_or(ProgramError::ArithmeticOverflow)
+}
+
+/// Price of the pool oracle, when the pool has one.
+///
+/// The oracle account is the next one in `accounts`, its price is rejected once older
+/// than the pool allows.
+#[inline(always)]
+pub fn read_oracle(
+    config: &Config,
+    accounts: &mut Iter<AccountInfo>,
+    now: i64,
+) -> Result<Option<u128>, ProgramError> {
+    let Some(oracle) = config.has_oracle() else {
+        return Ok(None);
+    };
+
+    let oracle_account = accounts.next().ok_or(ProgramError::NotEnoughAccountKeys)?;
+
+    if oracle.ne(oracle_account.key()) {
+        return Err(AmmError::InvalidOracle.into());
+    }
+
+    let (oracle_price, updated_at) = PriceFeed::read(oracle_account)?;
+
+    if now.saturating_sub(updated_at) > config.oracle_max_age() {
+        return Err(AmmError::StaleOracle.into());
+    }
+
+    Ok(Some(oracle_price))
+}
+
+/// Guard the price a trade leaves the pool at against the oracle price, if any, and
+/// trip the circuit breaker once the price moved too far within the slot.
+#[inline(always)]
+pub fn check_price(
+    config: &mut Config,
+    oracle_price: Option<u128>,
+    slot: u64,
+    price_before: u128,
+    price_after: u128,
+) -> ProgramResult {
+    if let Some(oracle_price) = oracle_price {
+        if deviation_bps(price_after, oracle_price)? > config.oracle_max_deviation_bps() as u128 {
+            return Err(AmmError::OracleDeviation.into());
+        }
+    }
+
+    if config.max_slot_move_bps() != 0 {
+        let slot_move_bps = config.track_slot_move(slot, price_before, price_after);
+
+        if slot_move_bps > config.max_slot_move_bps() as u64 {
+            return Err(AmmError::SlotMoveExceeded.into());
+        }
+    }
+
+    Ok(())
+}
+
+/// Check that `vaults` are the pool's associated token accounts of `mints`, in order.
+#[inline(always)]
+pub fn check_vaults<V: Borrow<AccountInfo>>(
+    pool: &AccountInfo,
+    token_program: &AccountInfo,
+    mints: &[Pubkey],
+    vaults: &[V],
+) -> ProgramResult {
+    if vaults.len().ne(&mints.len()) {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    }
+
+    for (mint, vault) in mints.iter().zip(vaults) {
+        let (expected, _) = find_program_address(
+            &[pool.key(), token_program.key(), mint],
+            &pinocchio_associated_token_account::ID,
+        );
+
+        if expected.ne(vault.borrow().key()) {
+            return Err(ProgramError::InvalidAccountData);
+        }
+    }
+
+    Ok(())
+}
+
+/// Close a program owned `account`, sending its rent to `destination`.
+#[inline(always)]
+pub fn close_account(account: &AccountInfo, destination: &AccountInfo) -> ProgramResult {
+    *destination.try_borrow_mut_lamports()? += account.lamports();
+    account.close()
+}
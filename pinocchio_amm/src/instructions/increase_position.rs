@@ -0,0 +1,184 @@
+use crate::helpers::check_vaults;
+use crate::state::{AmmState, Config, Position};
+use core::mem::size_of;
+use pinocchio::{
+    account_info::AccountInfo,
+    instruction::{Seed, Signer},
+    program_error::ProgramError,
+    pubkey::find_program_address,
+    sysvars::{rent::Rent, Sysvar},
+    ProgramResult,
+};
+use pinocchio_system::instructions::CreateAccount;
+use pinocchio_token::instructions::Transfer;
+
+pub struct IncreasePositionAccounts<'a> {
+    pub user: &'a AccountInfo,
+    pub config: &'a AccountInfo,
+    pub position: &'a AccountInfo,
+    pub user_lp_ata: &'a AccountInfo,
+    pub lp_vault: &'a AccountInfo,
+    pub token_program: &'a AccountInfo,
+}
+
+impl<'a> TryFrom<&'a [AccountInfo]> for IncreasePositionAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountInfo]) -> Result<Self, Self::Error> {
+        let [user, config, position, user_lp_ata, lp_vault, _system_program, token_program] =
+            accounts
+        else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        Ok(Self {
+            user,
+            config,
+            position,
+            user_lp_ata,
+            lp_vault,
+            token_program,
+        })
+    }
+}
+
+pub struct IncreasePositionInstructionData {
+    pub amount: u64,
+}
+
+impl<'a> TryFrom<&'a [u8]> for IncreasePositionInstructionData {
+    type Error = ProgramError;
+
+    fn try_from(data: &'a [u8]) -> Result<Self, Self::Error> {
+        if data.len().ne(&size_of::<u64>()) {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let amount = u64::from_le_bytes(data[0..8].try_into().unwrap());
+
+        if amount == 0 {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        Ok(Self { amount })
+    }
+}
+
+/// Escrow LP tokens in a fee position, creating the user's position on first use.
+///
+/// The position is created at the canonical address of `[b"position", config, user]`,
+/// whose bump it stores, so each user has a single position per pool.
+pub struct IncreasePosition<'a> {
+    pub accounts: IncreasePositionAccounts<'a>,
+    pub instruction_data: IncreasePositionInstructionData,
+}
+
+impl<'a> TryFrom<(&'a [u8], &'a [AccountInfo])> for IncreasePosition<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountInfo])) -> Result<Self, Self::Error> {
+        let accounts = IncreasePositionAccounts::try_from(accounts)?;
+        let instruction_data = IncreasePositionInstructionData::try_from(data)?;
+
+        Ok(Self {
+            accounts,
+            instruction_data,
+        })
+    }
+}
+
+impl<'a> IncreasePosition<'a> {
+    pub const DISCRIMINATOR: &'a u8 = &32;
+
+    pub fn process(&mut self) -> ProgramResult {
+        if !self.accounts.user.is_signer() {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        let mut config = Config::load_mut(self.accounts.config)?;
+
+        if config.state().ne(&(AmmState::Initialized as u8)) {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        if self.accounts.position.data_is_empty() {
+            let (expected, bump) = find_program_address(
+                &[
+                    b"position",
+                    self.accounts.config.key(),
+                    self.accounts.user.key(),
+                ],
+                &crate::ID,
+            );
+
+            if expected.ne(self.accounts.position.key()) {
+                return Err(ProgramError::InvalidAccountData);
+            }
+
+            let position_bump = [bump];
+            let position_seeds = [
+                Seed::from(b"position"),
+                Seed::from(self.accounts.config.key()),
+                Seed::from(self.accounts.user.key()),
+                Seed::from(&position_bump),
+            ];
+
+            CreateAccount {
+                from: self.accounts.user,
+                to: self.accounts.position,
+                lamports: Rent::get()?.minimum_balance(Position::LEN),
+                space: Position::LEN as u64,
+                owner: &crate::ID,
+            }
+            .invoke_signed(&[Signer::from(&position_seeds)])?;
+
+            Position::load_mut(self.accounts.position)?.set_inner(
+                *self.accounts.config.key(),
+                *self.accounts.user.key(),
+                config.fee_growth_x(),
+                config.fee_growth_y(),
+                position_bump,
+            );
+        }
+
+        let (mint_lp, _) =
+            find_program_address(&[b"mint_lp", self.accounts.config.key()], &crate::ID);
+
+        check_vaults(
+            self.accounts.position,
+            self.accounts.token_program,
+            &[mint_lp],
+            core::slice::from_ref(self.accounts.lp_vault),
+        )?;
+
+        let mut position = Position::load_mut(self.accounts.position)?;
+        if position.config().ne(self.accounts.config.key())
+            || position.owner().ne(self.accounts.user.key())
+        {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        let (Some(amount), Some(positioned_lp)) = (
+            position.amount().checked_add(self.instruction_data.amount),
+            config
+                .positioned_lp()
+                .checked_add(self.instruction_data.amount),
+        ) else {
+            return Err(ProgramError::ArithmeticOverflow);
+        };
+
+        position.settle(config.fee_growth_x(), config.fee_growth_y(), amount)?;
+        config.set_positioned_lp(positioned_lp);
+
+        drop(position);
+        drop(config);
+
+        Transfer {
+            from: self.accounts.user_lp_ata,
+            to: self.accounts.lp_vault,
+            authority: self.accounts.user,
+            amount: self.instruction_data.amount,
+        }
+        .invoke()
+    }
+}
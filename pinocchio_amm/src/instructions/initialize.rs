@@ -1,3 +1,4 @@
+use crate::metadata::{CreateMetadataAccountV3, LpMetadata, TOKEN_METADATA_ID};
 use crate::state::Config;
 use core::mem::size_of;
 use pinocchio::{
@@ -15,13 +16,17 @@ pub struct InitializeAccounts<'a> {
     pub initializer: &'a AccountInfo,
     pub mint_lp: &'a AccountInfo,
     pub config: &'a AccountInfo,
+    pub system_program: &'a AccountInfo,
+    pub remaining_accounts: &'a [AccountInfo],
 }
 
 impl<'a> TryFrom<&'a [AccountInfo]> for InitializeAccounts<'a> {
     type Error = ProgramError;
 
     fn try_from(accounts: &'a [AccountInfo]) -> Result<Self, Self::Error> {
-        let [initializer, mint_lp, config, _system_program, _token_program] = accounts else {
+        let [initializer, mint_lp, config, system_program, _token_program, remaining_accounts @ ..] =
+            accounts
+        else {
             return Err(ProgramError::NotEnoughAccountKeys);
         };
 
@@ -29,6 +34,8 @@ impl<'a> TryFrom<&'a [AccountInfo]> for InitializeAccounts<'a> {
             initializer,
             mint_lp,
             config,
+            system_program,
+            remaining_accounts,
         })
         //..
     }
@@ -42,6 +49,7 @@ pub struct InitializeInstructionData {
     pub mint_y: [u8; 32],
     pub config_bump: [u8; 1],
     pub lp_bump: [u8; 1],
+    pub lp_decimals: u8,
     pub authority: [u8; 32],
 }
 
@@ -78,6 +86,7 @@ impl TryFrom<&[u8]> for InitializeInstructionData {
 pub struct Initialize<'a> {
     pub accounts: InitializeAccounts<'a>,
     pub instruction_data: InitializeInstructionData,
+    pub metadata: Option<LpMetadata<'a>>,
 }
 
 impl<'a> TryFrom<(&'a [u8], &'a [AccountInfo])> for Initialize<'a> {
@@ -85,12 +94,23 @@ impl<'a> TryFrom<(&'a [u8], &'a [AccountInfo])> for Initialize<'a> {
 
     fn try_from((data, accounts): (&'a [u8], &'a [AccountInfo])) -> Result<Self, Self::Error> {
         let accounts = InitializeAccounts::try_from(accounts)?;
+
+        // LP metadata may follow the authority
+        let (data, metadata) = match data.len() > size_of::<InitializeInstructionData>() {
+            true => {
+                let (data, metadata) = data.split_at(size_of::<InitializeInstructionData>());
+                (data, Some(LpMetadata::try_from(metadata)?))
+            }
+            false => (data, None),
+        };
+
         let instruction_data: InitializeInstructionData =
             InitializeInstructionData::try_from(data)?;
 
         Ok(Self {
             accounts,
             instruction_data,
+            metadata,
         })
     }
 }
@@ -150,11 +170,33 @@ impl<'a> Initialize<'a> {
 
         InitializeMint2 {
             mint: self.accounts.mint_lp,
-            decimals: 6,
+            decimals: self.instruction_data.lp_decimals,
             mint_authority: self.accounts.config.key(),
             freeze_authority: None,
         }
         .invoke_signed(&[Signer::from(&mint_lp_seeds)])?;
+
+        if let Some(lp_metadata) = &self.metadata {
+            let [metadata, token_metadata_program, ..] = self.accounts.remaining_accounts else {
+                return Err(ProgramError::NotEnoughAccountKeys);
+            };
+
+            if token_metadata_program.key().ne(&TOKEN_METADATA_ID) {
+                return Err(ProgramError::IncorrectProgramId);
+            }
+
+            // The config is both the mint authority and the update authority
+            CreateMetadataAccountV3 {
+                metadata,
+                mint: self.accounts.mint_lp,
+                mint_authority: self.accounts.config,
+                payer: self.accounts.initializer,
+                update_authority: self.accounts.config,
+                system_program: self.accounts.system_program,
+                lp_metadata,
+            }
+            .invoke_signed(&[Signer::from(&config_seeds)])?;
+        }
         //..
 
         Ok(())
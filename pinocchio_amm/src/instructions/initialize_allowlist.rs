@@ -0,0 +1,107 @@
+use crate::helpers::check_authority;
+use crate::state::{Allowlist, Config};
+use pinocchio::{
+    account_info::AccountInfo,
+    instruction::{Seed, Signer},
+    program_error::ProgramError,
+    sysvars::{rent::Rent, Sysvar},
+    ProgramResult,
+};
+use pinocchio_system::instructions::CreateAccount;
+
+pub struct InitializeAllowlistAccounts<'a> {
+    pub authority: &'a AccountInfo,
+    pub config: &'a AccountInfo,
+    pub allowlist: &'a AccountInfo,
+}
+
+impl<'a> TryFrom<&'a [AccountInfo]> for InitializeAllowlistAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountInfo]) -> Result<Self, Self::Error> {
+        let [authority, config, allowlist, _system_program] = accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        Ok(Self {
+            authority,
+            config,
+            allowlist,
+        })
+    }
+}
+
+pub struct InitializeAllowlistInstructionData {
+    pub mode: u8,
+    pub allowlist_bump: [u8; 1],
+}
+
+impl<'a> TryFrom<&'a [u8]> for InitializeAllowlistInstructionData {
+    type Error = ProgramError;
+
+    fn try_from(data: &'a [u8]) -> Result<Self, Self::Error> {
+        let [mode, allowlist_bump] = data else {
+            return Err(ProgramError::InvalidInstructionData);
+        };
+
+        Ok(Self {
+            mode: *mode,
+            allowlist_bump: [*allowlist_bump],
+        })
+    }
+}
+
+/// Create the pool allowlist and restrict `Deposit`, `Swap` and `Withdraw` to its wallets.
+pub struct InitializeAllowlist<'a> {
+    pub accounts: InitializeAllowlistAccounts<'a>,
+    pub instruction_data: InitializeAllowlistInstructionData,
+}
+
+impl<'a> TryFrom<(&'a [u8], &'a [AccountInfo])> for InitializeAllowlist<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountInfo])) -> Result<Self, Self::Error> {
+        let accounts = InitializeAllowlistAccounts::try_from(accounts)?;
+        let instruction_data = InitializeAllowlistInstructionData::try_from(data)?;
+
+        Ok(Self {
+            accounts,
+            instruction_data,
+        })
+    }
+}
+
+impl<'a> InitializeAllowlist<'a> {
+    pub const DISCRIMINATOR: &'a u8 = &22;
+
+    pub fn process(&mut self) -> ProgramResult {
+        let mut config = Config::load_mut(self.accounts.config)?;
+
+        check_authority(&config, self.accounts.authority)?;
+
+        let allowlist_seeds = [
+            Seed::from(b"allowlist"),
+            Seed::from(self.accounts.config.key()),
+            Seed::from(&self.instruction_data.allowlist_bump),
+        ];
+
+        CreateAccount {
+            from: self.accounts.authority,
+            to: self.accounts.allowlist,
+            lamports: Rent::get()?.minimum_balance(Allowlist::LEN),
+            space: Allowlist::LEN as u64,
+            owner: &crate::ID,
+        }
+        .invoke_signed(&[Signer::from(&allowlist_seeds)])?;
+
+        Allowlist::load_mut(self.accounts.allowlist)?.set_inner(
+            *self.accounts.config.key(),
+            self.instruction_data.mode,
+            self.instruction_data.allowlist_bump,
+        )?;
+
+        config.set_allowlist(*self.accounts.allowlist.key());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,177 @@
+use crate::helpers::{check_authority, check_vaults};
+use crate::state::{Config, Farm};
+use core::mem::size_of;
+use pinocchio::{
+    account_info::AccountInfo,
+    instruction::{Seed, Signer},
+    program_error::ProgramError,
+    pubkey::find_program_address,
+    sysvars::{clock::Clock, rent::Rent, Sysvar},
+    ProgramResult,
+};
+use pinocchio_system::instructions::CreateAccount;
+use pinocchio_token::instructions::Transfer;
+
+pub struct InitializeFarmAccounts<'a> {
+    pub authority: &'a AccountInfo,
+    pub config: &'a AccountInfo,
+    pub mint_lp: &'a AccountInfo,
+    pub reward_mint: &'a AccountInfo,
+    pub farm: &'a AccountInfo,
+    pub authority_reward_ata: &'a AccountInfo,
+    pub reward_vault: &'a AccountInfo,
+    pub token_program: &'a AccountInfo,
+}
+
+impl<'a> TryFrom<&'a [AccountInfo]> for InitializeFarmAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountInfo]) -> Result<Self, Self::Error> {
+        let [authority, config, mint_lp, reward_mint, farm, authority_reward_ata, reward_vault, _system_program, token_program] =
+            accounts
+        else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        Ok(Self {
+            authority,
+            config,
+            mint_lp,
+            reward_mint,
+            farm,
+            authority_reward_ata,
+            reward_vault,
+            token_program,
+        })
+    }
+}
+
+pub struct InitializeFarmInstructionData {
+    pub seed: u64,
+    pub reward_rate: u64,
+    pub end_time: i64,
+    pub farm_bump: [u8; 1],
+}
+
+impl<'a> TryFrom<&'a [u8]> for InitializeFarmInstructionData {
+    type Error = ProgramError;
+
+    fn try_from(data: &'a [u8]) -> Result<Self, Self::Error> {
+        if data
+            .len()
+            .ne(&(size_of::<u64>() * 2 + size_of::<i64>() + size_of::<u8>()))
+        {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let seed = u64::from_le_bytes(data[0..8].try_into().unwrap());
+        let reward_rate = u64::from_le_bytes(data[8..16].try_into().unwrap());
+        let end_time = i64::from_le_bytes(data[16..24].try_into().unwrap());
+        let farm_bump = [data[24]];
+
+        if reward_rate == 0 || end_time <= Clock::get()?.unix_timestamp {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        Ok(Self {
+            seed,
+            reward_rate,
+            end_time,
+            farm_bump,
+        })
+    }
+}
+
+/// Open a farm paying `reward_rate` reward tokens per second to staked LP until
+/// `end_time`, funded upfront by the pool authority.
+///
+/// The farm pays nothing while no LP is staked, and `end_time` is pushed later by that
+/// time, see `Farm::update`.
+pub struct InitializeFarm<'a> {
+    pub accounts: InitializeFarmAccounts<'a>,
+    pub instruction_data: InitializeFarmInstructionData,
+}
+
+impl<'a> TryFrom<(&'a [u8], &'a [AccountInfo])> for InitializeFarm<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountInfo])) -> Result<Self, Self::Error> {
+        let accounts = InitializeFarmAccounts::try_from(accounts)?;
+        let instruction_data = InitializeFarmInstructionData::try_from(data)?;
+
+        Ok(Self {
+            accounts,
+            instruction_data,
+        })
+    }
+}
+
+impl<'a> InitializeFarm<'a> {
+    pub const DISCRIMINATOR: &'a u8 = &14;
+
+    pub fn process(&mut self) -> ProgramResult {
+        check_authority(
+            &*Config::load(self.accounts.config)?,
+            self.accounts.authority,
+        )?;
+
+        let (mint_lp, _) =
+            find_program_address(&[b"mint_lp", self.accounts.config.key()], &crate::ID);
+
+        if mint_lp.ne(self.accounts.mint_lp.key()) {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        check_vaults(
+            self.accounts.farm,
+            self.accounts.token_program,
+            &[*self.accounts.reward_mint.key()],
+            core::slice::from_ref(self.accounts.reward_vault),
+        )?;
+
+        let seed_binding = self.instruction_data.seed.to_le_bytes();
+        let farm_seeds = [
+            Seed::from(b"farm"),
+            Seed::from(self.accounts.config.key()),
+            Seed::from(&seed_binding),
+            Seed::from(&self.instruction_data.farm_bump),
+        ];
+
+        CreateAccount {
+            from: self.accounts.authority,
+            to: self.accounts.farm,
+            lamports: Rent::get()?.minimum_balance(Farm::LEN),
+            space: Farm::LEN as u64,
+            owner: &crate::ID,
+        }
+        .invoke_signed(&[Signer::from(&farm_seeds)])?;
+
+        let now = Clock::get()?.unix_timestamp;
+
+        Farm::load_mut(self.accounts.farm)?.set_inner(
+            *self.accounts.config.key(),
+            mint_lp,
+            *self.accounts.reward_mint.key(),
+            self.instruction_data.seed,
+            self.instruction_data.reward_rate,
+            now,
+            self.instruction_data.end_time,
+            self.instruction_data.farm_bump,
+        )?;
+
+        // Fund every reward the farm will ever emit
+        let rewards = self
+            .instruction_data
+            .reward_rate
+            .checked_mul((self.instruction_data.end_time - now) as u64)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+
+        Transfer {
+            from: self.accounts.authority_reward_ata,
+            to: self.accounts.reward_vault,
+            authority: self.accounts.authority,
+            amount: rewards,
+        }
+        .invoke()
+    }
+}
@@ -0,0 +1,169 @@
+use crate::helpers::check_vaults;
+use crate::state::Launch;
+use core::mem::size_of;
+use pinocchio::{
+    account_info::AccountInfo,
+    instruction::{Seed, Signer},
+    program_error::ProgramError,
+    sysvars::{rent::Rent, Sysvar},
+    ProgramResult,
+};
+use pinocchio_system::instructions::CreateAccount;
+use pinocchio_token::instructions::Transfer;
+
+pub struct InitializeLaunchAccounts<'a> {
+    pub creator: &'a AccountInfo,
+    pub launch: &'a AccountInfo,
+    pub mint: &'a AccountInfo,
+    pub quote_mint: &'a AccountInfo,
+    pub creator_token_ata: &'a AccountInfo,
+    pub token_vault: &'a AccountInfo,
+    pub token_program: &'a AccountInfo,
+}
+
+impl<'a> TryFrom<&'a [AccountInfo]> for InitializeLaunchAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountInfo]) -> Result<Self, Self::Error> {
+        let [creator, launch, mint, quote_mint, creator_token_ata, token_vault, _system_program, token_program] =
+            accounts
+        else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        Ok(Self {
+            creator,
+            launch,
+            mint,
+            quote_mint,
+            creator_token_ata,
+            token_vault,
+            token_program,
+        })
+    }
+}
+
+pub struct InitializeLaunchInstructionData {
+    pub virtual_quote: u64,
+    pub virtual_token: u64,
+    pub sale_supply: u64,
+    pub pool_supply: u64,
+    pub target_raise: u64,
+    pub lp_lock_duration: i64,
+    pub pool_fee: u16,
+    pub launch_bump: [u8; 1],
+}
+
+impl<'a> TryFrom<&'a [u8]> for InitializeLaunchInstructionData {
+    type Error = ProgramError;
+
+    fn try_from(data: &'a [u8]) -> Result<Self, Self::Error> {
+        if data
+            .len()
+            .ne(&(size_of::<u64>() * 5 + size_of::<i64>() + size_of::<u16>() + size_of::<u8>()))
+        {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let virtual_quote = u64::from_le_bytes(data[0..8].try_into().unwrap());
+        let virtual_token = u64::from_le_bytes(data[8..16].try_into().unwrap());
+        let sale_supply = u64::from_le_bytes(data[16..24].try_into().unwrap());
+        let pool_supply = u64::from_le_bytes(data[24..32].try_into().unwrap());
+        let target_raise = u64::from_le_bytes(data[32..40].try_into().unwrap());
+        let lp_lock_duration = i64::from_le_bytes(data[40..48].try_into().unwrap());
+        let pool_fee = u16::from_le_bytes(data[48..50].try_into().unwrap());
+        let launch_bump = [data[50]];
+
+        if sale_supply == 0 || pool_supply == 0 {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        Ok(Self {
+            virtual_quote,
+            virtual_token,
+            sale_supply,
+            pool_supply,
+            target_raise,
+            lp_lock_duration,
+            pool_fee,
+            launch_bump,
+        })
+    }
+}
+
+/// Open a bonding curve launch, escrowing the supply for sale and for the pool.
+pub struct InitializeLaunch<'a> {
+    pub accounts: InitializeLaunchAccounts<'a>,
+    pub instruction_data: InitializeLaunchInstructionData,
+}
+
+impl<'a> TryFrom<(&'a [u8], &'a [AccountInfo])> for InitializeLaunch<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountInfo])) -> Result<Self, Self::Error> {
+        let accounts = InitializeLaunchAccounts::try_from(accounts)?;
+        let instruction_data = InitializeLaunchInstructionData::try_from(data)?;
+
+        Ok(Self {
+            accounts,
+            instruction_data,
+        })
+    }
+}
+
+impl<'a> InitializeLaunch<'a> {
+    pub const DISCRIMINATOR: &'a u8 = &35;
+
+    pub fn process(&mut self) -> ProgramResult {
+        if !self.accounts.creator.is_signer() {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        check_vaults(
+            self.accounts.launch,
+            self.accounts.token_program,
+            &[*self.accounts.mint.key()],
+            core::slice::from_ref(self.accounts.token_vault),
+        )?;
+
+        let launch_seeds = [
+            Seed::from(b"launch"),
+            Seed::from(self.accounts.mint.key()),
+            Seed::from(&self.instruction_data.launch_bump),
+        ];
+
+        CreateAccount {
+            from: self.accounts.creator,
+            to: self.accounts.launch,
+            lamports: Rent::get()?.minimum_balance(Launch::LEN),
+            space: Launch::LEN as u64,
+            owner: &crate::ID,
+        }
+        .invoke_signed(&[Signer::from(&launch_seeds)])?;
+
+        Launch::load_mut(self.accounts.launch)?.set_inner(
+            *self.accounts.creator.key(),
+            *self.accounts.mint.key(),
+            *self.accounts.quote_mint.key(),
+            self.instruction_data.virtual_quote,
+            self.instruction_data.virtual_token,
+            self.instruction_data.sale_supply,
+            self.instruction_data.target_raise,
+            self.instruction_data.pool_fee,
+            self.instruction_data.lp_lock_duration,
+            self.instruction_data.launch_bump,
+        )?;
+
+        Transfer {
+            from: self.accounts.creator_token_ata,
+            to: self.accounts.token_vault,
+            authority: self.accounts.creator,
+            amount: self
+                .instruction_data
+                .sale_supply
+                .checked_add(self.instruction_data.pool_supply)
+                .ok_or(ProgramError::ArithmeticOverflow)?,
+        }
+        .invoke()
+    }
+}
@@ -0,0 +1,181 @@
+use crate::state::{MultiPool, MAX_TOKENS};
+use core::mem::size_of;
+use pinocchio::{
+    account_info::AccountInfo,
+    instruction::{Seed, Signer},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvars::{rent::Rent, Sysvar},
+    ProgramResult,
+};
+use pinocchio_system::instructions::CreateAccount;
+use pinocchio_token::{instructions::InitializeMint2, state::Mint};
+
+pub struct InitializeMultiPoolAccounts<'a> {
+    pub initializer: &'a AccountInfo,
+    pub mint_lp: &'a AccountInfo,
+    pub pool: &'a AccountInfo,
+    pub mints: &'a [AccountInfo],
+}
+
+impl<'a> TryFrom<&'a [AccountInfo]> for InitializeMultiPoolAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountInfo]) -> Result<Self, Self::Error> {
+        let [initializer, mint_lp, pool, _system_program, _token_program, mints @ ..] = accounts
+        else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        if !(2..=MAX_TOKENS).contains(&mints.len()) {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        }
+
+        Ok(Self {
+            initializer,
+            mint_lp,
+            pool,
+            mints,
+        })
+    }
+}
+
+pub struct InitializeMultiPoolInstructionData {
+    pub seed: u64,
+    pub fee: u16,
+    pub pool_bump: [u8; 1],
+    pub lp_bump: [u8; 1],
+    pub authority: [u8; 32],
+    pub count: usize,
+    pub weights: [u16; MAX_TOKENS],
+}
+
+impl<'a> TryFrom<&'a [u8]> for InitializeMultiPoolInstructionData {
+    type Error = ProgramError;
+
+    fn try_from(data: &'a [u8]) -> Result<Self, Self::Error> {
+        const HEADER_LEN: usize =
+            size_of::<u64>() + size_of::<u16>() + size_of::<[u8; 1]>() * 2 + size_of::<[u8; 32]>();
+
+        if data.len() <= HEADER_LEN
+            || !(data.len() - HEADER_LEN).is_multiple_of(size_of::<u16>())
+            || (data.len() - HEADER_LEN) / size_of::<u16>() > MAX_TOKENS
+        {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let seed = u64::from_le_bytes(data[0..8].try_into().unwrap());
+        let fee = u16::from_le_bytes(data[8..10].try_into().unwrap());
+        let pool_bump = [data[10]];
+        let lp_bump = [data[11]];
+        let authority: [u8; 32] = data[12..44].try_into().unwrap();
+
+        let count = (data.len() - HEADER_LEN) / size_of::<u16>();
+        let mut weights = [0u16; MAX_TOKENS];
+        for (weight, bytes) in weights
+            .iter_mut()
+            .zip(data[HEADER_LEN..].chunks_exact(size_of::<u16>()))
+        {
+            *weight = u16::from_le_bytes(bytes.try_into().unwrap());
+        }
+
+        Ok(Self {
+            seed,
+            fee,
+            pool_bump,
+            lp_bump,
+            authority,
+            count,
+            weights,
+        })
+    }
+}
+
+/// Create a weighted pool of two to `MAX_TOKENS` mints, passed in the remaining accounts
+/// in the same order as their weights.
+pub struct InitializeMultiPool<'a> {
+    pub accounts: InitializeMultiPoolAccounts<'a>,
+    pub instruction_data: InitializeMultiPoolInstructionData,
+}
+
+impl<'a> TryFrom<(&'a [u8], &'a [AccountInfo])> for InitializeMultiPool<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountInfo])) -> Result<Self, Self::Error> {
+        let accounts = InitializeMultiPoolAccounts::try_from(accounts)?;
+        let instruction_data = InitializeMultiPoolInstructionData::try_from(data)?;
+
+        // One weight per mint
+        if instruction_data.count.ne(&accounts.mints.len()) {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        Ok(Self {
+            accounts,
+            instruction_data,
+        })
+    }
+}
+
+impl<'a> InitializeMultiPool<'a> {
+    pub const DISCRIMINATOR: &'a u8 = &8;
+
+    pub fn process(&mut self) -> ProgramResult {
+        let seed_binding = self.instruction_data.seed.to_le_bytes();
+        let pool_seeds = [
+            Seed::from(b"multi_pool"),
+            Seed::from(&seed_binding),
+            Seed::from(&self.instruction_data.pool_bump),
+        ];
+
+        CreateAccount {
+            from: self.accounts.initializer,
+            to: self.accounts.pool,
+            lamports: Rent::get()?.minimum_balance(MultiPool::LEN),
+            space: MultiPool::LEN as u64,
+            owner: &crate::ID,
+        }
+        .invoke_signed(&[Signer::from(&pool_seeds)])?;
+
+        let count = self.instruction_data.count;
+        let mut mints = [Pubkey::default(); MAX_TOKENS];
+        for (mint, account) in mints.iter_mut().zip(self.accounts.mints) {
+            if account.owner().ne(&pinocchio_token::ID) {
+                return Err(ProgramError::InvalidAccountOwner);
+            }
+            *mint = *account.key();
+        }
+
+        MultiPool::load_mut(self.accounts.pool)?.set_inner(
+            self.instruction_data.seed,
+            self.instruction_data.authority,
+            self.instruction_data.fee,
+            self.instruction_data.pool_bump,
+            &mints[..count],
+            &self.instruction_data.weights[..count],
+        )?;
+
+        let mint_lp_seeds = [
+            Seed::from(b"mint_lp"),
+            Seed::from(self.accounts.pool.key()),
+            Seed::from(&self.instruction_data.lp_bump),
+        ];
+
+        CreateAccount {
+            from: self.accounts.initializer,
+            to: self.accounts.mint_lp,
+            lamports: Rent::get()?.minimum_balance(Mint::LEN),
+            space: Mint::LEN as u64,
+            owner: &pinocchio_token::ID,
+        }
+        .invoke_signed(&[Signer::from(&mint_lp_seeds)])?;
+
+        InitializeMint2 {
+            mint: self.accounts.mint_lp,
+            decimals: 6,
+            mint_authority: self.accounts.pool.key(),
+            freeze_authority: None,
+        }
+        .invoke_signed(&[Signer::from(&mint_lp_seeds)])
+    }
+}
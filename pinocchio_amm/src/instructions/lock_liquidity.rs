@@ -0,0 +1,166 @@
+use crate::helpers::check_vaults;
+use crate::state::Lock;
+use core::mem::size_of;
+use pinocchio::{
+    account_info::AccountInfo,
+    instruction::{Seed, Signer},
+    program_error::ProgramError,
+    pubkey::find_program_address,
+    sysvars::{clock::Clock, rent::Rent, Sysvar},
+    ProgramResult,
+};
+use pinocchio_system::instructions::CreateAccount;
+use pinocchio_token::instructions::Transfer;
+
+pub struct LockLiquidityAccounts<'a> {
+    pub owner: &'a AccountInfo,
+    pub config: &'a AccountInfo,
+    pub mint_lp: &'a AccountInfo,
+    pub lock: &'a AccountInfo,
+    pub owner_lp_ata: &'a AccountInfo,
+    pub lock_vault: &'a AccountInfo,
+    pub token_program: &'a AccountInfo,
+}
+
+impl<'a> TryFrom<&'a [AccountInfo]> for LockLiquidityAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountInfo]) -> Result<Self, Self::Error> {
+        let [owner, config, mint_lp, lock, owner_lp_ata, lock_vault, _system_program, token_program] =
+            accounts
+        else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        Ok(Self {
+            owner,
+            config,
+            mint_lp,
+            lock,
+            owner_lp_ata,
+            lock_vault,
+            token_program,
+        })
+    }
+}
+
+pub struct LockLiquidityInstructionData {
+    pub seed: u64,
+    pub amount: u64,
+    pub cliff_time: i64,
+    pub end_time: i64,
+    pub lock_bump: [u8; 1],
+}
+
+impl<'a> TryFrom<&'a [u8]> for LockLiquidityInstructionData {
+    type Error = ProgramError;
+
+    fn try_from(data: &'a [u8]) -> Result<Self, Self::Error> {
+        if data
+            .len()
+            .ne(&(size_of::<u64>() * 2 + size_of::<i64>() * 2 + size_of::<u8>()))
+        {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let seed = u64::from_le_bytes(data[0..8].try_into().unwrap());
+        let amount = u64::from_le_bytes(data[8..16].try_into().unwrap());
+        let cliff_time = i64::from_le_bytes(data[16..24].try_into().unwrap());
+        let end_time = i64::from_le_bytes(data[24..32].try_into().unwrap());
+        let lock_bump = [data[32]];
+
+        if amount == 0 {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        Ok(Self {
+            seed,
+            amount,
+            cliff_time,
+            end_time,
+            lock_bump,
+        })
+    }
+}
+
+/// Escrow LP tokens in a lock PDA vesting linearly until `end_time`, after a cliff.
+pub struct LockLiquidity<'a> {
+    pub accounts: LockLiquidityAccounts<'a>,
+    pub instruction_data: LockLiquidityInstructionData,
+}
+
+impl<'a> TryFrom<(&'a [u8], &'a [AccountInfo])> for LockLiquidity<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountInfo])) -> Result<Self, Self::Error> {
+        let accounts = LockLiquidityAccounts::try_from(accounts)?;
+        let instruction_data = LockLiquidityInstructionData::try_from(data)?;
+
+        Ok(Self {
+            accounts,
+            instruction_data,
+        })
+    }
+}
+
+impl<'a> LockLiquidity<'a> {
+    pub const DISCRIMINATOR: &'a u8 = &28;
+
+    pub fn process(&mut self) -> ProgramResult {
+        if !self.accounts.owner.is_signer() {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        let (mint_lp, _) =
+            find_program_address(&[b"mint_lp", self.accounts.config.key()], &crate::ID);
+
+        if mint_lp.ne(self.accounts.mint_lp.key()) {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        check_vaults(
+            self.accounts.lock,
+            self.accounts.token_program,
+            &[mint_lp],
+            core::slice::from_ref(self.accounts.lock_vault),
+        )?;
+
+        let seed_binding = self.instruction_data.seed.to_le_bytes();
+        let lock_seeds = [
+            Seed::from(b"lock"),
+            Seed::from(self.accounts.config.key()),
+            Seed::from(self.accounts.owner.key()),
+            Seed::from(&seed_binding),
+            Seed::from(&self.instruction_data.lock_bump),
+        ];
+
+        CreateAccount {
+            from: self.accounts.owner,
+            to: self.accounts.lock,
+            lamports: Rent::get()?.minimum_balance(Lock::LEN),
+            space: Lock::LEN as u64,
+            owner: &crate::ID,
+        }
+        .invoke_signed(&[Signer::from(&lock_seeds)])?;
+
+        Lock::load_mut(self.accounts.lock)?.set_inner(
+            *self.accounts.config.key(),
+            *self.accounts.owner.key(),
+            mint_lp,
+            self.instruction_data.seed,
+            self.instruction_data.amount,
+            Clock::get()?.unix_timestamp,
+            self.instruction_data.cliff_time,
+            self.instruction_data.end_time,
+            self.instruction_data.lock_bump,
+        )?;
+
+        Transfer {
+            from: self.accounts.owner_lp_ata,
+            to: self.accounts.lock_vault,
+            authority: self.accounts.owner,
+            amount: self.instruction_data.amount,
+        }
+        .invoke()
+    }
+}
@@ -0,0 +1,372 @@
+use crate::errors::AmmError;
+use crate::helpers::{check_allowlist, check_vaults, spot_price, Deadline};
+use crate::math::{constant_product, HASH_LEN};
+use crate::state::{AmmState, Config, CurveType};
+use core::mem::size_of;
+use pinocchio::{
+    account_info::AccountInfo,
+    instruction::{Seed, Signer},
+    program_error::ProgramError,
+    pubkey::find_program_address,
+    sysvars::{clock::Clock, Sysvar},
+    ProgramResult,
+};
+use pinocchio_token::instructions::{Burn, MintTo, Transfer};
+use pinocchio_token::state::{Mint, TokenAccount};
+
+pub struct MigrateLiquidityAccounts<'a> {
+    pub user: &'a AccountInfo,
+    pub source_config: &'a AccountInfo,
+    pub source_mint_lp: &'a AccountInfo,
+    pub source_vault_x: &'a AccountInfo,
+    pub source_vault_y: &'a AccountInfo,
+    pub user_source_lp_ata: &'a AccountInfo,
+    pub target_config: &'a AccountInfo,
+    pub target_mint_lp: &'a AccountInfo,
+    pub target_vault_x: &'a AccountInfo,
+    pub target_vault_y: &'a AccountInfo,
+    pub user_target_lp_ata: &'a AccountInfo,
+    pub user_x_ata: &'a AccountInfo,
+    pub user_y_ata: &'a AccountInfo,
+    pub token_program: &'a AccountInfo,
+    pub remaining_accounts: &'a [AccountInfo],
+}
+
+impl<'a> TryFrom<&'a [AccountInfo]> for MigrateLiquidityAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountInfo]) -> Result<Self, Self::Error> {
+        let [user, source_config, source_mint_lp, source_vault_x, source_vault_y, user_source_lp_ata, target_config, target_mint_lp, target_vault_x, target_vault_y, user_target_lp_ata, user_x_ata, user_y_ata, token_program, remaining_accounts @ ..] =
+            accounts
+        else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        Ok(Self {
+            user,
+            source_config,
+            source_mint_lp,
+            source_vault_x,
+            source_vault_y,
+            user_source_lp_ata,
+            target_config,
+            target_mint_lp,
+            target_vault_x,
+            target_vault_y,
+            user_target_lp_ata,
+            user_x_ata,
+            user_y_ata,
+            token_program,
+            remaining_accounts,
+        })
+    }
+}
+
+pub struct MigrateLiquidityInstructionData<'a> {
+    pub amount: u64,
+    pub min_lp: u64,
+    pub deadline: Deadline,
+    pub source_proof: &'a [u8],
+    pub target_proof: &'a [u8],
+}
+
+impl<'a> TryFrom<&'a [u8]> for MigrateLiquidityInstructionData<'a> {
+    type Error = ProgramError;
+
+    fn try_from(data: &'a [u8]) -> Result<Self, Self::Error> {
+        const HEADER_LEN: usize = size_of::<u64>() * 2 + Deadline::LEN + size_of::<u8>();
+
+        if data.len() < HEADER_LEN {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let amount = u64::from_le_bytes(data[0..8].try_into().unwrap());
+        let min_lp = u64::from_le_bytes(data[8..16].try_into().unwrap());
+        let deadline = Deadline::try_from(&data[16..16 + Deadline::LEN])?;
+
+        // The source proof is given as a number of hashes, the target proof takes the rest
+        let source_proof_len = data[HEADER_LEN - 1] as usize * HASH_LEN;
+        let proofs = &data[HEADER_LEN..];
+
+        if proofs.len() < source_proof_len
+            || !(proofs.len() - source_proof_len).is_multiple_of(HASH_LEN)
+        {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let (source_proof, target_proof) = proofs.split_at(source_proof_len);
+
+        if amount == 0 || min_lp == 0 {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        Ok(Self {
+            amount,
+            min_lp,
+            deadline,
+            source_proof,
+            target_proof,
+        })
+    }
+}
+
+/// Move liquidity between two pools of the same pair in one instruction.
+///
+/// Burns `amount` source LP, withdraws its share of both tokens to the user token
+/// accounts and deposits them into the target pool at its current ratio. Whatever does
+/// not fit that ratio stays with the user.
+///
+/// Allowlisted pools take their allowlist account from the remaining accounts, the
+/// source one first. Their merkle proofs trail the instruction data, the source proof
+/// prefixed with its number of hashes.
+pub struct MigrateLiquidity<'a> {
+    pub accounts: MigrateLiquidityAccounts<'a>,
+    pub instruction_data: MigrateLiquidityInstructionData<'a>,
+}
+
+impl<'a> TryFrom<(&'a [u8], &'a [AccountInfo])> for MigrateLiquidity<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountInfo])) -> Result<Self, Self::Error> {
+        let accounts = MigrateLiquidityAccounts::try_from(accounts)?;
+        let instruction_data = MigrateLiquidityInstructionData::try_from(data)?;
+
+        Ok(Self {
+            accounts,
+            instruction_data,
+        })
+    }
+}
+
+impl<'a> MigrateLiquidity<'a> {
+    pub const DISCRIMINATOR: &'a u8 = &27;
+
+    pub fn process(&mut self) -> ProgramResult {
+        self.instruction_data.deadline.check()?;
+
+        if self.accounts.source_config.key() == self.accounts.target_config.key() {
+            return Err(ProgramError::InvalidArgument);
+        }
+
+        let source = Config::load(self.accounts.source_config)?;
+        let target = Config::load(self.accounts.target_config)?;
+
+        if source.state().ne(&(AmmState::Initialized as u8))
+            && source.state().ne(&(AmmState::WithdrawOnly as u8))
+        {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        if target.state().ne(&(AmmState::Initialized as u8)) {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        if source.mint_x().ne(target.mint_x()) || source.mint_y().ne(target.mint_y()) {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        // Only the authority provides liquidity until a bootstrapping sale ends
+        if target.curve().eq(&(CurveType::Weighted as u8))
+            && Clock::get()?.unix_timestamp < target.weights_end_time()
+            && target.has_authority().ne(&Some(*self.accounts.user.key()))
+        {
+            return Err(AmmError::DepositRestricted.into());
+        }
+
+        let mut remaining_accounts = self.accounts.remaining_accounts.iter();
+        check_allowlist(
+            &source,
+            self.accounts.user,
+            &mut remaining_accounts,
+            self.instruction_data.source_proof,
+        )?;
+        check_allowlist(
+            &target,
+            self.accounts.user,
+            &mut remaining_accounts,
+            self.instruction_data.target_proof,
+        )?;
+
+        let mints = [*source.mint_x(), *source.mint_y()];
+
+        for (config, mint_lp, vault_x, vault_y) in [
+            (
+                self.accounts.source_config,
+                self.accounts.source_mint_lp,
+                self.accounts.source_vault_x,
+                self.accounts.source_vault_y,
+            ),
+            (
+                self.accounts.target_config,
+                self.accounts.target_mint_lp,
+                self.accounts.target_vault_x,
+                self.accounts.target_vault_y,
+            ),
+        ] {
+            let (expected_mint_lp, _) =
+                find_program_address(&[b"mint_lp", config.key()], &crate::ID);
+
+            if expected_mint_lp.ne(mint_lp.key()) {
+                return Err(ProgramError::InvalidAccountData);
+            }
+
+            check_vaults(
+                config,
+                self.accounts.token_program,
+                &mints,
+                &[vault_x, vault_y],
+            )?;
+        }
+
+        let source_mint_lp =
+            unsafe { Mint::from_account_info_unchecked(self.accounts.source_mint_lp)? };
+        let source_vault_x =
+            unsafe { TokenAccount::from_account_info_unchecked(self.accounts.source_vault_x)? };
+        let source_vault_y =
+            unsafe { TokenAccount::from_account_info_unchecked(self.accounts.source_vault_y)? };
+
+        let (source_x, source_y) =
+            source.reserves(source_vault_x.amount(), source_vault_y.amount());
+
+        // Same split as `Withdraw`
+        let (x, y) = match source_mint_lp.supply() == self.instruction_data.amount {
+            true => (source_x, source_y),
+            false => constant_product::withdraw_amounts(
+                source_x,
+                source_y,
+                source_mint_lp.supply(),
+                self.instruction_data.amount,
+            )?,
+        };
+
+        let target_mint_lp =
+            unsafe { Mint::from_account_info_unchecked(self.accounts.target_mint_lp)? };
+        let target_vault_x =
+            unsafe { TokenAccount::from_account_info_unchecked(self.accounts.target_vault_x)? };
+        let target_vault_y =
+            unsafe { TokenAccount::from_account_info_unchecked(self.accounts.target_vault_y)? };
+
+        let supply = target_mint_lp.supply();
+        let (target_x, target_y) =
+            target.reserves(target_vault_x.amount(), target_vault_y.amount());
+
+        // An empty target takes everything and mints as much LP as was burned, otherwise
+        // the scarcer token sets the LP and the other one is deposited in proportion
+        let (lp, deposit_x, deposit_y) = match supply == 0 && target_x == 0 && target_y == 0 {
+            true => (self.instruction_data.amount, x, y),
+            false => {
+                let lp = constant_product::lp_for_amounts(target_x, target_y, supply, x, y)?;
+                let (deposit_x, deposit_y) =
+                    constant_product::deposit_amounts(target_x, target_y, supply, lp)?;
+
+                (lp, deposit_x, deposit_y)
+            }
+        };
+
+        if lp < self.instruction_data.min_lp || deposit_x > x || deposit_y > y {
+            return Err(AmmError::SlippageExceeded.into());
+        }
+
+        Burn {
+            mint: self.accounts.source_mint_lp,
+            account: self.accounts.user_source_lp_ata,
+            authority: self.accounts.user,
+            amount: self.instruction_data.amount,
+        }
+        .invoke()?;
+
+        let source_seed_binding = source.seed().to_le_bytes();
+        let source_bump = source.config_bump();
+        let source_seeds = [
+            Seed::from(b"config"),
+            Seed::from(&source_seed_binding),
+            Seed::from(source.mint_x()),
+            Seed::from(source.mint_y()),
+            Seed::from(&source_bump),
+        ];
+        let source_signer = [Signer::from(&source_seeds)];
+
+        Transfer {
+            from: self.accounts.source_vault_x,
+            to: self.accounts.user_x_ata,
+            authority: self.accounts.source_config,
+            amount: x,
+        }
+        .invoke_signed(&source_signer)?;
+
+        Transfer {
+            from: self.accounts.source_vault_y,
+            to: self.accounts.user_y_ata,
+            authority: self.accounts.source_config,
+            amount: y,
+        }
+        .invoke_signed(&source_signer)?;
+
+        Transfer {
+            from: self.accounts.user_x_ata,
+            to: self.accounts.target_vault_x,
+            authority: self.accounts.user,
+            amount: deposit_x,
+        }
+        .invoke()?;
+
+        Transfer {
+            from: self.accounts.user_y_ata,
+            to: self.accounts.target_vault_y,
+            authority: self.accounts.user,
+            amount: deposit_y,
+        }
+        .invoke()?;
+
+        let target_seed_binding = target.seed().to_le_bytes();
+        let target_bump = target.config_bump();
+        let target_seeds = [
+            Seed::from(b"config"),
+            Seed::from(&target_seed_binding),
+            Seed::from(target.mint_x()),
+            Seed::from(target.mint_y()),
+            Seed::from(&target_bump),
+        ];
+
+        MintTo {
+            mint: self.accounts.target_mint_lp,
+            account: self.accounts.user_target_lp_ata,
+            mint_authority: self.accounts.target_config,
+            amount: lp,
+        }
+        .invoke_signed(&[Signer::from(&target_seeds)])?;
+
+        drop(source);
+        drop(target);
+
+        let clock = Clock::get()?;
+
+        // Keep the supply the fee growth is spread over in step with both mints, and
+        // count the migration as a trade of each pool
+        for (config, mint_lp, x, y) in [
+            (
+                self.accounts.source_config,
+                &source_mint_lp,
+                source_x.checked_sub(x),
+                source_y.checked_sub(y),
+            ),
+            (
+                self.accounts.target_config,
+                &target_mint_lp,
+                target_x.checked_add(deposit_x),
+                target_y.checked_add(deposit_y),
+            ),
+        ] {
+            let mut config = Config::load_mut(config)?;
+            config.set_lp_supply(mint_lp.supply());
+
+            let (weight_x, weight_y) = config.weights(clock.unix_timestamp);
+            let price = x
+                .zip(y)
+                .and_then(|(x, y)| spot_price(x, weight_x, y, weight_y).ok());
+            config.record_trade(clock.slot, clock.unix_timestamp, price);
+        }
+
+        Ok(())
+    }
+}
@@ -1,9 +1,99 @@
+pub mod buy_launch;
+pub mod cancel_range_order;
+pub mod claim_batch_order;
+pub mod claim_fees;
+pub mod claim_range_order;
+pub mod claim_rewards;
+pub mod commit_swap;
+pub mod decrease_position;
 pub mod deposit;
+pub mod get_pool_state;
+pub mod graduate;
+pub mod helpers;
+pub mod increase_position;
 pub mod initialize;
+pub mod initialize_allowlist;
+pub mod initialize_farm;
+pub mod initialize_launch;
+pub mod initialize_multi_pool;
+pub mod lock_liquidity;
+pub mod migrate_liquidity;
+pub mod multi_deposit;
+pub mod multi_exit;
+pub mod multi_join;
+pub mod multi_swap;
+pub mod multi_withdraw;
+pub mod native;
+pub mod pause;
+pub mod place_batch_order;
+pub mod place_range_order;
+pub mod reveal_swap;
+pub mod sell_launch;
+pub mod set_batch_window;
+pub mod set_circuit_breaker;
+pub mod set_dynamic_fee;
+pub mod set_guardian;
+pub mod set_oracle;
+pub mod set_referral_fee;
+pub mod set_state;
+pub mod set_swap_hook;
+pub mod set_weights;
+pub mod settle_batch;
+pub mod stake;
+pub mod stake_locked;
 pub mod swap;
+pub mod unlock_liquidity;
+pub mod unstake;
+pub mod unstake_locked;
+pub mod update_allowlist;
 pub mod withdraw;
 
+pub use buy_launch::*;
+pub use cancel_range_order::*;
+pub use claim_batch_order::*;
+pub use claim_fees::*;
+pub use claim_range_order::*;
+pub use claim_rewards::*;
+pub use commit_swap::*;
+pub use decrease_position::*;
 pub use deposit::*;
+pub use get_pool_state::*;
+pub use graduate::*;
+pub use helpers::*;
+pub use increase_position::*;
 pub use initialize::*;
+pub use initialize_allowlist::*;
+pub use initialize_farm::*;
+pub use initialize_launch::*;
+pub use initialize_multi_pool::*;
+pub use lock_liquidity::*;
+pub use migrate_liquidity::*;
+pub use multi_deposit::*;
+pub use multi_exit::*;
+pub use multi_join::*;
+pub use multi_swap::*;
+pub use multi_withdraw::*;
+pub use native::*;
+pub use pause::*;
+pub use place_batch_order::*;
+pub use place_range_order::*;
+pub use reveal_swap::*;
+pub use sell_launch::*;
+pub use set_batch_window::*;
+pub use set_circuit_breaker::*;
+pub use set_dynamic_fee::*;
+pub use set_guardian::*;
+pub use set_oracle::*;
+pub use set_referral_fee::*;
+pub use set_state::*;
+pub use set_swap_hook::*;
+pub use set_weights::*;
+pub use settle_batch::*;
+pub use stake::*;
+pub use stake_locked::*;
 pub use swap::*;
+pub use unlock_liquidity::*;
+pub use unstake::*;
+pub use unstake_locked::*;
+pub use update_allowlist::*;
 pub use withdraw::*;
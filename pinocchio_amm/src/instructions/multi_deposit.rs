@@ -0,0 +1,198 @@
+use crate::helpers::check_vaults;
+use crate::state::{MultiPool, MAX_TOKENS};
+use crate::AmmState;
+use core::mem::size_of;
+use pinocchio::instruction::{Seed, Signer};
+use pinocchio::sysvars::clock::Clock;
+use pinocchio::sysvars::Sysvar;
+use pinocchio::{account_info::AccountInfo, program_error::ProgramError, ProgramResult};
+use pinocchio_token::instructions::{MintTo, Transfer};
+use pinocchio_token::state::{Mint, TokenAccount};
+
+pub struct MultiDepositAccounts<'a> {
+    pub user: &'a AccountInfo,
+    pub pool: &'a AccountInfo,
+    pub mint_lp: &'a AccountInfo,
+    pub user_lp_ata: &'a AccountInfo,
+    pub token_program: &'a AccountInfo,
+    pub vaults: &'a [AccountInfo],
+    pub user_atas: &'a [AccountInfo],
+}
+
+impl<'a> TryFrom<&'a [AccountInfo]> for MultiDepositAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountInfo]) -> Result<Self, Self::Error> {
+        let [user, pool, mint_lp, user_lp_ata, token_program, token_accounts @ ..] = accounts
+        else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        // The vaults first, then the user token accounts, both in mint order
+        if (token_accounts.len() % 2).ne(&0) || token_accounts.is_empty() {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        }
+        let (vaults, user_atas) = token_accounts.split_at(token_accounts.len() / 2);
+
+        Ok(Self {
+            user,
+            pool,
+            mint_lp,
+            user_lp_ata,
+            token_program,
+            vaults,
+            user_atas,
+        })
+    }
+}
+
+pub struct MultiDepositInstructionData {
+    pub amount: u64,
+    pub expiration: i64,
+    pub count: usize,
+    pub max_amounts: [u64; MAX_TOKENS],
+}
+
+impl<'a> TryFrom<&'a [u8]> for MultiDepositInstructionData {
+    type Error = ProgramError;
+
+    fn try_from(data: &'a [u8]) -> Result<Self, Self::Error> {
+        const HEADER_LEN: usize = size_of::<u64>() + size_of::<i64>();
+
+        if data.len() <= HEADER_LEN
+            || !(data.len() - HEADER_LEN).is_multiple_of(size_of::<u64>())
+            || (data.len() - HEADER_LEN) / size_of::<u64>() > MAX_TOKENS
+        {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let amount = u64::from_le_bytes(data[0..8].try_into().unwrap());
+        let expiration = i64::from_le_bytes(data[8..16].try_into().unwrap());
+
+        let count = (data.len() - HEADER_LEN) / size_of::<u64>();
+        let mut max_amounts = [0u64; MAX_TOKENS];
+        for (max, bytes) in max_amounts
+            .iter_mut()
+            .zip(data[HEADER_LEN..].chunks_exact(size_of::<u64>()))
+        {
+            *max = u64::from_le_bytes(bytes.try_into().unwrap());
+        }
+
+        if amount == 0
+            || max_amounts[..count].contains(&0)
+            || expiration < Clock::get()?.unix_timestamp
+        {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        Ok(Self {
+            amount,
+            expiration,
+            count,
+            max_amounts,
+        })
+    }
+}
+
+/// Deposit every token of a multi-asset pool in proportion to its balance.
+pub struct MultiDeposit<'a> {
+    pub accounts: MultiDepositAccounts<'a>,
+    pub instruction_data: MultiDepositInstructionData,
+}
+
+impl<'a> TryFrom<(&'a [u8], &'a [AccountInfo])> for MultiDeposit<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountInfo])) -> Result<Self, Self::Error> {
+        let accounts = MultiDepositAccounts::try_from(accounts)?;
+        let instruction_data = MultiDepositInstructionData::try_from(data)?;
+
+        if instruction_data.count.ne(&accounts.vaults.len()) {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        Ok(Self {
+            accounts,
+            instruction_data,
+        })
+    }
+}
+
+impl<'a> MultiDeposit<'a> {
+    pub const DISCRIMINATOR: &'a u8 = &10;
+
+    pub fn process(&mut self) -> ProgramResult {
+        let pool = MultiPool::load(self.accounts.pool)?;
+
+        if pool.state().ne(&(AmmState::Initialized as u8)) {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        check_vaults(
+            self.accounts.pool,
+            self.accounts.token_program,
+            pool.mints(),
+            self.accounts.vaults,
+        )?;
+
+        let supply = unsafe { Mint::from_account_info_unchecked(self.accounts.mint_lp)?.supply() };
+
+        let mut balances = [0u64; MAX_TOKENS];
+        for (balance, vault) in balances.iter_mut().zip(self.accounts.vaults) {
+            *balance = unsafe { TokenAccount::from_account_info_unchecked(vault)?.amount() };
+        }
+        let balances = &balances[..pool.count()];
+
+        for (i, (vault, user_ata)) in self
+            .accounts
+            .vaults
+            .iter()
+            .zip(self.accounts.user_atas)
+            .enumerate()
+        {
+            // Seed the pool with the maximum amounts, then deposit pro rata, rounded up
+            let amount = match supply == 0 && balances.iter().all(|&b| b == 0) {
+                true => self.instruction_data.max_amounts[i],
+                false => {
+                    if supply == 0 {
+                        return Err(ProgramError::InvalidAccountData);
+                    }
+                    u64::try_from(
+                        (balances[i] as u128 * self.instruction_data.amount as u128)
+                            .div_ceil(supply as u128),
+                    )
+                    .map_err(|_| ProgramError::ArithmeticOverflow)?
+                }
+            };
+
+            // Check for slippage
+            if amount > self.instruction_data.max_amounts[i] {
+                return Err(ProgramError::InvalidArgument);
+            }
+
+            Transfer {
+                from: user_ata,
+                to: vault,
+                authority: self.accounts.user,
+                amount,
+            }
+            .invoke()?;
+        }
+
+        let seed_binding = pool.seed().to_le_bytes();
+        let pool_bump = pool.bump();
+        let seeds = [
+            Seed::from(b"multi_pool"),
+            Seed::from(&seed_binding),
+            Seed::from(&pool_bump),
+        ];
+
+        MintTo {
+            mint: self.accounts.mint_lp,
+            account: self.accounts.user_lp_ata,
+            mint_authority: self.accounts.pool,
+            amount: self.instruction_data.amount,
+        }
+        .invoke_signed(&[Signer::from(&seeds)])
+    }
+}
@@ -0,0 +1,164 @@
+use crate::errors::AmmError;
+use crate::helpers::check_vaults;
+use crate::math::{lp_out_given_in, single_asset_fee};
+use crate::state::MultiPool;
+use crate::AmmState;
+use core::mem::size_of;
+use pinocchio::instruction::{Seed, Signer};
+use pinocchio::sysvars::clock::Clock;
+use pinocchio::sysvars::Sysvar;
+use pinocchio::{account_info::AccountInfo, program_error::ProgramError, ProgramResult};
+use pinocchio_token::instructions::{MintTo, Transfer};
+use pinocchio_token::state::{Mint, TokenAccount};
+
+pub struct MultiJoinAccounts<'a> {
+    pub user: &'a AccountInfo,
+    pub pool: &'a AccountInfo,
+    pub mint_lp: &'a AccountInfo,
+    pub user_ata: &'a AccountInfo,
+    pub user_lp_ata: &'a AccountInfo,
+    pub token_program: &'a AccountInfo,
+    pub vaults: &'a [AccountInfo],
+}
+
+impl<'a> TryFrom<&'a [AccountInfo]> for MultiJoinAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountInfo]) -> Result<Self, Self::Error> {
+        let [user, pool, mint_lp, user_ata, user_lp_ata, token_program, vaults @ ..] = accounts
+        else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        Ok(Self {
+            user,
+            pool,
+            mint_lp,
+            user_ata,
+            user_lp_ata,
+            token_program,
+            vaults,
+        })
+    }
+}
+
+pub struct MultiJoinInstructionData {
+    pub index: u8,
+    pub amount_in: u64,
+    pub min_lp: u64,
+    pub expiration: i64,
+}
+
+impl<'a> TryFrom<&'a [u8]> for MultiJoinInstructionData {
+    type Error = ProgramError;
+
+    fn try_from(data: &'a [u8]) -> Result<Self, Self::Error> {
+        if data
+            .len()
+            .ne(&(size_of::<u8>() + size_of::<u64>() * 2 + size_of::<i64>()))
+        {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let index = data[0];
+        let amount_in = u64::from_le_bytes(data[1..9].try_into().unwrap());
+        let min_lp = u64::from_le_bytes(data[9..17].try_into().unwrap());
+        let expiration = i64::from_le_bytes(data[17..25].try_into().unwrap());
+
+        if amount_in == 0 || min_lp == 0 || expiration < Clock::get()?.unix_timestamp {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        Ok(Self {
+            index,
+            amount_in,
+            min_lp,
+            expiration,
+        })
+    }
+}
+
+/// Join a multi-asset pool with a single token, minting LP on the weighted invariant.
+pub struct MultiJoin<'a> {
+    pub accounts: MultiJoinAccounts<'a>,
+    pub instruction_data: MultiJoinInstructionData,
+}
+
+impl<'a> TryFrom<(&'a [u8], &'a [AccountInfo])> for MultiJoin<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountInfo])) -> Result<Self, Self::Error> {
+        let accounts = MultiJoinAccounts::try_from(accounts)?;
+        let instruction_data = MultiJoinInstructionData::try_from(data)?;
+
+        Ok(Self {
+            accounts,
+            instruction_data,
+        })
+    }
+}
+
+impl<'a> MultiJoin<'a> {
+    pub const DISCRIMINATOR: &'a u8 = &12;
+
+    pub fn process(&mut self) -> ProgramResult {
+        let pool = MultiPool::load(self.accounts.pool)?;
+
+        if pool.state().ne(&(AmmState::Initialized as u8)) {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        check_vaults(
+            self.accounts.pool,
+            self.accounts.token_program,
+            pool.mints(),
+            self.accounts.vaults,
+        )?;
+
+        let index = self.instruction_data.index as usize;
+        let vault = self
+            .accounts
+            .vaults
+            .get(index)
+            .ok_or(ProgramError::InvalidInstructionData)?;
+
+        let balance = unsafe { TokenAccount::from_account_info_unchecked(vault)?.amount() };
+        let supply = unsafe { Mint::from_account_info_unchecked(self.accounts.mint_lp)?.supply() };
+        let weight = pool.weight(index);
+
+        // Only the share implicitly swapped into the other tokens pays the fee
+        let amount_in = self.instruction_data.amount_in;
+        let fee = single_asset_fee(amount_in, weight, pool.fee());
+
+        let lp = lp_out_given_in(balance, weight, supply, amount_in - fee)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+
+        if lp == 0 || lp < self.instruction_data.min_lp {
+            return Err(AmmError::SlippageExceeded.into());
+        }
+
+        let seed_binding = pool.seed().to_le_bytes();
+        let pool_bump = pool.bump();
+        let seeds = [
+            Seed::from(b"multi_pool"),
+            Seed::from(&seed_binding),
+            Seed::from(&pool_bump),
+        ];
+
+        Transfer {
+            from: self.accounts.user_ata,
+            to: vault,
+            authority: self.accounts.user,
+            amount: amount_in,
+        }
+        .invoke()?;
+
+        MintTo {
+            mint: self.accounts.mint_lp,
+            account: self.accounts.user_lp_ata,
+            mint_authority: self.accounts.pool,
+            amount: lp,
+        }
+        .invoke_signed(&[Signer::from(&seeds)])
+    }
+}
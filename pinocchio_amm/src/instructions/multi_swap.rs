@@ -0,0 +1,175 @@
+use crate::errors::AmmError;
+use crate::helpers::check_vaults;
+use crate::math::out_given_in;
+use crate::state::MultiPool;
+use crate::AmmState;
+use core::mem::size_of;
+use pinocchio::instruction::{Seed, Signer};
+use pinocchio::sysvars::clock::Clock;
+use pinocchio::sysvars::Sysvar;
+use pinocchio::{account_info::AccountInfo, program_error::ProgramError, ProgramResult};
+use pinocchio_token::instructions::Transfer;
+use pinocchio_token::state::TokenAccount;
+
+pub struct MultiSwapAccounts<'a> {
+    pub user: &'a AccountInfo,
+    pub pool: &'a AccountInfo,
+    pub user_in: &'a AccountInfo,
+    pub user_out: &'a AccountInfo,
+    pub token_program: &'a AccountInfo,
+    pub vaults: &'a [AccountInfo],
+}
+
+impl<'a> TryFrom<&'a [AccountInfo]> for MultiSwapAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountInfo]) -> Result<Self, Self::Error> {
+        let [user, pool, user_in, user_out, token_program, vaults @ ..] = accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        Ok(Self {
+            user,
+            pool,
+            user_in,
+            user_out,
+            token_program,
+            vaults,
+        })
+    }
+}
+
+pub struct MultiSwapInstructionData {
+    pub index_in: u8,
+    pub index_out: u8,
+    pub amount: u64,
+    pub min: u64,
+    pub expiration: i64,
+}
+
+impl<'a> TryFrom<&'a [u8]> for MultiSwapInstructionData {
+    type Error = ProgramError;
+
+    fn try_from(data: &'a [u8]) -> Result<Self, Self::Error> {
+        if data
+            .len()
+            .ne(&(size_of::<u8>() * 2 + size_of::<u64>() * 2 + size_of::<i64>()))
+        {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let index_in = data[0];
+        let index_out = data[1];
+        let amount = u64::from_le_bytes(data[2..10].try_into().unwrap());
+        let min = u64::from_le_bytes(data[10..18].try_into().unwrap());
+        let expiration = i64::from_le_bytes(data[18..26].try_into().unwrap());
+
+        if index_in == index_out
+            || amount == 0
+            || min == 0
+            || expiration < Clock::get()?.unix_timestamp
+        {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        Ok(Self {
+            index_in,
+            index_out,
+            amount,
+            min,
+            expiration,
+        })
+    }
+}
+
+/// Swap any token of a multi-asset pool for any other, the pool vaults are passed in
+/// mint order after the fixed accounts.
+pub struct MultiSwap<'a> {
+    pub accounts: MultiSwapAccounts<'a>,
+    pub instruction_data: MultiSwapInstructionData,
+}
+
+impl<'a> TryFrom<(&'a [u8], &'a [AccountInfo])> for MultiSwap<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountInfo])) -> Result<Self, Self::Error> {
+        let accounts = MultiSwapAccounts::try_from(accounts)?;
+        let instruction_data = MultiSwapInstructionData::try_from(data)?;
+
+        Ok(Self {
+            accounts,
+            instruction_data,
+        })
+    }
+}
+
+impl<'a> MultiSwap<'a> {
+    pub const DISCRIMINATOR: &'a u8 = &9;
+
+    pub fn process(&mut self) -> ProgramResult {
+        let pool = MultiPool::load(self.accounts.pool)?;
+
+        if pool.state().ne(&(AmmState::Initialized as u8)) {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        check_vaults(
+            self.accounts.pool,
+            self.accounts.token_program,
+            pool.mints(),
+            self.accounts.vaults,
+        )?;
+
+        let index_in = self.instruction_data.index_in as usize;
+        let index_out = self.instruction_data.index_out as usize;
+        let (Some(vault_in), Some(vault_out)) = (
+            self.accounts.vaults.get(index_in),
+            self.accounts.vaults.get(index_out),
+        ) else {
+            return Err(ProgramError::InvalidInstructionData);
+        };
+
+        let balance_in = unsafe { TokenAccount::from_account_info_unchecked(vault_in)?.amount() };
+        let balance_out = unsafe { TokenAccount::from_account_info_unchecked(vault_out)?.amount() };
+
+        let amount = self.instruction_data.amount;
+        let amount_after_fee = (amount as u128 * (10_000 - pool.fee() as u128) / 10_000) as u64;
+
+        let withdraw = out_given_in(
+            balance_in,
+            pool.weight(index_in),
+            balance_out,
+            pool.weight(index_out),
+            amount_after_fee,
+        )
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+
+        if withdraw == 0 || withdraw < self.instruction_data.min {
+            return Err(AmmError::SlippageExceeded.into());
+        }
+
+        let seed_binding = pool.seed().to_le_bytes();
+        let pool_bump = pool.bump();
+        let seeds = [
+            Seed::from(b"multi_pool"),
+            Seed::from(&seed_binding),
+            Seed::from(&pool_bump),
+        ];
+
+        Transfer {
+            from: self.accounts.user_in,
+            to: vault_in,
+            authority: self.accounts.user,
+            amount,
+        }
+        .invoke()?;
+
+        Transfer {
+            from: vault_out,
+            to: self.accounts.user_out,
+            authority: self.accounts.pool,
+            amount: withdraw,
+        }
+        .invoke_signed(&[Signer::from(&seeds)])
+    }
+}
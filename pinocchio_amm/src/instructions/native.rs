@@ -0,0 +1,127 @@
+use core::slice::Iter;
+use pinocchio::{
+    account_info::AccountInfo,
+    instruction::{Seed, Signer},
+    program_error::ProgramError,
+    pubkey::{find_program_address, Pubkey},
+    sysvars::{rent::Rent, Sysvar},
+    ProgramResult,
+};
+use pinocchio_system::instructions::{Allocate, Assign, CreateAccount, Transfer};
+use pinocchio_token::instructions::{CloseAccount, InitializeAccount3};
+use pinocchio_token::state::TokenAccount;
+
+// So11111111111111111111111111111111111111112
+pub const NATIVE_MINT: Pubkey = [
+    0x06, 0x9b, 0x88, 0x57, 0xfe, 0xab, 0x81, 0x84, 0xfb, 0x68, 0x7f, 0x63, 0x46, 0x18, 0xc0, 0x35,
+    0xda, 0xc4, 0x39, 0xdc, 0x1a, 0xeb, 0x3b, 0x55, 0x98, 0xa0, 0xf0, 0x00, 0x00, 0x00, 0x00, 0x01,
+];
+
+/// Temporary wSOL account opened and closed within one instruction, so users can trade
+/// native SOL straight from their system account.
+///
+/// The account lives at the `[b"wsol", user]` PDA and is owned by the user, who signs the
+/// transfers out of it and receives every lamport back when it is closed.
+pub struct WrappedSol<'a> {
+    pub account: &'a AccountInfo,
+    pub native_mint: &'a AccountInfo,
+    pub user: &'a AccountInfo,
+    pub bump: [u8; 1],
+}
+
+impl<'a> WrappedSol<'a> {
+    /// Take the `[wsol_account, native_mint, system_program]` accounts from `accounts`.
+    pub fn next(
+        user: &'a AccountInfo,
+        accounts: &mut Iter<'a, AccountInfo>,
+    ) -> Result<Self, ProgramError> {
+        let (Some(account), Some(native_mint), Some(_system_program)) =
+            (accounts.next(), accounts.next(), accounts.next())
+        else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        if native_mint.key().ne(&NATIVE_MINT) {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        let (expected, bump) = find_program_address(&[b"wsol", user.key()], &crate::ID);
+
+        if expected.ne(account.key()) {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        Ok(Self {
+            account,
+            native_mint,
+            user,
+            bump: [bump],
+        })
+    }
+
+    /// Open the account holding `amount` wSOL taken from the user's lamports.
+    pub fn open(&self, amount: u64) -> ProgramResult {
+        let seeds = [
+            Seed::from(b"wsol"),
+            Seed::from(self.user.key()),
+            Seed::from(&self.bump),
+        ];
+        let signer = [Signer::from(&seeds)];
+
+        let lamports = Rent::get()?
+            .minimum_balance(TokenAccount::LEN)
+            .checked_add(amount)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+
+        // Anyone can send lamports to the address, so top it up instead of failing
+        match self.account.lamports() {
+            0 => CreateAccount {
+                from: self.user,
+                to: self.account,
+                lamports,
+                space: TokenAccount::LEN as u64,
+                owner: &pinocchio_token::ID,
+            }
+            .invoke_signed(&signer)?,
+            current => {
+                if lamports > current {
+                    Transfer {
+                        from: self.user,
+                        to: self.account,
+                        lamports: lamports - current,
+                    }
+                    .invoke()?;
+                }
+
+                Allocate {
+                    account: self.account,
+                    space: TokenAccount::LEN as u64,
+                }
+                .invoke_signed(&signer)?;
+
+                Assign {
+                    account: self.account,
+                    owner: &pinocchio_token::ID,
+                }
+                .invoke_signed(&signer)?;
+            }
+        }
+
+        InitializeAccount3 {
+            account: self.account,
+            mint: self.native_mint,
+            owner: self.user.key(),
+        }
+        .invoke()
+    }
+
+    /// Close the account, unwrapping whatever it holds back to the user.
+    pub fn close(&self) -> ProgramResult {
+        CloseAccount {
+            account: self.account,
+            destination: self.user,
+            authority: self.user,
+        }
+        .invoke()
+    }
+}
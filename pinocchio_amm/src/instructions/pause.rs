@@ -0,0 +1,91 @@
+use crate::state::{AmmState, Config};
+use pinocchio::{account_info::AccountInfo, program_error::ProgramError, ProgramResult};
+
+pub struct PauseAccounts<'a> {
+    pub guardian: &'a AccountInfo,
+    pub config: &'a AccountInfo,
+}
+
+impl<'a> TryFrom<&'a [AccountInfo]> for PauseAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountInfo]) -> Result<Self, Self::Error> {
+        let [guardian, config] = accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        Ok(Self { guardian, config })
+    }
+}
+
+pub struct PauseInstructionData {
+    pub state: u8,
+}
+
+impl<'a> TryFrom<&'a [u8]> for PauseInstructionData {
+    type Error = ProgramError;
+
+    fn try_from(data: &'a [u8]) -> Result<Self, Self::Error> {
+        let [state] = data else {
+            return Err(ProgramError::InvalidInstructionData);
+        };
+
+        if state.ne(&(AmmState::Disabled as u8)) && state.ne(&(AmmState::WithdrawOnly as u8)) {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        Ok(Self { state: *state })
+    }
+}
+
+/// Move the pool to `Disabled` or `WithdrawOnly`, signed by the guardian or the authority.
+///
+/// Pausing only ever tightens the pool state, unpausing goes through `SetState`.
+pub struct Pause<'a> {
+    pub accounts: PauseAccounts<'a>,
+    pub instruction_data: PauseInstructionData,
+}
+
+impl<'a> TryFrom<(&'a [u8], &'a [AccountInfo])> for Pause<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountInfo])) -> Result<Self, Self::Error> {
+        let accounts = PauseAccounts::try_from(accounts)?;
+        let instruction_data = PauseInstructionData::try_from(data)?;
+
+        Ok(Self {
+            accounts,
+            instruction_data,
+        })
+    }
+}
+
+impl<'a> Pause<'a> {
+    pub const DISCRIMINATOR: &'a u8 = &25;
+
+    pub fn process(&mut self) -> ProgramResult {
+        let mut config = Config::load_mut(self.accounts.config)?;
+
+        if !self.accounts.guardian.is_signer() {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        let signer = Some(*self.accounts.guardian.key());
+        if config.has_guardian().ne(&signer) && config.has_authority().ne(&signer) {
+            return Err(ProgramError::IncorrectAuthority);
+        }
+
+        // Disabled is stricter than withdraw-only, which is stricter than trading
+        let strictness = |state: u8| match state {
+            state if state == AmmState::Initialized as u8 => 0,
+            state if state == AmmState::WithdrawOnly as u8 => 1,
+            _ => 2,
+        };
+
+        if strictness(self.instruction_data.state) <= strictness(config.state()) {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        config.set_state(self.instruction_data.state)
+    }
+}
@@ -0,0 +1,198 @@
+use crate::helpers::{check_allowlist, check_vaults};
+use crate::state::{AmmState, Batch, Config, BATCH_ORDER_DEPOSIT};
+use core::mem::size_of;
+use pinocchio::{
+    account_info::AccountInfo,
+    instruction::{Seed, Signer},
+    program_error::ProgramError,
+    sysvars::{clock::Clock, rent::Rent, Sysvar},
+    ProgramResult,
+};
+use pinocchio_system::instructions::{CreateAccount, Transfer as SystemTransfer};
+use pinocchio_token::instructions::Transfer;
+
+pub struct PlaceBatchOrderAccounts<'a> {
+    pub user: &'a AccountInfo,
+    pub config: &'a AccountInfo,
+    pub batch: &'a AccountInfo,
+    pub user_ata: &'a AccountInfo,
+    pub batch_vault: &'a AccountInfo,
+    pub token_program: &'a AccountInfo,
+    pub remaining_accounts: &'a [AccountInfo],
+}
+
+impl<'a> TryFrom<&'a [AccountInfo]> for PlaceBatchOrderAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountInfo]) -> Result<Self, Self::Error> {
+        let [user, config, batch, user_ata, batch_vault, _system_program, token_program, remaining_accounts @ ..] =
+            accounts
+        else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        Ok(Self {
+            user,
+            config,
+            batch,
+            user_ata,
+            batch_vault,
+            token_program,
+            remaining_accounts,
+        })
+    }
+}
+
+pub struct PlaceBatchOrderInstructionData<'a> {
+    pub is_x: bool,
+    pub amount: u64,
+    pub min_out: u64,
+    pub batch_bump: [u8; 1],
+    pub proof: &'a [u8],
+}
+
+impl<'a> TryFrom<&'a [u8]> for PlaceBatchOrderInstructionData<'a> {
+    type Error = ProgramError;
+
+    fn try_from(data: &'a [u8]) -> Result<Self, Self::Error> {
+        const LEN: usize = size_of::<u8>() + size_of::<u64>() * 2 + size_of::<u8>();
+
+        // An allowlist proof may trail the order
+        if data.len() < LEN {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        Ok(Self {
+            is_x: data[0] == 1,
+            amount: u64::from_le_bytes(data[1..9].try_into().unwrap()),
+            min_out: u64::from_le_bytes(data[9..17].try_into().unwrap()),
+            batch_bump: [data[17]],
+            proof: &data[LEN..],
+        })
+    }
+}
+
+/// Queue an order selling x for y, or y for x, into the batch of the current epoch,
+/// opening the batch with the first order.
+///
+/// The order is refunded at settlement if its share of the output is below `min_out`.
+/// Each user can queue at most `MAX_OWNER_BATCH_ORDERS` orders in a batch, and locks
+/// `BATCH_ORDER_DEPOSIT` lamports in it per order until the order is claimed.
+/// The user opening the batch pays its rent, and gets it back once every order is
+/// claimed.
+pub struct PlaceBatchOrder<'a> {
+    pub accounts: PlaceBatchOrderAccounts<'a>,
+    pub instruction_data: PlaceBatchOrderInstructionData<'a>,
+}
+
+impl<'a> TryFrom<(&'a [u8], &'a [AccountInfo])> for PlaceBatchOrder<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountInfo])) -> Result<Self, Self::Error> {
+        let accounts = PlaceBatchOrderAccounts::try_from(accounts)?;
+        let instruction_data = PlaceBatchOrderInstructionData::try_from(data)?;
+
+        Ok(Self {
+            accounts,
+            instruction_data,
+        })
+    }
+}
+
+impl<'a> PlaceBatchOrder<'a> {
+    pub const DISCRIMINATOR: &'a u8 = &46;
+
+    pub fn process(&mut self) -> ProgramResult {
+        if !self.accounts.user.is_signer() {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        let mut config = Config::load_mut(self.accounts.config)?;
+
+        if config.state().ne(&(AmmState::Initialized as u8)) || config.batch_window() == 0 {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        check_allowlist(
+            &config,
+            self.accounts.user,
+            &mut self.accounts.remaining_accounts.iter(),
+            self.instruction_data.proof,
+        )?;
+
+        let mint = match self.instruction_data.is_x {
+            true => *config.mint_x(),
+            false => *config.mint_y(),
+        };
+
+        check_vaults(
+            self.accounts.batch,
+            self.accounts.token_program,
+            &[mint],
+            core::slice::from_ref(self.accounts.batch_vault),
+        )?;
+
+        let slot = Clock::get()?.slot;
+        let epoch = slot / config.batch_window();
+
+        if self.accounts.batch.data_is_empty() {
+            let epoch_binding = epoch.to_le_bytes();
+            let batch_seeds = [
+                Seed::from(b"batch"),
+                Seed::from(self.accounts.config.key()),
+                Seed::from(&epoch_binding),
+                Seed::from(&self.instruction_data.batch_bump),
+            ];
+
+            CreateAccount {
+                from: self.accounts.user,
+                to: self.accounts.batch,
+                lamports: Rent::get()?.minimum_balance(Batch::LEN),
+                space: Batch::LEN as u64,
+                owner: &crate::ID,
+            }
+            .invoke_signed(&[Signer::from(&batch_seeds)])?;
+
+            Batch::load_mut(self.accounts.batch)?.set_inner(
+                *self.accounts.config.key(),
+                *self.accounts.user.key(),
+                epoch,
+                (epoch + 1) * config.batch_window(),
+                self.instruction_data.batch_bump,
+            );
+            config.open_batch()?;
+        }
+
+        drop(config);
+
+        let mut batch = Batch::load_mut(self.accounts.batch)?;
+
+        if batch.config().ne(self.accounts.config.key()) || slot >= batch.end_slot() {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        batch.push(
+            *self.accounts.user.key(),
+            self.instruction_data.is_x,
+            self.instruction_data.amount,
+            self.instruction_data.min_out,
+        )?;
+
+        drop(batch);
+
+        SystemTransfer {
+            from: self.accounts.user,
+            to: self.accounts.batch,
+            lamports: BATCH_ORDER_DEPOSIT,
+        }
+        .invoke()?;
+
+        Transfer {
+            from: self.accounts.user_ata,
+            to: self.accounts.batch_vault,
+            authority: self.accounts.user,
+            amount: self.instruction_data.amount,
+        }
+        .invoke()
+    }
+}
@@ -0,0 +1,175 @@
+use crate::helpers::{check_vaults, spot_price};
+use crate::state::{AmmState, Config, RangeOrder};
+use core::mem::size_of;
+use pinocchio::{
+    account_info::AccountInfo,
+    instruction::{Seed, Signer},
+    program_error::ProgramError,
+    sysvars::{clock::Clock, rent::Rent, Sysvar},
+    ProgramResult,
+};
+use pinocchio_system::instructions::CreateAccount;
+use pinocchio_token::instructions::Transfer;
+use pinocchio_token::state::TokenAccount;
+
+pub struct PlaceRangeOrderAccounts<'a> {
+    pub owner: &'a AccountInfo,
+    pub config: &'a AccountInfo,
+    pub order: &'a AccountInfo,
+    pub owner_ata: &'a AccountInfo,
+    pub vault_x: &'a AccountInfo,
+    pub vault_y: &'a AccountInfo,
+    pub token_program: &'a AccountInfo,
+}
+
+impl<'a> TryFrom<&'a [AccountInfo]> for PlaceRangeOrderAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountInfo]) -> Result<Self, Self::Error> {
+        let [owner, config, order, owner_ata, vault_x, vault_y, _system_program, token_program] =
+            accounts
+        else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        Ok(Self {
+            owner,
+            config,
+            order,
+            owner_ata,
+            vault_x,
+            vault_y,
+            token_program,
+        })
+    }
+}
+
+pub struct PlaceRangeOrderInstructionData {
+    pub seed: u64,
+    pub is_x: bool,
+    pub amount: u64,
+    pub price: u128,
+    pub order_bump: [u8; 1],
+}
+
+impl<'a> TryFrom<&'a [u8]> for PlaceRangeOrderInstructionData {
+    type Error = ProgramError;
+
+    fn try_from(data: &'a [u8]) -> Result<Self, Self::Error> {
+        if data
+            .len()
+            .ne(&(size_of::<u64>() * 2 + size_of::<u128>() + size_of::<u8>() * 2))
+        {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        Ok(Self {
+            seed: u64::from_le_bytes(data[0..8].try_into().unwrap()),
+            is_x: data[8] == 1,
+            amount: u64::from_le_bytes(data[9..17].try_into().unwrap()),
+            price: u128::from_le_bytes(data[17..33].try_into().unwrap()),
+            order_bump: [data[33]],
+        })
+    }
+}
+
+/// Rest a limit order in the pool, selling x for y once the price of x in y rises to
+/// `price`, or y for x once it falls to `price`.
+pub struct PlaceRangeOrder<'a> {
+    pub accounts: PlaceRangeOrderAccounts<'a>,
+    pub instruction_data: PlaceRangeOrderInstructionData,
+}
+
+impl<'a> TryFrom<(&'a [u8], &'a [AccountInfo])> for PlaceRangeOrder<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountInfo])) -> Result<Self, Self::Error> {
+        let accounts = PlaceRangeOrderAccounts::try_from(accounts)?;
+        let instruction_data = PlaceRangeOrderInstructionData::try_from(data)?;
+
+        Ok(Self {
+            accounts,
+            instruction_data,
+        })
+    }
+}
+
+impl<'a> PlaceRangeOrder<'a> {
+    pub const DISCRIMINATOR: &'a u8 = &39;
+
+    pub fn process(&mut self) -> ProgramResult {
+        if !self.accounts.owner.is_signer() {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        let mut config = Config::load_mut(self.accounts.config)?;
+
+        if config.state().ne(&(AmmState::Initialized as u8)) {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        check_vaults(
+            self.accounts.config,
+            self.accounts.token_program,
+            &[*config.mint_x(), *config.mint_y()],
+            &[self.accounts.vault_x, self.accounts.vault_y],
+        )?;
+
+        let vault_x = unsafe { TokenAccount::from_account_info_unchecked(self.accounts.vault_x)? };
+        let vault_y = unsafe { TokenAccount::from_account_info_unchecked(self.accounts.vault_y)? };
+        let (reserve_x, reserve_y) = config.reserves(vault_x.amount(), vault_y.amount());
+        let (weight_x, weight_y) = config.weights(Clock::get()?.unix_timestamp);
+        let price = spot_price(reserve_x, weight_x, reserve_y, weight_y)?;
+
+        let order_seed_binding = self.instruction_data.seed.to_le_bytes();
+        let order_seeds = [
+            Seed::from(b"range_order"),
+            Seed::from(self.accounts.config.key()),
+            Seed::from(self.accounts.owner.key()),
+            Seed::from(&order_seed_binding),
+            Seed::from(&self.instruction_data.order_bump),
+        ];
+
+        CreateAccount {
+            from: self.accounts.owner,
+            to: self.accounts.order,
+            lamports: Rent::get()?.minimum_balance(RangeOrder::LEN),
+            space: RangeOrder::LEN as u64,
+            owner: &crate::ID,
+        }
+        .invoke_signed(&[Signer::from(&order_seeds)])?;
+
+        let mut order = RangeOrder::load_mut(self.accounts.order)?;
+        order.set_inner(
+            *self.accounts.config.key(),
+            *self.accounts.owner.key(),
+            self.instruction_data.is_x,
+            self.instruction_data.price,
+            self.instruction_data.amount,
+            self.instruction_data.order_bump,
+        )?;
+
+        // An order already crossed would fill on the next swap below the market price
+        if order.is_crossed(price) {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        config.add_orders(self.instruction_data.is_x, self.instruction_data.amount)?;
+
+        drop(order);
+        drop(config);
+
+        let vault = match self.instruction_data.is_x {
+            true => self.accounts.vault_x,
+            false => self.accounts.vault_y,
+        };
+
+        Transfer {
+            from: self.accounts.owner_ata,
+            to: vault,
+            authority: self.accounts.owner,
+            amount: self.instruction_data.amount,
+        }
+        .invoke()
+    }
+}
@@ -0,0 +1,102 @@
+use crate::helpers::close_account;
+use crate::instructions::{Swap, SwapAccounts, SwapInstructionData};
+use crate::math::{hashv, HASH_LEN};
+use crate::state::SwapCommit;
+use pinocchio::{
+    account_info::AccountInfo,
+    program_error::ProgramError,
+    sysvars::{clock::Clock, Sysvar},
+    ProgramResult,
+};
+
+/// Execute a committed swap, then close the commit to the user.
+///
+/// The accounts are the commit followed by the accounts of `Swap`, and the data is the
+/// salt followed by the version 2 data of `Swap`. The commit hashes the swap data with
+/// every account of `Swap`, the referrer, range orders and hook included, so the reveal
+/// cannot point the swap elsewhere.
+pub struct RevealSwap<'a> {
+    pub commit: &'a AccountInfo,
+    pub salt: &'a [u8],
+    pub swap_data: &'a [u8],
+    pub swap: Swap<'a>,
+}
+
+impl<'a> TryFrom<(&'a [u8], &'a [AccountInfo])> for RevealSwap<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountInfo])) -> Result<Self, Self::Error> {
+        let [commit, swap_accounts @ ..] = accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        if data.len() < HASH_LEN {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+        let (salt, swap_data) = data.split_at(HASH_LEN);
+
+        let swap = Swap {
+            accounts: SwapAccounts::try_from(swap_accounts)?,
+            instruction_data: SwapInstructionData::try_from_v2(swap_data)?,
+        };
+
+        Ok(Self {
+            commit,
+            salt,
+            swap_data,
+            swap,
+        })
+    }
+}
+
+impl<'a> RevealSwap<'a> {
+    pub const DISCRIMINATOR: &'a u8 = &44;
+
+    pub fn process(&mut self) -> ProgramResult {
+        let user = self.swap.accounts.user;
+
+        if !user.is_signer() {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        let commit = SwapCommit::load(self.commit)?;
+
+        if commit.config().ne(self.swap.accounts.config.key()) || commit.user().ne(user.key()) {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        if Clock::get()?.slot < commit.slot().saturating_add(commit.reveal_delay()) {
+            return Err(ProgramError::InvalidArgument);
+        }
+
+        let accounts = &self.swap.accounts;
+        let remaining_accounts = accounts
+            .remaining_accounts
+            .iter()
+            .fold([0; HASH_LEN], |hash, account| {
+                hashv(&[&hash, account.key()])
+            });
+        let hash = hashv(&[
+            self.swap_data,
+            user.key(),
+            accounts.user_x.key(),
+            accounts.user_y.key(),
+            accounts.vault_x.key(),
+            accounts.vault_y.key(),
+            accounts.config.key(),
+            accounts.token_program.key(),
+            &remaining_accounts,
+            self.salt,
+        ]);
+
+        if hash.ne(commit.hash()) {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        drop(commit);
+
+        self.swap.process()?;
+
+        close_account(self.commit, user)
+    }
+}
@@ -0,0 +1,80 @@
+use crate::helpers::check_vaults;
+use crate::instructions::{LaunchTradeAccounts, LaunchTradeInstructionData};
+use crate::state::{Launch, LaunchState};
+use pinocchio::{
+    account_info::AccountInfo,
+    instruction::{Seed, Signer},
+    program_error::ProgramError,
+    ProgramResult,
+};
+use pinocchio_token::instructions::Transfer;
+
+/// Sell `amount` launched tokens back to the curve for quote tokens.
+///
+/// Takes the same accounts as `BuyLaunch`.
+pub struct SellLaunch<'a> {
+    pub accounts: LaunchTradeAccounts<'a>,
+    pub instruction_data: LaunchTradeInstructionData,
+}
+
+impl<'a> TryFrom<(&'a [u8], &'a [AccountInfo])> for SellLaunch<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountInfo])) -> Result<Self, Self::Error> {
+        let accounts = LaunchTradeAccounts::try_from(accounts)?;
+        let instruction_data = LaunchTradeInstructionData::try_from(data)?;
+
+        Ok(Self {
+            accounts,
+            instruction_data,
+        })
+    }
+}
+
+impl<'a> SellLaunch<'a> {
+    pub const DISCRIMINATOR: &'a u8 = &37;
+
+    pub fn process(&mut self) -> ProgramResult {
+        if !self.accounts.user.is_signer() {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        let mut launch = Launch::load_mut(self.accounts.launch)?;
+
+        if launch.state().ne(&(LaunchState::Trading as u8)) {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        check_vaults(
+            self.accounts.launch,
+            self.accounts.token_program,
+            &[*launch.quote_mint(), *launch.mint()],
+            &[self.accounts.quote_vault, self.accounts.token_vault],
+        )?;
+
+        let result = launch.sell(self.instruction_data.amount, self.instruction_data.min)?;
+
+        let mint = *launch.mint();
+        let bump = launch.bump();
+
+        drop(launch);
+
+        Transfer {
+            from: self.accounts.user_token_ata,
+            to: self.accounts.token_vault,
+            authority: self.accounts.user,
+            amount: result.deposit,
+        }
+        .invoke()?;
+
+        let launch_seeds = [Seed::from(b"launch"), Seed::from(&mint), Seed::from(&bump)];
+
+        Transfer {
+            from: self.accounts.quote_vault,
+            to: self.accounts.user_quote_ata,
+            authority: self.accounts.launch,
+            amount: result.withdraw,
+        }
+        .invoke_signed(&[Signer::from(&launch_seeds)])
+    }
+}
@@ -0,0 +1,73 @@
+use crate::helpers::check_authority;
+use crate::state::Config;
+use core::mem::size_of;
+use pinocchio::{account_info::AccountInfo, program_error::ProgramError, ProgramResult};
+
+pub struct SetBatchWindowAccounts<'a> {
+    pub authority: &'a AccountInfo,
+    pub config: &'a AccountInfo,
+}
+
+impl<'a> TryFrom<&'a [AccountInfo]> for SetBatchWindowAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountInfo]) -> Result<Self, Self::Error> {
+        let [authority, config] = accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        Ok(Self { authority, config })
+    }
+}
+
+pub struct SetBatchWindowInstructionData {
+    pub batch_window: u64,
+}
+
+impl<'a> TryFrom<&'a [u8]> for SetBatchWindowInstructionData {
+    type Error = ProgramError;
+
+    fn try_from(data: &'a [u8]) -> Result<Self, Self::Error> {
+        if data.len().ne(&size_of::<u64>()) {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let batch_window = u64::from_le_bytes(data[0..8].try_into().unwrap());
+
+        Ok(Self { batch_window })
+    }
+}
+
+/// Set the slots each batch of the pool takes orders for, next to continuous `Swap`.
+///
+/// `0` turns batch mode off, batches already open can still be settled.
+pub struct SetBatchWindow<'a> {
+    pub accounts: SetBatchWindowAccounts<'a>,
+    pub instruction_data: SetBatchWindowInstructionData,
+}
+
+impl<'a> TryFrom<(&'a [u8], &'a [AccountInfo])> for SetBatchWindow<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountInfo])) -> Result<Self, Self::Error> {
+        let accounts = SetBatchWindowAccounts::try_from(accounts)?;
+        let instruction_data = SetBatchWindowInstructionData::try_from(data)?;
+
+        Ok(Self {
+            accounts,
+            instruction_data,
+        })
+    }
+}
+
+impl<'a> SetBatchWindow<'a> {
+    pub const DISCRIMINATOR: &'a u8 = &45;
+
+    pub fn process(&mut self) -> ProgramResult {
+        let mut config = Config::load_mut(self.accounts.config)?;
+
+        check_authority(&config, self.accounts.authority)?;
+
+        config.set_batch_window(self.instruction_data.batch_window)
+    }
+}
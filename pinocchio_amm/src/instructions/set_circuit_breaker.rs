@@ -0,0 +1,74 @@
+use crate::helpers::check_authority;
+use crate::state::Config;
+use core::mem::size_of;
+use pinocchio::{account_info::AccountInfo, program_error::ProgramError, ProgramResult};
+
+pub struct SetCircuitBreakerAccounts<'a> {
+    pub authority: &'a AccountInfo,
+    pub config: &'a AccountInfo,
+}
+
+impl<'a> TryFrom<&'a [AccountInfo]> for SetCircuitBreakerAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountInfo]) -> Result<Self, Self::Error> {
+        let [authority, config] = accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        Ok(Self { authority, config })
+    }
+}
+
+pub struct SetCircuitBreakerInstructionData {
+    pub max_slot_move_bps: u16,
+}
+
+impl<'a> TryFrom<&'a [u8]> for SetCircuitBreakerInstructionData {
+    type Error = ProgramError;
+
+    fn try_from(data: &'a [u8]) -> Result<Self, Self::Error> {
+        if data.len().ne(&size_of::<u16>()) {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let max_slot_move_bps = u16::from_le_bytes(data[0..2].try_into().unwrap());
+
+        Ok(Self { max_slot_move_bps })
+    }
+}
+
+/// Set how far from its price at the start of a slot the price may move within the
+/// slot, in basis points, `0` disables it.
+pub struct SetCircuitBreaker<'a> {
+    pub accounts: SetCircuitBreakerAccounts<'a>,
+    pub instruction_data: SetCircuitBreakerInstructionData,
+}
+
+impl<'a> TryFrom<(&'a [u8], &'a [AccountInfo])> for SetCircuitBreaker<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountInfo])) -> Result<Self, Self::Error> {
+        let accounts = SetCircuitBreakerAccounts::try_from(accounts)?;
+        let instruction_data = SetCircuitBreakerInstructionData::try_from(data)?;
+
+        Ok(Self {
+            accounts,
+            instruction_data,
+        })
+    }
+}
+
+impl<'a> SetCircuitBreaker<'a> {
+    pub const DISCRIMINATOR: &'a u8 = &5;
+
+    pub fn process(&mut self) -> ProgramResult {
+        let mut config = Config::load_mut(self.accounts.config)?;
+
+        check_authority(&config, self.accounts.authority)?;
+
+        config.set_max_slot_move_bps(self.instruction_data.max_slot_move_bps);
+
+        Ok(())
+    }
+}
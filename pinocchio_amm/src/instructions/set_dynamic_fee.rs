@@ -0,0 +1,86 @@
+use crate::helpers::check_authority;
+use crate::state::Config;
+use core::mem::size_of;
+use pinocchio::{account_info::AccountInfo, program_error::ProgramError, ProgramResult};
+
+pub struct SetDynamicFeeAccounts<'a> {
+    pub authority: &'a AccountInfo,
+    pub config: &'a AccountInfo,
+}
+
+impl<'a> TryFrom<&'a [AccountInfo]> for SetDynamicFeeAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountInfo]) -> Result<Self, Self::Error> {
+        let [authority, config] = accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        Ok(Self { authority, config })
+    }
+}
+
+pub struct SetDynamicFeeInstructionData {
+    pub max_fee: u16,
+    pub volatility_fee_factor: u16,
+    pub volatility_half_life: i64,
+}
+
+impl<'a> TryFrom<&'a [u8]> for SetDynamicFeeInstructionData {
+    type Error = ProgramError;
+
+    fn try_from(data: &'a [u8]) -> Result<Self, Self::Error> {
+        if data
+            .len()
+            .ne(&(size_of::<u16>() + size_of::<u16>() + size_of::<i64>()))
+        {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let max_fee = u16::from_le_bytes(data[0..2].try_into().unwrap());
+        let volatility_fee_factor = u16::from_le_bytes(data[2..4].try_into().unwrap());
+        let volatility_half_life = i64::from_le_bytes(data[4..12].try_into().unwrap());
+
+        Ok(Self {
+            max_fee,
+            volatility_fee_factor,
+            volatility_half_life,
+        })
+    }
+}
+
+/// Configure the volatility-adaptive fee, a `max_fee` of `0` disables it.
+pub struct SetDynamicFee<'a> {
+    pub accounts: SetDynamicFeeAccounts<'a>,
+    pub instruction_data: SetDynamicFeeInstructionData,
+}
+
+impl<'a> TryFrom<(&'a [u8], &'a [AccountInfo])> for SetDynamicFee<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountInfo])) -> Result<Self, Self::Error> {
+        let accounts = SetDynamicFeeAccounts::try_from(accounts)?;
+        let instruction_data = SetDynamicFeeInstructionData::try_from(data)?;
+
+        Ok(Self {
+            accounts,
+            instruction_data,
+        })
+    }
+}
+
+impl<'a> SetDynamicFee<'a> {
+    pub const DISCRIMINATOR: &'a u8 = &6;
+
+    pub fn process(&mut self) -> ProgramResult {
+        let mut config = Config::load_mut(self.accounts.config)?;
+
+        check_authority(&config, self.accounts.authority)?;
+
+        config.set_dynamic_fee(
+            self.instruction_data.max_fee,
+            self.instruction_data.volatility_fee_factor,
+            self.instruction_data.volatility_half_life,
+        )
+    }
+}
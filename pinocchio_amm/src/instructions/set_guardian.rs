@@ -0,0 +1,73 @@
+use crate::helpers::check_authority;
+use crate::state::Config;
+use core::mem::size_of;
+use pinocchio::{account_info::AccountInfo, program_error::ProgramError, ProgramResult};
+
+pub struct SetGuardianAccounts<'a> {
+    pub authority: &'a AccountInfo,
+    pub config: &'a AccountInfo,
+}
+
+impl<'a> TryFrom<&'a [AccountInfo]> for SetGuardianAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountInfo]) -> Result<Self, Self::Error> {
+        let [authority, config] = accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        Ok(Self { authority, config })
+    }
+}
+
+pub struct SetGuardianInstructionData {
+    pub guardian: [u8; 32],
+}
+
+impl<'a> TryFrom<&'a [u8]> for SetGuardianInstructionData {
+    type Error = ProgramError;
+
+    fn try_from(data: &'a [u8]) -> Result<Self, Self::Error> {
+        if data.len().ne(&size_of::<[u8; 32]>()) {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let guardian: [u8; 32] = data[0..32].try_into().unwrap();
+
+        Ok(Self { guardian })
+    }
+}
+
+/// Set, or clear with the default pubkey, the guardian allowed to `Pause` the pool.
+pub struct SetGuardian<'a> {
+    pub accounts: SetGuardianAccounts<'a>,
+    pub instruction_data: SetGuardianInstructionData,
+}
+
+impl<'a> TryFrom<(&'a [u8], &'a [AccountInfo])> for SetGuardian<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountInfo])) -> Result<Self, Self::Error> {
+        let accounts = SetGuardianAccounts::try_from(accounts)?;
+        let instruction_data = SetGuardianInstructionData::try_from(data)?;
+
+        Ok(Self {
+            accounts,
+            instruction_data,
+        })
+    }
+}
+
+impl<'a> SetGuardian<'a> {
+    pub const DISCRIMINATOR: &'a u8 = &24;
+
+    pub fn process(&mut self) -> ProgramResult {
+        let mut config = Config::load_mut(self.accounts.config)?;
+
+        check_authority(&config, self.accounts.authority)?;
+
+        config.set_guardian(self.instruction_data.guardian);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,86 @@
+use crate::helpers::check_authority;
+use crate::state::Config;
+use core::mem::size_of;
+use pinocchio::{account_info::AccountInfo, program_error::ProgramError, ProgramResult};
+
+pub struct SetOracleAccounts<'a> {
+    pub authority: &'a AccountInfo,
+    pub config: &'a AccountInfo,
+}
+
+impl<'a> TryFrom<&'a [AccountInfo]> for SetOracleAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountInfo]) -> Result<Self, Self::Error> {
+        let [authority, config] = accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        Ok(Self { authority, config })
+    }
+}
+
+pub struct SetOracleInstructionData {
+    pub oracle: [u8; 32],
+    pub max_deviation_bps: u16,
+    pub max_age: i64,
+}
+
+impl<'a> TryFrom<&'a [u8]> for SetOracleInstructionData {
+    type Error = ProgramError;
+
+    fn try_from(data: &'a [u8]) -> Result<Self, Self::Error> {
+        if data
+            .len()
+            .ne(&(size_of::<[u8; 32]>() + size_of::<u16>() + size_of::<i64>()))
+        {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let oracle: [u8; 32] = data[0..32].try_into().unwrap();
+        let max_deviation_bps = u16::from_le_bytes(data[32..34].try_into().unwrap());
+        let max_age = i64::from_le_bytes(data[34..42].try_into().unwrap());
+
+        Ok(Self {
+            oracle,
+            max_deviation_bps,
+            max_age,
+        })
+    }
+}
+
+/// Set, or clear with the default pubkey, the price feed guarding `Swap`.
+pub struct SetOracle<'a> {
+    pub accounts: SetOracleAccounts<'a>,
+    pub instruction_data: SetOracleInstructionData,
+}
+
+impl<'a> TryFrom<(&'a [u8], &'a [AccountInfo])> for SetOracle<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountInfo])) -> Result<Self, Self::Error> {
+        let accounts = SetOracleAccounts::try_from(accounts)?;
+        let instruction_data = SetOracleInstructionData::try_from(data)?;
+
+        Ok(Self {
+            accounts,
+            instruction_data,
+        })
+    }
+}
+
+impl<'a> SetOracle<'a> {
+    pub const DISCRIMINATOR: &'a u8 = &4;
+
+    pub fn process(&mut self) -> ProgramResult {
+        let mut config = Config::load_mut(self.accounts.config)?;
+
+        check_authority(&config, self.accounts.authority)?;
+
+        config.set_oracle(
+            self.instruction_data.oracle,
+            self.instruction_data.max_deviation_bps,
+            self.instruction_data.max_age,
+        )
+    }
+}
@@ -0,0 +1,73 @@
+use crate::helpers::check_authority;
+use crate::state::Config;
+use core::mem::size_of;
+use pinocchio::{account_info::AccountInfo, program_error::ProgramError, ProgramResult};
+
+pub struct SetReferralFeeAccounts<'a> {
+    pub authority: &'a AccountInfo,
+    pub config: &'a AccountInfo,
+}
+
+impl<'a> TryFrom<&'a [AccountInfo]> for SetReferralFeeAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountInfo]) -> Result<Self, Self::Error> {
+        let [authority, config] = accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        Ok(Self { authority, config })
+    }
+}
+
+pub struct SetReferralFeeInstructionData {
+    pub max_referral_bps: u16,
+}
+
+impl<'a> TryFrom<&'a [u8]> for SetReferralFeeInstructionData {
+    type Error = ProgramError;
+
+    fn try_from(data: &'a [u8]) -> Result<Self, Self::Error> {
+        if data.len().ne(&size_of::<u16>()) {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let max_referral_bps = u16::from_le_bytes(data[0..2].try_into().unwrap());
+
+        Ok(Self { max_referral_bps })
+    }
+}
+
+/// Set the largest share of the swap fee, in basis points, a referrer can claim on `Swap`.
+///
+/// `0` turns referral payouts off.
+pub struct SetReferralFee<'a> {
+    pub accounts: SetReferralFeeAccounts<'a>,
+    pub instruction_data: SetReferralFeeInstructionData,
+}
+
+impl<'a> TryFrom<(&'a [u8], &'a [AccountInfo])> for SetReferralFee<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountInfo])) -> Result<Self, Self::Error> {
+        let accounts = SetReferralFeeAccounts::try_from(accounts)?;
+        let instruction_data = SetReferralFeeInstructionData::try_from(data)?;
+
+        Ok(Self {
+            accounts,
+            instruction_data,
+        })
+    }
+}
+
+impl<'a> SetReferralFee<'a> {
+    pub const DISCRIMINATOR: &'a u8 = &18;
+
+    pub fn process(&mut self) -> ProgramResult {
+        let mut config = Config::load_mut(self.accounts.config)?;
+
+        check_authority(&config, self.accounts.authority)?;
+
+        config.set_max_referral_bps(self.instruction_data.max_referral_bps)
+    }
+}
@@ -0,0 +1,72 @@
+use crate::helpers::check_authority;
+use crate::state::{AmmState, Config};
+use pinocchio::{account_info::AccountInfo, program_error::ProgramError, ProgramResult};
+
+pub struct SetStateAccounts<'a> {
+    pub authority: &'a AccountInfo,
+    pub config: &'a AccountInfo,
+}
+
+impl<'a> TryFrom<&'a [AccountInfo]> for SetStateAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountInfo]) -> Result<Self, Self::Error> {
+        let [authority, config] = accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        Ok(Self { authority, config })
+    }
+}
+
+pub struct SetStateInstructionData {
+    pub state: u8,
+}
+
+impl<'a> TryFrom<&'a [u8]> for SetStateInstructionData {
+    type Error = ProgramError;
+
+    fn try_from(data: &'a [u8]) -> Result<Self, Self::Error> {
+        let [state] = data else {
+            return Err(ProgramError::InvalidInstructionData);
+        };
+
+        if state.eq(&(AmmState::Uninitialized as u8)) {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        Ok(Self { state: *state })
+    }
+}
+
+/// Move the pool to any initialized state, which is how the authority unpauses it.
+pub struct SetState<'a> {
+    pub accounts: SetStateAccounts<'a>,
+    pub instruction_data: SetStateInstructionData,
+}
+
+impl<'a> TryFrom<(&'a [u8], &'a [AccountInfo])> for SetState<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountInfo])) -> Result<Self, Self::Error> {
+        let accounts = SetStateAccounts::try_from(accounts)?;
+        let instruction_data = SetStateInstructionData::try_from(data)?;
+
+        Ok(Self {
+            accounts,
+            instruction_data,
+        })
+    }
+}
+
+impl<'a> SetState<'a> {
+    pub const DISCRIMINATOR: &'a u8 = &26;
+
+    pub fn process(&mut self) -> ProgramResult {
+        let mut config = Config::load_mut(self.accounts.config)?;
+
+        check_authority(&config, self.accounts.authority)?;
+
+        config.set_state(self.instruction_data.state)
+    }
+}
@@ -0,0 +1,76 @@
+use crate::helpers::check_authority;
+use crate::state::Config;
+use pinocchio::{account_info::AccountInfo, program_error::ProgramError, ProgramResult};
+
+pub struct SetSwapHookAccounts<'a> {
+    pub authority: &'a AccountInfo,
+    pub config: &'a AccountInfo,
+}
+
+impl<'a> TryFrom<&'a [AccountInfo]> for SetSwapHookAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountInfo]) -> Result<Self, Self::Error> {
+        let [authority, config] = accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        Ok(Self { authority, config })
+    }
+}
+
+pub struct SetSwapHookInstructionData {
+    pub swap_hook: [u8; 32],
+}
+
+impl<'a> TryFrom<&'a [u8]> for SetSwapHookInstructionData {
+    type Error = ProgramError;
+
+    fn try_from(data: &'a [u8]) -> Result<Self, Self::Error> {
+        let swap_hook: [u8; 32] = data
+            .try_into()
+            .map_err(|_| ProgramError::InvalidInstructionData)?;
+
+        Ok(Self { swap_hook })
+    }
+}
+
+/// Set, or clear with the default pubkey, the program `Swap` calls after every trade, see
+/// `SwapHook`.
+///
+/// The hook program and its signer follow the other remaining accounts of `Swap`, and
+/// swaps that leave them out fail, so traders cannot skip the hook.
+///
+/// A hook that fails reverts the swap. The pool cannot make the hook best effort, as the
+/// runtime aborts the whole transaction on a failed CPI and the caller never sees the
+/// error. To keep trading through a broken hook, the authority clears it.
+pub struct SetSwapHook<'a> {
+    pub accounts: SetSwapHookAccounts<'a>,
+    pub instruction_data: SetSwapHookInstructionData,
+}
+
+impl<'a> TryFrom<(&'a [u8], &'a [AccountInfo])> for SetSwapHook<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountInfo])) -> Result<Self, Self::Error> {
+        let accounts = SetSwapHookAccounts::try_from(accounts)?;
+        let instruction_data = SetSwapHookInstructionData::try_from(data)?;
+
+        Ok(Self {
+            accounts,
+            instruction_data,
+        })
+    }
+}
+
+impl<'a> SetSwapHook<'a> {
+    pub const DISCRIMINATOR: &'a u8 = &49;
+
+    pub fn process(&mut self) -> ProgramResult {
+        let mut config = Config::load_mut(self.accounts.config)?;
+
+        check_authority(&config, self.accounts.authority)?;
+
+        config.set_swap_hook(self.instruction_data.swap_hook)
+    }
+}
@@ -0,0 +1,111 @@
+use crate::errors::AmmError;
+use crate::helpers::check_authority;
+use crate::state::Config;
+use core::mem::size_of;
+use pinocchio::pubkey::find_program_address;
+use pinocchio::{account_info::AccountInfo, program_error::ProgramError, ProgramResult};
+use pinocchio_token::state::Mint;
+
+pub struct SetWeightsAccounts<'a> {
+    pub authority: &'a AccountInfo,
+    pub config: &'a AccountInfo,
+    pub mint_lp: &'a AccountInfo,
+}
+
+impl<'a> TryFrom<&'a [AccountInfo]> for SetWeightsAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountInfo]) -> Result<Self, Self::Error> {
+        let [authority, config, mint_lp] = accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        Ok(Self {
+            authority,
+            config,
+            mint_lp,
+        })
+    }
+}
+
+pub struct SetWeightsInstructionData {
+    pub start_weight_x: u16,
+    pub end_weight_x: u16,
+    pub start_time: i64,
+    pub end_time: i64,
+}
+
+impl<'a> TryFrom<&'a [u8]> for SetWeightsInstructionData {
+    type Error = ProgramError;
+
+    fn try_from(data: &'a [u8]) -> Result<Self, Self::Error> {
+        if data
+            .len()
+            .ne(&(size_of::<u16>() * 2 + size_of::<i64>() * 2))
+        {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let start_weight_x = u16::from_le_bytes(data[0..2].try_into().unwrap());
+        let end_weight_x = u16::from_le_bytes(data[2..4].try_into().unwrap());
+        let start_time = i64::from_le_bytes(data[4..12].try_into().unwrap());
+        let end_time = i64::from_le_bytes(data[12..20].try_into().unwrap());
+
+        Ok(Self {
+            start_weight_x,
+            end_weight_x,
+            start_time,
+            end_time,
+        })
+    }
+}
+
+/// Turn an empty pool into a liquidity bootstrapping pool: a weighted pool whose weights
+/// move from the start to the end weights between `start_time` and `end_time`.
+pub struct SetWeights<'a> {
+    pub accounts: SetWeightsAccounts<'a>,
+    pub instruction_data: SetWeightsInstructionData,
+}
+
+impl<'a> TryFrom<(&'a [u8], &'a [AccountInfo])> for SetWeights<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountInfo])) -> Result<Self, Self::Error> {
+        let accounts = SetWeightsAccounts::try_from(accounts)?;
+        let instruction_data = SetWeightsInstructionData::try_from(data)?;
+
+        Ok(Self {
+            accounts,
+            instruction_data,
+        })
+    }
+}
+
+impl<'a> SetWeights<'a> {
+    pub const DISCRIMINATOR: &'a u8 = &7;
+
+    pub fn process(&mut self) -> ProgramResult {
+        let mut config = Config::load_mut(self.accounts.config)?;
+
+        check_authority(&config, self.accounts.authority)?;
+
+        // The curve can only change before any liquidity is priced on it
+        let (mint_lp, _) =
+            find_program_address(&[b"mint_lp", self.accounts.config.key()], &crate::ID);
+
+        if mint_lp.ne(self.accounts.mint_lp.key()) {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        if Mint::from_account_info(self.accounts.mint_lp)?.supply() != 0 {
+            return Err(AmmError::PoolNotEmpty.into());
+        }
+
+        config.set_weights(
+            self.instruction_data.start_weight_x,
+            self.instruction_data.end_weight_x,
+            self.instruction_data.start_time,
+            self.instruction_data.end_time,
+        )
+    }
+}
@@ -0,0 +1,237 @@
+use crate::helpers::{check_price, check_vaults, deviation_bps, read_oracle, spot_price};
+use crate::math::constant_product;
+use crate::state::{AmmState, Batch, BatchState, Config, CurveType};
+use pinocchio::{
+    account_info::AccountInfo,
+    instruction::{Seed, Signer},
+    program_error::ProgramError,
+    sysvars::{clock::Clock, Sysvar},
+    ProgramResult,
+};
+use pinocchio_token::instructions::Transfer;
+use pinocchio_token::state::TokenAccount;
+
+pub struct SettleBatchAccounts<'a> {
+    pub config: &'a AccountInfo,
+    pub batch: &'a AccountInfo,
+    pub batch_vault_x: &'a AccountInfo,
+    pub batch_vault_y: &'a AccountInfo,
+    pub vault_x: &'a AccountInfo,
+    pub vault_y: &'a AccountInfo,
+    pub token_program: &'a AccountInfo,
+    pub remaining_accounts: &'a [AccountInfo],
+}
+
+impl<'a> TryFrom<&'a [AccountInfo]> for SettleBatchAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountInfo]) -> Result<Self, Self::Error> {
+        let [config, batch, batch_vault_x, batch_vault_y, vault_x, vault_y, token_program, remaining_accounts @ ..] =
+            accounts
+        else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        Ok(Self {
+            config,
+            batch,
+            batch_vault_x,
+            batch_vault_y,
+            vault_x,
+            vault_y,
+            token_program,
+            remaining_accounts,
+        })
+    }
+}
+
+/// Clear every order of a closed batch at one price, anyone can settle.
+///
+/// Each order pays the pool fee on what it sells, the rest of both sides is matched and
+/// only the imbalance trades against the curve, see `constant_product::clear_batch`.
+/// Orders the clearing price leaves short of their `min_out` are refunded, and the
+/// batch clears again without them.
+///
+/// The price is guarded like a swap's, against the pool oracle passed as the remaining
+/// account when the pool has one, and against the circuit breaker.
+pub struct SettleBatch<'a> {
+    pub accounts: SettleBatchAccounts<'a>,
+}
+
+impl<'a> TryFrom<(&'a [u8], &'a [AccountInfo])> for SettleBatch<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountInfo])) -> Result<Self, Self::Error> {
+        if !data.is_empty() {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let accounts = SettleBatchAccounts::try_from(accounts)?;
+
+        Ok(Self { accounts })
+    }
+}
+
+impl<'a> SettleBatch<'a> {
+    pub const DISCRIMINATOR: &'a u8 = &47;
+
+    pub fn process(&mut self) -> ProgramResult {
+        let mut config = Config::load_mut(self.accounts.config)?;
+
+        if config.state().ne(&(AmmState::Initialized as u8))
+            || config.curve().ne(&(CurveType::ConstantProduct as u8))
+        {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        let mut batch = Batch::load_mut(self.accounts.batch)?;
+        let clock = Clock::get()?;
+
+        if batch.config().ne(self.accounts.config.key())
+            || batch.state().ne(&(BatchState::Open as u8))
+            || clock.slot < batch.end_slot()
+        {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        let mints = [*config.mint_x(), *config.mint_y()];
+        check_vaults(
+            self.accounts.config,
+            self.accounts.token_program,
+            &mints,
+            &[self.accounts.vault_x, self.accounts.vault_y],
+        )?;
+        check_vaults(
+            self.accounts.batch,
+            self.accounts.token_program,
+            &mints,
+            &[self.accounts.batch_vault_x, self.accounts.batch_vault_y],
+        )?;
+
+        let vault_x = unsafe { TokenAccount::from_account_info_unchecked(self.accounts.vault_x)? };
+        let vault_y = unsafe { TokenAccount::from_account_info_unchecked(self.accounts.vault_y)? };
+        let (reserve_x, reserve_y) = config.reserves(vault_x.amount(), vault_y.amount());
+        let price_before = spot_price(reserve_x, 5_000, reserve_y, 5_000)?;
+
+        if config.max_fee() != 0 {
+            config.decay_volatility(clock.unix_timestamp);
+        }
+        let fee = config.effective_fee();
+
+        // Every refund moves the clearing price, at most once per order
+        let (in_x, in_y, fee_x, fee_y, result) = loop {
+            let (in_x, in_y) = (batch.in_x(), batch.in_y());
+            let fee_x = (in_x as u128 * fee as u128).div_ceil(10_000) as u64;
+            let fee_y = (in_y as u128 * fee as u128).div_ceil(10_000) as u64;
+
+            let result =
+                constant_product::clear_batch(reserve_x, reserve_y, in_x - fee_x, in_y - fee_y)?;
+
+            if !batch.refund_unmet(result.out_x, result.out_y) {
+                break (in_x, in_y, fee_x, fee_y, result);
+            }
+        };
+
+        // The fees go to the LP, the share of positions is set aside
+        let position_fee_x = config.accrue_fee(true, fee_x)?;
+        let position_fee_y = config.accrue_fee(false, fee_y)?;
+
+        let (Some(x), Some(y)) = (
+            reserve_x
+                .checked_add(in_x)
+                .and_then(|x| x.checked_sub(result.out_x))
+                .and_then(|x| x.checked_sub(position_fee_x)),
+            reserve_y
+                .checked_add(in_y)
+                .and_then(|y| y.checked_sub(result.out_y))
+                .and_then(|y| y.checked_sub(position_fee_y)),
+        ) else {
+            return Err(ProgramError::ArithmeticOverflow);
+        };
+        let price_after = spot_price(x, 5_000, y, 5_000)?;
+
+        // Batches move the price like swaps do
+        let oracle_price = read_oracle(
+            &config,
+            &mut self.accounts.remaining_accounts.iter(),
+            clock.unix_timestamp,
+        )?;
+        check_price(
+            &mut config,
+            oracle_price,
+            clock.slot,
+            price_before,
+            price_after,
+        )?;
+
+        if config.max_fee() != 0 {
+            let move_bps = deviation_bps(price_after, price_before)?;
+            config.accumulate_volatility(u64::try_from(move_bps).unwrap_or(u64::MAX));
+        }
+
+        config.record_swap(true, in_x, result.out_y, fee_x);
+        config.record_swap(false, in_y, result.out_x, fee_y);
+        config.record_trade(clock.slot, clock.unix_timestamp, Some(price_after));
+
+        batch.settle(result.out_x, result.out_y);
+        config.close_batch()?;
+
+        let seed_binding = config.seed().to_le_bytes();
+        let config_bump = config.config_bump();
+        let epoch_binding = batch.epoch().to_le_bytes();
+        let batch_bump = batch.bump();
+
+        drop(batch);
+        drop(config);
+
+        let config_seeds = [
+            Seed::from(b"config"),
+            Seed::from(&seed_binding),
+            Seed::from(&mints[0]),
+            Seed::from(&mints[1]),
+            Seed::from(&config_bump),
+        ];
+        let batch_seeds = [
+            Seed::from(b"batch"),
+            Seed::from(self.accounts.config.key()),
+            Seed::from(&epoch_binding),
+            Seed::from(&batch_bump),
+        ];
+
+        // Each side either pays the pool its surplus or takes what it lacks from it
+        for (batch_vault, vault, amount_in, amount_out) in [
+            (
+                self.accounts.batch_vault_x,
+                self.accounts.vault_x,
+                in_x,
+                result.out_x,
+            ),
+            (
+                self.accounts.batch_vault_y,
+                self.accounts.vault_y,
+                in_y,
+                result.out_y,
+            ),
+        ] {
+            match amount_in.cmp(&amount_out) {
+                core::cmp::Ordering::Greater => Transfer {
+                    from: batch_vault,
+                    to: vault,
+                    authority: self.accounts.batch,
+                    amount: amount_in - amount_out,
+                }
+                .invoke_signed(&[Signer::from(&batch_seeds)])?,
+                core::cmp::Ordering::Less => Transfer {
+                    from: vault,
+                    to: batch_vault,
+                    authority: self.accounts.config,
+                    amount: amount_out - amount_in,
+                }
+                .invoke_signed(&[Signer::from(&config_seeds)])?,
+                core::cmp::Ordering::Equal => {}
+            }
+        }
+
+        Ok(())
+    }
+}
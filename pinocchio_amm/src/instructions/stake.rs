@@ -0,0 +1,150 @@
+use crate::helpers::check_vaults;
+use crate::state::{Farm, UserStake};
+use core::mem::size_of;
+use pinocchio::{
+    account_info::AccountInfo,
+    instruction::{Seed, Signer},
+    program_error::ProgramError,
+    sysvars::{clock::Clock, rent::Rent, Sysvar},
+    ProgramResult,
+};
+use pinocchio_system::instructions::CreateAccount;
+use pinocchio_token::instructions::Transfer;
+
+pub struct StakeAccounts<'a> {
+    pub user: &'a AccountInfo,
+    pub farm: &'a AccountInfo,
+    pub user_stake: &'a AccountInfo,
+    pub user_lp_ata: &'a AccountInfo,
+    pub lp_vault: &'a AccountInfo,
+    pub token_program: &'a AccountInfo,
+}
+
+impl<'a> TryFrom<&'a [AccountInfo]> for StakeAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountInfo]) -> Result<Self, Self::Error> {
+        let [user, farm, user_stake, user_lp_ata, lp_vault, _system_program, token_program] =
+            accounts
+        else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        Ok(Self {
+            user,
+            farm,
+            user_stake,
+            user_lp_ata,
+            lp_vault,
+            token_program,
+        })
+    }
+}
+
+pub struct StakeInstructionData {
+    pub amount: u64,
+    pub stake_bump: [u8; 1],
+}
+
+impl<'a> TryFrom<&'a [u8]> for StakeInstructionData {
+    type Error = ProgramError;
+
+    fn try_from(data: &'a [u8]) -> Result<Self, Self::Error> {
+        if data.len().ne(&(size_of::<u64>() + size_of::<u8>())) {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let amount = u64::from_le_bytes(data[0..8].try_into().unwrap());
+        let stake_bump = [data[8]];
+
+        if amount == 0 {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        Ok(Self { amount, stake_bump })
+    }
+}
+
+/// Stake LP tokens into a farm, creating the user's stake account on first use.
+pub struct Stake<'a> {
+    pub accounts: StakeAccounts<'a>,
+    pub instruction_data: StakeInstructionData,
+}
+
+impl<'a> TryFrom<(&'a [u8], &'a [AccountInfo])> for Stake<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountInfo])) -> Result<Self, Self::Error> {
+        let accounts = StakeAccounts::try_from(accounts)?;
+        let instruction_data = StakeInstructionData::try_from(data)?;
+
+        Ok(Self {
+            accounts,
+            instruction_data,
+        })
+    }
+}
+
+impl<'a> Stake<'a> {
+    pub const DISCRIMINATOR: &'a u8 = &15;
+
+    pub fn process(&mut self) -> ProgramResult {
+        if !self.accounts.user.is_signer() {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        if self.accounts.user_stake.data_is_empty() {
+            let stake_seeds = [
+                Seed::from(b"stake"),
+                Seed::from(self.accounts.farm.key()),
+                Seed::from(self.accounts.user.key()),
+                Seed::from(&self.instruction_data.stake_bump),
+            ];
+
+            CreateAccount {
+                from: self.accounts.user,
+                to: self.accounts.user_stake,
+                lamports: Rent::get()?.minimum_balance(UserStake::LEN),
+                space: UserStake::LEN as u64,
+                owner: &crate::ID,
+            }
+            .invoke_signed(&[Signer::from(&stake_seeds)])?;
+
+            UserStake::load_mut(self.accounts.user_stake)?.set_inner(
+                *self.accounts.farm.key(),
+                *self.accounts.user.key(),
+                self.instruction_data.stake_bump,
+            );
+        }
+
+        let mut farm = Farm::load_mut(self.accounts.farm)?;
+        farm.update(Clock::get()?.unix_timestamp)?;
+
+        check_vaults(
+            self.accounts.farm,
+            self.accounts.token_program,
+            &[*farm.mint_lp()],
+            core::slice::from_ref(self.accounts.lp_vault),
+        )?;
+
+        let mut user_stake = UserStake::load_mut(self.accounts.user_stake)?;
+        if user_stake.farm().ne(self.accounts.farm.key())
+            || user_stake.owner().ne(self.accounts.user.key())
+        {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        farm.stake(&mut user_stake, self.instruction_data.amount)?;
+
+        drop(user_stake);
+        drop(farm);
+
+        Transfer {
+            from: self.accounts.user_lp_ata,
+            to: self.accounts.lp_vault,
+            authority: self.accounts.user,
+            amount: self.instruction_data.amount,
+        }
+        .invoke()
+    }
+}
@@ -0,0 +1,182 @@
+use crate::helpers::check_vaults;
+use crate::state::{Farm, Lock, UserStake};
+use core::mem::size_of;
+use pinocchio::{
+    account_info::AccountInfo,
+    instruction::{Seed, Signer},
+    program_error::ProgramError,
+    sysvars::{clock::Clock, rent::Rent, Sysvar},
+    ProgramResult,
+};
+use pinocchio_system::instructions::CreateAccount;
+use pinocchio_token::instructions::Transfer;
+
+pub struct StakeLockedAccounts<'a> {
+    pub owner: &'a AccountInfo,
+    pub lock: &'a AccountInfo,
+    pub farm: &'a AccountInfo,
+    pub lock_stake: &'a AccountInfo,
+    pub lock_vault: &'a AccountInfo,
+    pub lp_vault: &'a AccountInfo,
+    pub token_program: &'a AccountInfo,
+}
+
+impl<'a> TryFrom<&'a [AccountInfo]> for StakeLockedAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountInfo]) -> Result<Self, Self::Error> {
+        let [owner, lock, farm, lock_stake, lock_vault, lp_vault, _system_program, token_program] =
+            accounts
+        else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        Ok(Self {
+            owner,
+            lock,
+            farm,
+            lock_stake,
+            lock_vault,
+            lp_vault,
+            token_program,
+        })
+    }
+}
+
+pub struct StakeLockedInstructionData {
+    pub amount: u64,
+    pub stake_bump: [u8; 1],
+}
+
+impl<'a> TryFrom<&'a [u8]> for StakeLockedInstructionData {
+    type Error = ProgramError;
+
+    fn try_from(data: &'a [u8]) -> Result<Self, Self::Error> {
+        if data.len().ne(&(size_of::<u64>() + size_of::<u8>())) {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let amount = u64::from_le_bytes(data[0..8].try_into().unwrap());
+        let stake_bump = [data[8]];
+
+        if amount == 0 {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        Ok(Self { amount, stake_bump })
+    }
+}
+
+/// Stake locked LP into a farm, the lock owning the stake so the LP stays escrowed.
+pub struct StakeLocked<'a> {
+    pub accounts: StakeLockedAccounts<'a>,
+    pub instruction_data: StakeLockedInstructionData,
+}
+
+impl<'a> TryFrom<(&'a [u8], &'a [AccountInfo])> for StakeLocked<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountInfo])) -> Result<Self, Self::Error> {
+        let accounts = StakeLockedAccounts::try_from(accounts)?;
+        let instruction_data = StakeLockedInstructionData::try_from(data)?;
+
+        Ok(Self {
+            accounts,
+            instruction_data,
+        })
+    }
+}
+
+impl<'a> StakeLocked<'a> {
+    pub const DISCRIMINATOR: &'a u8 = &30;
+
+    pub fn process(&mut self) -> ProgramResult {
+        if !self.accounts.owner.is_signer() {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        let lock = Lock::load(self.accounts.lock)?;
+
+        if lock.owner().ne(self.accounts.owner.key()) {
+            return Err(ProgramError::IncorrectAuthority);
+        }
+
+        check_vaults(
+            self.accounts.lock,
+            self.accounts.token_program,
+            &[*lock.mint_lp()],
+            core::slice::from_ref(self.accounts.lock_vault),
+        )?;
+
+        if self.accounts.lock_stake.data_is_empty() {
+            let stake_seeds = [
+                Seed::from(b"stake"),
+                Seed::from(self.accounts.farm.key()),
+                Seed::from(self.accounts.lock.key()),
+                Seed::from(&self.instruction_data.stake_bump),
+            ];
+
+            CreateAccount {
+                from: self.accounts.owner,
+                to: self.accounts.lock_stake,
+                lamports: Rent::get()?.minimum_balance(UserStake::LEN),
+                space: UserStake::LEN as u64,
+                owner: &crate::ID,
+            }
+            .invoke_signed(&[Signer::from(&stake_seeds)])?;
+
+            UserStake::load_mut(self.accounts.lock_stake)?.set_inner(
+                *self.accounts.farm.key(),
+                *self.accounts.lock.key(),
+                self.instruction_data.stake_bump,
+            );
+        }
+
+        let mut farm = Farm::load_mut(self.accounts.farm)?;
+        farm.update(Clock::get()?.unix_timestamp)?;
+
+        if farm.mint_lp().ne(lock.mint_lp()) {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        check_vaults(
+            self.accounts.farm,
+            self.accounts.token_program,
+            &[*farm.mint_lp()],
+            core::slice::from_ref(self.accounts.lp_vault),
+        )?;
+
+        let mut lock_stake = UserStake::load_mut(self.accounts.lock_stake)?;
+        if lock_stake.farm().ne(self.accounts.farm.key())
+            || lock_stake.owner().ne(self.accounts.lock.key())
+        {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        farm.stake(&mut lock_stake, self.instruction_data.amount)?;
+
+        let config = *lock.config();
+        let seed_binding = lock.seed().to_le_bytes();
+        let bump = lock.bump();
+
+        drop(lock_stake);
+        drop(farm);
+        drop(lock);
+
+        let lock_seeds = [
+            Seed::from(b"lock"),
+            Seed::from(&config),
+            Seed::from(self.accounts.owner.key()),
+            Seed::from(&seed_binding),
+            Seed::from(&bump),
+        ];
+
+        Transfer {
+            from: self.accounts.lock_vault,
+            to: self.accounts.lp_vault,
+            authority: self.accounts.lock,
+            amount: self.instruction_data.amount,
+        }
+        .invoke_signed(&[Signer::from(&lock_seeds)])
+    }
+}
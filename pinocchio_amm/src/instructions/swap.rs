@@ -1,7 +1,15 @@
-use crate::state::Config;
+use crate::errors::AmmError;
+use crate::events::SwapEvent;
+use crate::helpers::{
+    check_allowlist, check_price, deviation_bps, read_oracle, spot_price, Deadline,
+};
+use crate::hook::SwapHook;
+use crate::math::constant_product::{self, SwapResult};
+use crate::math::{out_given_in, HASH_LEN};
+use crate::native::{WrappedSol, NATIVE_MINT};
+use crate::state::{Config, CurveType, RangeOrder};
 use crate::AmmState;
-use constant_product_curve::{ConstantProduct, LiquidityPair};
-use core::mem::size_of;
+use core::{mem::size_of, slice::Iter};
 use pinocchio::instruction::Signer;
 use pinocchio::pubkey::find_program_address;
 use pinocchio::sysvars::clock::Clock;
@@ -20,13 +28,16 @@ pub struct SwapAccounts<'a> {
     pub vault_y: &'a AccountInfo,
     pub config: &'a AccountInfo,
     pub token_program: &'a AccountInfo,
+    pub remaining_accounts: &'a [AccountInfo],
 }
 
 impl<'a> TryFrom<&'a [AccountInfo]> for SwapAccounts<'a> {
     type Error = ProgramError;
 
     fn try_from(accounts: &'a [AccountInfo]) -> Result<Self, Self::Error> {
-        let [user, user_x, user_y, vault_x, vault_y, config, token_program] = accounts else {
+        let [user, user_x, user_y, vault_x, vault_y, config, token_program, remaining_accounts @ ..] =
+            accounts
+        else {
             return Err(ProgramError::NotEnoughAccountKeys);
         };
         Ok(Self {
@@ -37,35 +48,77 @@ impl<'a> TryFrom<&'a [AccountInfo]> for SwapAccounts<'a> {
             vault_y,
             config,
             token_program,
+            remaining_accounts,
         })
         //..
     }
 }
 
-pub struct SwapInstructionData {
+pub struct SwapInstructionData<'a> {
     pub is_x: bool,
     pub amount: u64,
     pub min: u64,
-    pub expiration: i64,
+    pub deadline: Deadline,
+    pub referral_bps: u16,
+    pub proof: &'a [u8],
 }
 
-impl<'a> TryFrom<&'a [u8]> for SwapInstructionData {
+impl<'a> TryFrom<&'a [u8]> for SwapInstructionData<'a> {
     type Error = ProgramError;
 
     fn try_from(data: &'a [u8]) -> Result<Self, Self::Error> {
-        if data
-            .len()
-            .ne(&(size_of::<bool>() + size_of::<u64>() + size_of::<u64>() + size_of::<i64>()))
-        {
+        let instruction_data = Self::parse(data, size_of::<i64>(), |deadline| {
+            Ok(Deadline::UnixTimestamp(i64::from_le_bytes(
+                deadline.try_into().unwrap(),
+            )))
+        })?;
+
+        // Allowlist proofs only fit in version 2 data
+        if !instruction_data.proof.is_empty() {
             return Err(ProgramError::InvalidInstructionData);
         }
 
+        Ok(instruction_data)
+    }
+}
+
+impl<'a> SwapInstructionData<'a> {
+    /// Parse version 2 data, whose deadline may be a slot and which may end with an
+    /// allowlist proof.
+    pub fn try_from_v2(data: &'a [u8]) -> Result<Self, ProgramError> {
+        Self::parse(data, Deadline::LEN, |deadline| Deadline::try_from(deadline))
+    }
+
+    fn parse(
+        data: &'a [u8],
+        deadline_len: usize,
+        read_deadline: impl Fn(&[u8]) -> Result<Deadline, ProgramError>,
+    ) -> Result<Self, ProgramError> {
+        const HEADER_LEN: usize = size_of::<bool>() + size_of::<u64>() + size_of::<u64>();
+        let swap_data_len = HEADER_LEN + deadline_len;
+
+        if data.len() < swap_data_len {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        // The referral share and the proof are optional and trail the swap parameters,
+        // proofs being made of whole hashes
+        let trailing = &data[swap_data_len..];
+        let (referral_bps, proof) = match trailing.len() % HASH_LEN {
+            0 => (0, trailing),
+            2 => (
+                u16::from_le_bytes(trailing[0..2].try_into().unwrap()),
+                &trailing[2..],
+            ),
+            _ => return Err(ProgramError::InvalidInstructionData),
+        };
+
         let is_x = data[0] == 1;
         let amount = u64::from_le_bytes(data[1..9].try_into().unwrap());
         let min = u64::from_le_bytes(data[9..17].try_into().unwrap());
-        let expiration = i64::from_le_bytes(data[17..25].try_into().unwrap());
+        let deadline = read_deadline(&data[HEADER_LEN..swap_data_len])?;
 
-        if amount == 0 || min == 0 || expiration < Clock::get()?.unix_timestamp {
+        if amount == 0 || min == 0 {
             return Err(ProgramError::InvalidInstructionData);
         }
 
@@ -73,15 +126,16 @@ impl<'a> TryFrom<&'a [u8]> for SwapInstructionData {
             is_x,
             amount,
             min,
-            expiration,
+            deadline,
+            referral_bps,
+            proof,
         })
-        
     }
 }
 
 pub struct Swap<'a> {
     pub accounts: SwapAccounts<'a>,
-    pub instruction_data: SwapInstructionData,
+    pub instruction_data: SwapInstructionData<'a>,
 }
 
 impl<'a> TryFrom<(&'a [u8], &'a [AccountInfo])> for Swap<'a> {
@@ -101,8 +155,25 @@ impl<'a> TryFrom<(&'a [u8], &'a [AccountInfo])> for Swap<'a> {
 impl<'a> Swap<'a> {
     pub const DISCRIMINATOR: &'a u8 = &3;
 
+    pub const DISCRIMINATOR_V2: &'a u8 = &19;
+
+    /// Build the instruction from version 2 data.
+    pub fn try_from_v2(
+        (data, accounts): (&'a [u8], &'a [AccountInfo]),
+    ) -> Result<Self, ProgramError> {
+        let accounts = SwapAccounts::try_from(accounts)?;
+        let instruction_data = SwapInstructionData::try_from_v2(data)?;
+
+        Ok(Self {
+            accounts,
+            instruction_data,
+        })
+    }
+
     pub fn process(&mut self) -> ProgramResult {
-        let config = Config::load(self.accounts.config)?;
+        self.instruction_data.deadline.check()?;
+
+        let mut config = Config::load_mut(self.accounts.config)?;
 
         if config.state().ne(&(AmmState::Initialized as u8)) {
             return Err(ProgramError::InvalidAccountData);
@@ -138,76 +209,359 @@ impl<'a> Swap<'a> {
         let vault_x = unsafe { TokenAccount::from_account_info_unchecked(self.accounts.vault_x)? };
         let vault_y = unsafe { TokenAccount::from_account_info_unchecked(self.accounts.vault_y)? };
 
+        // Fees set aside for positions sit in the vaults but do not price the trade
+        let (reserve_x, reserve_y) = config.reserves(vault_x.amount(), vault_y.amount());
+
+        let clock = Clock::get()?;
+        let (weight_x, weight_y) = config.weights(clock.unix_timestamp);
+        let price_before = spot_price(reserve_x, weight_x, reserve_y, weight_y)?;
+
+        // Let the volatility decay before pricing the fee, when the dynamic fee is enabled
+        if config.max_fee() != 0 {
+            config.decay_volatility(clock.unix_timestamp);
+        }
+        let fee = config.effective_fee();
+
         // Swap Calculations
-        let mut curve = ConstantProduct::init(
-            vault_x.amount(),
-            vault_y.amount(),
-            vault_x.amount(),
-            config.fee(),
-            None,
-        )
-        .map_err(|_| ProgramError::Custom(1))?;
+        let swap_result = match config.curve() == CurveType::Weighted as u8 {
+            true => self.weighted_swap(reserve_x, weight_x, reserve_y, weight_y, fee)?,
+            false => {
+                let (reserve_in, reserve_out) = match self.instruction_data.is_x {
+                    true => (reserve_x, reserve_y),
+                    false => (reserve_y, reserve_x),
+                };
 
-        let p = match self.instruction_data.is_x {
-            true => LiquidityPair::X,
-            false => LiquidityPair::Y,
+                constant_product::swap(
+                    reserve_in,
+                    reserve_out,
+                    self.instruction_data.amount,
+                    fee,
+                    self.instruction_data.min,
+                )?
+            }
         };
 
-        let swap_result = curve
-            .swap(p, self.instruction_data.amount, self.instruction_data.min)
-            .map_err(|_| ProgramError::Custom(1))?;
-
         // Check for correct values
         if swap_result.deposit == 0 || swap_result.withdraw == 0 {
             return Err(ProgramError::InvalidArgument);
         }
 
+        // Share of the fee paid out to the referrer rather than left in the pool
+        let referral_bps = self
+            .instruction_data
+            .referral_bps
+            .min(config.max_referral_bps());
+        let referral_amount = (swap_result.fee as u128 * referral_bps as u128 / 10_000) as u64;
+
+        // The rest of the fee goes to the LP, the share of positions is set aside
+        let position_fee = config.accrue_fee(
+            self.instruction_data.is_x,
+            swap_result.fee - referral_amount,
+        )?;
+
+        // Reserves once the trade settles
+        let (x, y) = match self.instruction_data.is_x {
+            true => (
+                reserve_x + swap_result.deposit - referral_amount - position_fee,
+                reserve_y - swap_result.withdraw,
+            ),
+            false => (
+                reserve_x - swap_result.withdraw,
+                reserve_y + swap_result.deposit - referral_amount - position_fee,
+            ),
+        };
+        let price_after = spot_price(x, weight_x, y, weight_y)?;
+
+        let mut remaining_accounts = self.accounts.remaining_accounts.iter();
+
+        // The oracle is read up front, the price it guards is only final once the range
+        // orders are filled
+        let oracle_price = read_oracle(&config, &mut remaining_accounts, clock.unix_timestamp)?;
+
+        // The referrer token account follows, when the swap carries a referral share
+        let referrer = match self.instruction_data.referral_bps != 0 {
+            true => Some(
+                remaining_accounts
+                    .next()
+                    .ok_or(ProgramError::NotEnoughAccountKeys)?,
+            ),
+            false => None,
+        };
+
+        check_allowlist(
+            &config,
+            self.accounts.user,
+            &mut remaining_accounts,
+            self.instruction_data.proof,
+        )?;
+
+        let mint_x = *config.mint_x();
+        let mint_y = *config.mint_y();
+
+        let (user_in, vault_in, mint_in, user_out, vault_out, mint_out) =
+            match self.instruction_data.is_x {
+                true => (
+                    self.accounts.user_x,
+                    self.accounts.vault_x,
+                    mint_x,
+                    self.accounts.user_y,
+                    self.accounts.vault_y,
+                    mint_y,
+                ),
+                false => (
+                    self.accounts.user_y,
+                    self.accounts.vault_y,
+                    mint_y,
+                    self.accounts.user_x,
+                    self.accounts.vault_x,
+                    mint_x,
+                ),
+            };
+
+        // Users trading native SOL pass their wallet in place of the wSOL token account
+        let wrap_in = user_in.key().eq(self.accounts.user.key());
+        let unwrap_out = user_out.key().eq(self.accounts.user.key());
+
+        if (wrap_in && mint_in.ne(&NATIVE_MINT)) || (unwrap_out && mint_out.ne(&NATIVE_MINT)) {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        let wsol = match wrap_in || unwrap_out {
+            true => Some(WrappedSol::next(
+                self.accounts.user,
+                &mut remaining_accounts,
+            )?),
+            false => None,
+        };
+
+        // The hook program and its signer come before the range orders
+        let hook = match config.has_swap_hook() {
+            Some(swap_hook) => {
+                let (Some(program), Some(authority)) =
+                    (remaining_accounts.next(), remaining_accounts.next())
+                else {
+                    return Err(ProgramError::NotEnoughAccountKeys);
+                };
+                let (expected, bump) =
+                    find_program_address(&[b"hook", self.accounts.config.key()], &crate::ID);
+
+                if program.key().ne(&swap_hook) || expected.ne(authority.key()) {
+                    return Err(ProgramError::InvalidAccountData);
+                }
+
+                Some((program, authority, [bump]))
+            }
+            None => None,
+        };
+
+        // Fills move the price further, so the guards check the price they leave
+        let price_after = self.fill_range_orders(
+            &mut config,
+            remaining_accounts,
+            (x, y),
+            (weight_x, weight_y),
+            price_after,
+        )?;
+
+        check_price(
+            &mut config,
+            oracle_price,
+            clock.slot,
+            price_before,
+            price_after,
+        )?;
+
+        if config.max_fee() != 0 {
+            let move_bps = deviation_bps(price_after, price_before)?;
+            config.accumulate_volatility(u64::try_from(move_bps).unwrap_or(u64::MAX));
+        }
+
+        config.record_swap(
+            self.instruction_data.is_x,
+            swap_result.deposit,
+            swap_result.withdraw,
+            swap_result.fee,
+        );
+        config.record_trade(clock.slot, clock.unix_timestamp, Some(price_after));
+
         let seed_binding = config.seed().to_le_bytes();
         let config_bump = config.config_bump();
+
+        // Release the mutable borrow, the config signs the transfers below
+        drop(config);
+
         let seeds = [
             Seed::from("config".as_bytes()),
             Seed::from(&seed_binding),
-            Seed::from(config.mint_x()),
-            Seed::from(config.mint_y()),
+            Seed::from(&mint_x),
+            Seed::from(&mint_y),
             Seed::from(&config_bump),
         ];
         let signer_seeds = [Signer::from(&seeds)];
 
-        if self.instruction_data.is_x {
-            Transfer {
-                from: self.accounts.user_x,
-                to: self.accounts.vault_x,
-                authority: self.accounts.user,
-                amount: swap_result.deposit,
+        let from = match &wsol {
+            Some(wsol) if wrap_in => {
+                wsol.open(swap_result.deposit)?;
+                wsol.account
             }
-            .invoke()?;
+            _ => user_in,
+        };
 
+        Transfer {
+            from,
+            to: vault_in,
+            authority: self.accounts.user,
+            amount: swap_result.deposit - referral_amount,
+        }
+        .invoke()?;
+
+        // The token program rejects referrer accounts of another mint
+        if let Some(referrer) = referrer.filter(|_| referral_amount != 0) {
             Transfer {
-                from: self.accounts.vault_y,
-                to: self.accounts.user_y,
-                authority: self.accounts.config,
-                amount: swap_result.withdraw,
-            }
-            .invoke_signed(&signer_seeds)?;
-        } else {
-            Transfer {
-                from: self.accounts.user_y,
-                to: self.accounts.vault_y,
+                from,
+                to: referrer,
                 authority: self.accounts.user,
-                amount: swap_result.deposit,
+                amount: referral_amount,
             }
             .invoke()?;
+        }
 
-            Transfer {
-                from: self.accounts.vault_x,
-                to: self.accounts.user_x,
-                authority: self.accounts.config,
-                amount: swap_result.withdraw,
+        let to = match &wsol {
+            Some(wsol) if unwrap_out => {
+                wsol.open(0)?;
+                wsol.account
+            }
+            _ => user_out,
+        };
+
+        Transfer {
+            from: vault_out,
+            to,
+            authority: self.accounts.config,
+            amount: swap_result.withdraw,
+        }
+        .invoke_signed(&signer_seeds)?;
+
+        if let Some(wsol) = wsol {
+            wsol.close()?;
+        }
+
+        let event = SwapEvent {
+            config: *self.accounts.config.key(),
+            user: *self.accounts.user.key(),
+            is_x: self.instruction_data.is_x as u8,
+            amount_in: swap_result.deposit.to_le_bytes(),
+            amount_out: swap_result.withdraw.to_le_bytes(),
+            fee_amount: swap_result.fee.to_le_bytes(),
+            referral_amount: referral_amount.to_le_bytes(),
+            fee_bps: fee.to_le_bytes(),
+        };
+        event.emit();
+
+        if let Some((program, authority, bump)) = hook {
+            let hook_seeds = [
+                Seed::from(b"hook"),
+                Seed::from(self.accounts.config.key()),
+                Seed::from(&bump),
+            ];
+
+            SwapHook {
+                program,
+                authority,
+                config: self.accounts.config,
+                user: self.accounts.user,
+                event: &event,
             }
-            .invoke_signed(&signer_seeds)?;
+            .invoke_signed(&[Signer::from(&hook_seeds)])?;
         }
-        
 
         Ok(())
     }
-}
\ No newline at end of file
+
+    /// Fill the range orders trailing the other accounts that the pool price crossed,
+    /// in the order they come.
+    ///
+    /// The pool buys of each order what its curve would trade to take the price back to
+    /// the order price, so the orders the price crossed first should come first. Every
+    /// fill moves the price back towards the orders left. Returns the price once the
+    /// orders are filled.
+    fn fill_range_orders(
+        &self,
+        config: &mut Config,
+        orders: Iter<'a, AccountInfo>,
+        (mut x, mut y): (u64, u64),
+        (weight_x, weight_y): (u16, u16),
+        mut price: u128,
+    ) -> Result<u128, ProgramError> {
+        for order in orders {
+            let mut order = RangeOrder::load_mut(order)?;
+            if order.config().ne(self.accounts.config.key()) {
+                return Err(ProgramError::InvalidAccountData);
+            }
+
+            let (sold, proceeds) = order.fill_amounts((x, y), (weight_x, weight_y), price)?;
+            if sold == 0 || proceeds == 0 {
+                continue;
+            }
+
+            // The proceeds never reach the curve output, which is less than the reserve
+            (x, y) = match order.is_x() {
+                true => (
+                    x.checked_add(sold)
+                        .ok_or(ProgramError::ArithmeticOverflow)?,
+                    y - proceeds,
+                ),
+                false => (
+                    x - proceeds,
+                    y.checked_add(sold)
+                        .ok_or(ProgramError::ArithmeticOverflow)?,
+                ),
+            };
+
+            config.take_orders(order.is_x(), sold)?;
+            config.add_orders(!order.is_x(), proceeds)?;
+            order.fill(sold, proceeds)?;
+
+            price = spot_price(x, weight_x, y, weight_y)?;
+        }
+
+        Ok(price)
+    }
+
+    /// Price the trade on the weighted invariant at the current weights.
+    fn weighted_swap(
+        &self,
+        x: u64,
+        weight_x: u16,
+        y: u64,
+        weight_y: u16,
+        fee: u16,
+    ) -> Result<SwapResult, ProgramError> {
+        let (balance_in, weight_in, balance_out, weight_out) = match self.instruction_data.is_x {
+            true => (x, weight_x, y, weight_y),
+            false => (y, weight_y, x, weight_x),
+        };
+
+        let amount = self.instruction_data.amount;
+        let amount_after_fee = (amount as u128 * (10_000 - fee as u128) / 10_000) as u64;
+
+        let withdraw = out_given_in(
+            balance_in,
+            weight_in,
+            balance_out,
+            weight_out,
+            amount_after_fee,
+        )
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+
+        if withdraw < self.instruction_data.min {
+            return Err(AmmError::SlippageExceeded.into());
+        }
+
+        Ok(SwapResult {
+            deposit: amount,
+            withdraw,
+            fee: amount - amount_after_fee,
+        })
+    }
+}
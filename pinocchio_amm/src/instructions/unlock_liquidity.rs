@@ -0,0 +1,128 @@
+use crate::helpers::check_vaults;
+use crate::state::Lock;
+use core::mem::size_of;
+use pinocchio::{
+    account_info::AccountInfo,
+    instruction::{Seed, Signer},
+    program_error::ProgramError,
+    sysvars::{clock::Clock, Sysvar},
+    ProgramResult,
+};
+use pinocchio_token::instructions::Transfer;
+
+pub struct UnlockLiquidityAccounts<'a> {
+    pub owner: &'a AccountInfo,
+    pub lock: &'a AccountInfo,
+    pub owner_lp_ata: &'a AccountInfo,
+    pub lock_vault: &'a AccountInfo,
+    pub token_program: &'a AccountInfo,
+}
+
+impl<'a> TryFrom<&'a [AccountInfo]> for UnlockLiquidityAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountInfo]) -> Result<Self, Self::Error> {
+        let [owner, lock, owner_lp_ata, lock_vault, token_program] = accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        Ok(Self {
+            owner,
+            lock,
+            owner_lp_ata,
+            lock_vault,
+            token_program,
+        })
+    }
+}
+
+pub struct UnlockLiquidityInstructionData {
+    pub amount: u64,
+}
+
+impl<'a> TryFrom<&'a [u8]> for UnlockLiquidityInstructionData {
+    type Error = ProgramError;
+
+    fn try_from(data: &'a [u8]) -> Result<Self, Self::Error> {
+        if data.len().ne(&size_of::<u64>()) {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let amount = u64::from_le_bytes(data[0..8].try_into().unwrap());
+
+        if amount == 0 {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        Ok(Self { amount })
+    }
+}
+
+/// Withdraw vested LP tokens from a lock.
+///
+/// Vested LP still staked by the lock has to be unstaked with `UnstakeLocked` first.
+pub struct UnlockLiquidity<'a> {
+    pub accounts: UnlockLiquidityAccounts<'a>,
+    pub instruction_data: UnlockLiquidityInstructionData,
+}
+
+impl<'a> TryFrom<(&'a [u8], &'a [AccountInfo])> for UnlockLiquidity<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountInfo])) -> Result<Self, Self::Error> {
+        let accounts = UnlockLiquidityAccounts::try_from(accounts)?;
+        let instruction_data = UnlockLiquidityInstructionData::try_from(data)?;
+
+        Ok(Self {
+            accounts,
+            instruction_data,
+        })
+    }
+}
+
+impl<'a> UnlockLiquidity<'a> {
+    pub const DISCRIMINATOR: &'a u8 = &29;
+
+    pub fn process(&mut self) -> ProgramResult {
+        if !self.accounts.owner.is_signer() {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        let mut lock = Lock::load_mut(self.accounts.lock)?;
+
+        if lock.owner().ne(self.accounts.owner.key()) {
+            return Err(ProgramError::IncorrectAuthority);
+        }
+
+        check_vaults(
+            self.accounts.lock,
+            self.accounts.token_program,
+            &[*lock.mint_lp()],
+            core::slice::from_ref(self.accounts.lock_vault),
+        )?;
+
+        lock.withdraw(self.instruction_data.amount, Clock::get()?.unix_timestamp)?;
+
+        let config = *lock.config();
+        let seed_binding = lock.seed().to_le_bytes();
+        let bump = lock.bump();
+
+        drop(lock);
+
+        let lock_seeds = [
+            Seed::from(b"lock"),
+            Seed::from(&config),
+            Seed::from(self.accounts.owner.key()),
+            Seed::from(&seed_binding),
+            Seed::from(&bump),
+        ];
+
+        Transfer {
+            from: self.accounts.lock_vault,
+            to: self.accounts.owner_lp_ata,
+            authority: self.accounts.lock,
+            amount: self.instruction_data.amount,
+        }
+        .invoke_signed(&[Signer::from(&lock_seeds)])
+    }
+}
@@ -0,0 +1,150 @@
+use crate::helpers::check_vaults;
+use crate::state::{Farm, UserStake};
+use core::mem::size_of;
+use pinocchio::{
+    account_info::AccountInfo,
+    instruction::{Seed, Signer},
+    program_error::ProgramError,
+    sysvars::{clock::Clock, Sysvar},
+    ProgramResult,
+};
+use pinocchio_token::instructions::Transfer;
+
+pub struct UnstakeAccounts<'a> {
+    pub user: &'a AccountInfo,
+    pub farm: &'a AccountInfo,
+    pub user_stake: &'a AccountInfo,
+    pub user_lp_ata: &'a AccountInfo,
+    pub lp_vault: &'a AccountInfo,
+    pub user_reward_ata: &'a AccountInfo,
+    pub reward_vault: &'a AccountInfo,
+    pub token_program: &'a AccountInfo,
+}
+
+impl<'a> TryFrom<&'a [AccountInfo]> for UnstakeAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountInfo]) -> Result<Self, Self::Error> {
+        let [user, farm, user_stake, user_lp_ata, lp_vault, user_reward_ata, reward_vault, token_program] =
+            accounts
+        else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        Ok(Self {
+            user,
+            farm,
+            user_stake,
+            user_lp_ata,
+            lp_vault,
+            user_reward_ata,
+            reward_vault,
+            token_program,
+        })
+    }
+}
+
+pub struct UnstakeInstructionData {
+    pub amount: u64,
+}
+
+impl<'a> TryFrom<&'a [u8]> for UnstakeInstructionData {
+    type Error = ProgramError;
+
+    fn try_from(data: &'a [u8]) -> Result<Self, Self::Error> {
+        if data.len().ne(&size_of::<u64>()) {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let amount = u64::from_le_bytes(data[0..8].try_into().unwrap());
+
+        if amount == 0 {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        Ok(Self { amount })
+    }
+}
+
+/// Withdraw staked LP tokens and claim every pending reward in one go.
+pub struct Unstake<'a> {
+    pub accounts: UnstakeAccounts<'a>,
+    pub instruction_data: UnstakeInstructionData,
+}
+
+impl<'a> TryFrom<(&'a [u8], &'a [AccountInfo])> for Unstake<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountInfo])) -> Result<Self, Self::Error> {
+        let accounts = UnstakeAccounts::try_from(accounts)?;
+        let instruction_data = UnstakeInstructionData::try_from(data)?;
+
+        Ok(Self {
+            accounts,
+            instruction_data,
+        })
+    }
+}
+
+impl<'a> Unstake<'a> {
+    pub const DISCRIMINATOR: &'a u8 = &16;
+
+    pub fn process(&mut self) -> ProgramResult {
+        if !self.accounts.user.is_signer() {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        let mut farm = Farm::load_mut(self.accounts.farm)?;
+        farm.update(Clock::get()?.unix_timestamp)?;
+
+        check_vaults(
+            self.accounts.farm,
+            self.accounts.token_program,
+            &[*farm.mint_lp(), *farm.reward_mint()],
+            &[self.accounts.lp_vault, self.accounts.reward_vault],
+        )?;
+
+        let mut user_stake = UserStake::load_mut(self.accounts.user_stake)?;
+        if user_stake.farm().ne(self.accounts.farm.key())
+            || user_stake.owner().ne(self.accounts.user.key())
+        {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        let rewards = farm.unstake(&mut user_stake, self.instruction_data.amount)?;
+
+        let config = *farm.config();
+        let seed_binding = farm.seed().to_le_bytes();
+        let bump = farm.bump();
+
+        drop(user_stake);
+        drop(farm);
+
+        let farm_seeds = [
+            Seed::from(b"farm"),
+            Seed::from(&config),
+            Seed::from(&seed_binding),
+            Seed::from(&bump),
+        ];
+
+        Transfer {
+            from: self.accounts.lp_vault,
+            to: self.accounts.user_lp_ata,
+            authority: self.accounts.farm,
+            amount: self.instruction_data.amount,
+        }
+        .invoke_signed(&[Signer::from(&farm_seeds)])?;
+
+        if rewards != 0 {
+            Transfer {
+                from: self.accounts.reward_vault,
+                to: self.accounts.user_reward_ata,
+                authority: self.accounts.farm,
+                amount: rewards,
+            }
+            .invoke_signed(&[Signer::from(&farm_seeds)])?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,166 @@
+use crate::helpers::check_vaults;
+use crate::state::{Farm, Lock, UserStake};
+use core::mem::size_of;
+use pinocchio::{
+    account_info::AccountInfo,
+    instruction::{Seed, Signer},
+    program_error::ProgramError,
+    sysvars::{clock::Clock, Sysvar},
+    ProgramResult,
+};
+use pinocchio_token::instructions::Transfer;
+
+pub struct UnstakeLockedAccounts<'a> {
+    pub owner: &'a AccountInfo,
+    pub lock: &'a AccountInfo,
+    pub farm: &'a AccountInfo,
+    pub lock_stake: &'a AccountInfo,
+    pub lock_vault: &'a AccountInfo,
+    pub lp_vault: &'a AccountInfo,
+    pub owner_reward_ata: &'a AccountInfo,
+    pub reward_vault: &'a AccountInfo,
+    pub token_program: &'a AccountInfo,
+}
+
+impl<'a> TryFrom<&'a [AccountInfo]> for UnstakeLockedAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountInfo]) -> Result<Self, Self::Error> {
+        let [owner, lock, farm, lock_stake, lock_vault, lp_vault, owner_reward_ata, reward_vault, token_program] =
+            accounts
+        else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        Ok(Self {
+            owner,
+            lock,
+            farm,
+            lock_stake,
+            lock_vault,
+            lp_vault,
+            owner_reward_ata,
+            reward_vault,
+            token_program,
+        })
+    }
+}
+
+pub struct UnstakeLockedInstructionData {
+    pub amount: u64,
+}
+
+impl<'a> TryFrom<&'a [u8]> for UnstakeLockedInstructionData {
+    type Error = ProgramError;
+
+    fn try_from(data: &'a [u8]) -> Result<Self, Self::Error> {
+        if data.len().ne(&size_of::<u64>()) {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let amount = u64::from_le_bytes(data[0..8].try_into().unwrap());
+
+        Ok(Self { amount })
+    }
+}
+
+/// Move LP staked by a lock back into the lock and pay the farm rewards to the lock owner.
+///
+/// An `amount` of `0` only claims the rewards.
+pub struct UnstakeLocked<'a> {
+    pub accounts: UnstakeLockedAccounts<'a>,
+    pub instruction_data: UnstakeLockedInstructionData,
+}
+
+impl<'a> TryFrom<(&'a [u8], &'a [AccountInfo])> for UnstakeLocked<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountInfo])) -> Result<Self, Self::Error> {
+        let accounts = UnstakeLockedAccounts::try_from(accounts)?;
+        let instruction_data = UnstakeLockedInstructionData::try_from(data)?;
+
+        Ok(Self {
+            accounts,
+            instruction_data,
+        })
+    }
+}
+
+impl<'a> UnstakeLocked<'a> {
+    pub const DISCRIMINATOR: &'a u8 = &31;
+
+    pub fn process(&mut self) -> ProgramResult {
+        if !self.accounts.owner.is_signer() {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        let lock = Lock::load(self.accounts.lock)?;
+
+        if lock.owner().ne(self.accounts.owner.key()) {
+            return Err(ProgramError::IncorrectAuthority);
+        }
+
+        check_vaults(
+            self.accounts.lock,
+            self.accounts.token_program,
+            &[*lock.mint_lp()],
+            core::slice::from_ref(self.accounts.lock_vault),
+        )?;
+
+        let mut farm = Farm::load_mut(self.accounts.farm)?;
+        farm.update(Clock::get()?.unix_timestamp)?;
+
+        check_vaults(
+            self.accounts.farm,
+            self.accounts.token_program,
+            &[*farm.mint_lp(), *farm.reward_mint()],
+            &[self.accounts.lp_vault, self.accounts.reward_vault],
+        )?;
+
+        let mut lock_stake = UserStake::load_mut(self.accounts.lock_stake)?;
+        if lock_stake.farm().ne(self.accounts.farm.key())
+            || lock_stake.owner().ne(self.accounts.lock.key())
+        {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        let rewards = farm.unstake(&mut lock_stake, self.instruction_data.amount)?;
+
+        let config = *farm.config();
+        let seed_binding = farm.seed().to_le_bytes();
+        let bump = farm.bump();
+
+        drop(lock_stake);
+        drop(farm);
+        drop(lock);
+
+        let farm_seeds = [
+            Seed::from(b"farm"),
+            Seed::from(&config),
+            Seed::from(&seed_binding),
+            Seed::from(&bump),
+        ];
+
+        if self.instruction_data.amount != 0 {
+            Transfer {
+                from: self.accounts.lp_vault,
+                to: self.accounts.lock_vault,
+                authority: self.accounts.farm,
+                amount: self.instruction_data.amount,
+            }
+            .invoke_signed(&[Signer::from(&farm_seeds)])?;
+        }
+
+        if rewards != 0 {
+            Transfer {
+                from: self.accounts.reward_vault,
+                to: self.accounts.owner_reward_ata,
+                authority: self.accounts.farm,
+                amount: rewards,
+            }
+            .invoke_signed(&[Signer::from(&farm_seeds)])?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,100 @@
+use crate::helpers::check_authority;
+use crate::state::{Allowlist, Config};
+use pinocchio::{
+    account_info::AccountInfo, program_error::ProgramError, pubkey::Pubkey, ProgramResult,
+};
+
+pub struct UpdateAllowlistAccounts<'a> {
+    pub authority: &'a AccountInfo,
+    pub config: &'a AccountInfo,
+    pub allowlist: &'a AccountInfo,
+}
+
+impl<'a> TryFrom<&'a [AccountInfo]> for UpdateAllowlistAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountInfo]) -> Result<Self, Self::Error> {
+        let [authority, config, allowlist] = accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        Ok(Self {
+            authority,
+            config,
+            allowlist,
+        })
+    }
+}
+
+#[repr(u8)]
+pub enum AllowlistAction {
+    AddKey = 0u8,
+    RemoveKey = 1u8,
+    SetRoot = 2u8,
+}
+
+pub struct UpdateAllowlistInstructionData {
+    pub action: u8,
+    pub value: Pubkey,
+}
+
+impl<'a> TryFrom<&'a [u8]> for UpdateAllowlistInstructionData {
+    type Error = ProgramError;
+
+    fn try_from(data: &'a [u8]) -> Result<Self, Self::Error> {
+        if data.len().ne(&(1 + 32)) || data[0].gt(&(AllowlistAction::SetRoot as u8)) {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        Ok(Self {
+            action: data[0],
+            value: data[1..33].try_into().unwrap(),
+        })
+    }
+}
+
+/// Add or remove a wallet on a `Keys` allowlist, or replace the root of a merkle allowlist.
+pub struct UpdateAllowlist<'a> {
+    pub accounts: UpdateAllowlistAccounts<'a>,
+    pub instruction_data: UpdateAllowlistInstructionData,
+}
+
+impl<'a> TryFrom<(&'a [u8], &'a [AccountInfo])> for UpdateAllowlist<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountInfo])) -> Result<Self, Self::Error> {
+        let accounts = UpdateAllowlistAccounts::try_from(accounts)?;
+        let instruction_data = UpdateAllowlistInstructionData::try_from(data)?;
+
+        Ok(Self {
+            accounts,
+            instruction_data,
+        })
+    }
+}
+
+impl<'a> UpdateAllowlist<'a> {
+    pub const DISCRIMINATOR: &'a u8 = &23;
+
+    pub fn process(&mut self) -> ProgramResult {
+        let config = Config::load(self.accounts.config)?;
+
+        check_authority(&config, self.accounts.authority)?;
+
+        let mut allowlist = Allowlist::load_mut(self.accounts.allowlist)?;
+
+        if allowlist.config().ne(self.accounts.config.key()) {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        match self.instruction_data.action {
+            action if action == AllowlistAction::AddKey as u8 => {
+                allowlist.add_key(self.instruction_data.value)
+            }
+            action if action == AllowlistAction::RemoveKey as u8 => {
+                allowlist.remove_key(&self.instruction_data.value)
+            }
+            _ => allowlist.set_root(self.instruction_data.value),
+        }
+    }
+}
@@ -1,6 +1,9 @@
+use crate::errors::AmmError;
+use crate::helpers::{check_allowlist, spot_price, Deadline};
+use crate::math::{constant_product, HASH_LEN};
 use crate::state::Config;
 use crate::AmmState;
-use constant_product_curve::ConstantProduct;
+use core::mem::size_of;
 use pinocchio::instruction::{Seed, Signer};
 use pinocchio::pubkey::find_program_address;
 use pinocchio::sysvars::clock::Clock;
@@ -19,13 +22,14 @@ pub struct WithdrawAccounts<'a> {
     pub user_lp_ata: &'a AccountInfo,
     pub config: &'a AccountInfo,
     pub token_program: &'a AccountInfo,
+    pub remaining_accounts: &'a [AccountInfo],
 }
 
 impl<'a> TryFrom<&'a [AccountInfo]> for WithdrawAccounts<'a> {
     type Error = ProgramError;
 
     fn try_from(accounts: &'a [AccountInfo]) -> Result<Self, Self::Error> {
-        let [user, mint_lp, vault_x, vault_y, user_x_ata, user_y_ata, user_lp_ata, config, token_program] =
+        let [user, mint_lp, vault_x, vault_y, user_x_ata, user_y_ata, user_lp_ata, config, token_program, remaining_accounts @ ..] =
             accounts
         else {
             return Err(ProgramError::NotEnoughAccountKeys);
@@ -41,28 +45,66 @@ impl<'a> TryFrom<&'a [AccountInfo]> for WithdrawAccounts<'a> {
             user_lp_ata,
             config,
             token_program,
+            remaining_accounts,
         })
         //..
     }
 }
 
-pub struct WithdrawInstructionData {
+pub struct WithdrawInstructionData<'a> {
     pub amount: u64,
     pub min_x: u64,
     pub min_y: u64,
-    pub expiration: i64,
+    pub deadline: Deadline,
+    pub proof: &'a [u8],
 }
 
-impl<'a> TryFrom<&'a [u8]> for WithdrawInstructionData {
+impl<'a> TryFrom<&'a [u8]> for WithdrawInstructionData<'a> {
     type Error = ProgramError;
 
     fn try_from(data: &'a [u8]) -> Result<Self, Self::Error> {
+        let instruction_data = Self::parse(data, size_of::<i64>(), |deadline| {
+            Ok(Deadline::UnixTimestamp(i64::from_le_bytes(
+                deadline.try_into().unwrap(),
+            )))
+        })?;
+
+        // Allowlist proofs only fit in version 2 data
+        if !instruction_data.proof.is_empty() {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        Ok(instruction_data)
+    }
+}
+
+impl<'a> WithdrawInstructionData<'a> {
+    /// Parse version 2 data, whose deadline may be a slot and which may end with an
+    /// allowlist proof.
+    pub fn try_from_v2(data: &'a [u8]) -> Result<Self, ProgramError> {
+        Self::parse(data, Deadline::LEN, |deadline| Deadline::try_from(deadline))
+    }
+
+    fn parse(
+        data: &'a [u8],
+        deadline_len: usize,
+        read_deadline: impl Fn(&[u8]) -> Result<Deadline, ProgramError>,
+    ) -> Result<Self, ProgramError> {
+        const HEADER_LEN: usize = size_of::<u64>() * 3;
+
+        let data_len = HEADER_LEN + deadline_len;
+
+        // Proofs are made of whole hashes
+        if data.len() < data_len || !(data.len() - data_len).is_multiple_of(HASH_LEN) {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
         let amount = u64::from_le_bytes(data[0..8].try_into().unwrap());
         let min_x = u64::from_le_bytes(data[8..16].try_into().unwrap());
         let min_y = u64::from_le_bytes(data[16..24].try_into().unwrap());
-        let expiration = i64::from_le_bytes(data[24..32].try_into().unwrap());
+        let deadline = read_deadline(&data[HEADER_LEN..data_len])?;
 
-        if amount == 0 || min_x == 0 || min_y == 0 || expiration < Clock::get()?.unix_timestamp {
+        if amount == 0 || min_x == 0 || min_y == 0 {
             return Err(ProgramError::InvalidInstructionData);
         }
 
@@ -70,15 +112,15 @@ impl<'a> TryFrom<&'a [u8]> for WithdrawInstructionData {
             amount,
             min_x,
             min_y,
-            expiration,
+            deadline,
+            proof: &data[data_len..],
         })
-        
     }
 }
 
 pub struct Withdraw<'a> {
     pub accounts: WithdrawAccounts<'a>,
-    pub instruction_data: WithdrawInstructionData,
+    pub instruction_data: WithdrawInstructionData<'a>,
 }
 
 impl<'a> TryFrom<(&'a [u8], &'a [AccountInfo])> for Withdraw<'a> {
@@ -99,13 +141,40 @@ impl<'a> TryFrom<(&'a [u8], &'a [AccountInfo])> for Withdraw<'a> {
 impl<'a> Withdraw<'a> {
     pub const DISCRIMINATOR: &'a u8 = &2;
 
+    pub const DISCRIMINATOR_V2: &'a u8 = &21;
+
+    /// Build the instruction from version 2 data.
+    pub fn try_from_v2(
+        (data, accounts): (&'a [u8], &'a [AccountInfo]),
+    ) -> Result<Self, ProgramError> {
+        let accounts = WithdrawAccounts::try_from(accounts)?;
+        let instruction_data = WithdrawInstructionData::try_from_v2(data)?;
+
+        Ok(Self {
+            accounts,
+            instruction_data,
+        })
+    }
+
     pub fn process(&mut self) -> ProgramResult {
+        self.instruction_data.deadline.check()?;
+
         let config = Config::load(self.accounts.config)?;
 
-        if config.state().ne(&(AmmState::Initialized as u8)) {
+        // Paused pools still let LPs out while in withdraw-only mode
+        if config.state().ne(&(AmmState::Initialized as u8))
+            && config.state().ne(&(AmmState::WithdrawOnly as u8))
+        {
             return Err(ProgramError::InvalidAccountData);
         }
 
+        check_allowlist(
+            &config,
+            self.accounts.user,
+            &mut self.accounts.remaining_accounts.iter(),
+            self.instruction_data.proof,
+        )?;
+
         let (vault_x, _) = find_program_address(
             &[
                 self.accounts.config.key(),
@@ -132,29 +201,33 @@ impl<'a> Withdraw<'a> {
             return Err(ProgramError::InvalidAccountData);
         }
 
+        let (mint_lp, _) =
+            find_program_address(&[b"mint_lp", self.accounts.config.key()], &crate::ID);
+
+        if mint_lp.ne(self.accounts.mint_lp.key()) {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
         let mint_lp = unsafe { Mint::from_account_info_unchecked(self.accounts.mint_lp)? };
         let vault_x = unsafe { TokenAccount::from_account_info_unchecked(self.accounts.vault_x)? };
         let vault_y = unsafe { TokenAccount::from_account_info_unchecked(self.accounts.vault_y)? };
 
+        // Fees set aside for positions stay in the vaults until claimed
+        let (reserve_x, reserve_y) = config.reserves(vault_x.amount(), vault_y.amount());
+
         let (x, y) = match mint_lp.supply() == self.instruction_data.amount {
-            true => (vault_x.amount(), vault_y.amount()),
-            false => {
-                let amounts = ConstantProduct::xy_withdraw_amounts_from_l(
-                    vault_x.amount(),
-                    vault_y.amount(),
-                    mint_lp.supply(),
-                    self.instruction_data.amount,
-                    6,
-                )
-                .map_err(|_| ProgramError::InvalidArgument)?;
-
-                (amounts.x, amounts.y)
-            }
+            true => (reserve_x, reserve_y),
+            false => constant_product::withdraw_amounts(
+                reserve_x,
+                reserve_y,
+                mint_lp.supply(),
+                self.instruction_data.amount,
+            )?,
         };
 
         // Check for slippage
-        if !(x <= self.instruction_data.min_x && y <= self.instruction_data.min_y) {
-            return Err(ProgramError::InvalidArgument);
+        if x < self.instruction_data.min_x || y < self.instruction_data.min_y {
+            return Err(AmmError::SlippageExceeded.into());
         }
 
         let seed_binding = config.seed().to_le_bytes();
@@ -192,7 +265,20 @@ impl<'a> Withdraw<'a> {
         }
         .invoke()?;
 
+        drop(config);
+
+        // Keep the supply the fee growth is spread over in step with the mint
+        let mut config = Config::load_mut(self.accounts.config)?;
+        config.set_lp_supply(mint_lp.supply());
+
+        let clock = Clock::get()?;
+        let (weight_x, weight_y) = config.weights(clock.unix_timestamp);
+        let price = reserve_x
+            .checked_sub(x)
+            .zip(reserve_y.checked_sub(y))
+            .and_then(|(x, y)| spot_price(x, weight_x, y, weight_y).ok());
+        config.record_trade(clock.slot, clock.unix_timestamp, price);
 
         Ok(())
     }
-}
\ No newline at end of file
+}
@@ -1,10 +1,11 @@
 #![allow(unexpected_cfgs)]
 
 use pinocchio::{
-    account_info::AccountInfo, entrypoint, program_error::ProgramError, pubkey::Pubkey,
-    ProgramResult,
+    account_info::AccountInfo, program_error::ProgramError, pubkey::Pubkey, ProgramResult,
 };
-entrypoint!(process_instruction);
+
+#[cfg(not(feature = "no-entrypoint"))]
+pinocchio::entrypoint!(process_instruction);
 
 pub mod instructions;
 pub use instructions::*;
@@ -12,13 +13,32 @@ pub use instructions::*;
 pub mod state;
 pub use state::*;
 
+pub mod errors;
+pub use errors::*;
+
+pub mod events;
+pub use events::*;
+
+pub mod math;
+
+pub mod metadata;
+
+pub mod oracle;
+pub use oracle::*;
+
+pub mod hook;
+pub use hook::*;
+
+#[cfg(feature = "cpi")]
+pub mod cpi;
+
 // 22222222222222222222222222222222222222222222
 pub const ID: Pubkey = [
     0x0f, 0x1e, 0x6b, 0x14, 0x21, 0xc0, 0x4a, 0x07, 0x04, 0x31, 0x26, 0x5c, 0x19, 0xc5, 0xbb, 0xee,
     0x19, 0x92, 0xba, 0xe8, 0xaf, 0xd1, 0xcd, 0x07, 0x8e, 0xf8, 0xaf, 0x70, 0x47, 0xdc, 0x11, 0xf7,
 ];
 
-fn process_instruction(
+pub fn process_instruction(
     _program_id: &Pubkey,
     accounts: &[AccountInfo],
     instruction_data: &[u8],
@@ -30,6 +50,120 @@ fn process_instruction(
         Some((Deposit::DISCRIMINATOR, data)) => Deposit::try_from((data, accounts))?.process(),
         Some((Withdraw::DISCRIMINATOR, data)) => Withdraw::try_from((data, accounts))?.process(),
         Some((Swap::DISCRIMINATOR, data)) => Swap::try_from((data, accounts))?.process(),
+        Some((SetOracle::DISCRIMINATOR, data)) => SetOracle::try_from((data, accounts))?.process(),
+        Some((SetCircuitBreaker::DISCRIMINATOR, data)) => {
+            SetCircuitBreaker::try_from((data, accounts))?.process()
+        }
+        Some((SetDynamicFee::DISCRIMINATOR, data)) => {
+            SetDynamicFee::try_from((data, accounts))?.process()
+        }
+        Some((SetWeights::DISCRIMINATOR, data)) => {
+            SetWeights::try_from((data, accounts))?.process()
+        }
+        Some((InitializeMultiPool::DISCRIMINATOR, data)) => {
+            InitializeMultiPool::try_from((data, accounts))?.process()
+        }
+        Some((MultiSwap::DISCRIMINATOR, data)) => MultiSwap::try_from((data, accounts))?.process(),
+        Some((MultiDeposit::DISCRIMINATOR, data)) => {
+            MultiDeposit::try_from((data, accounts))?.process()
+        }
+        Some((MultiWithdraw::DISCRIMINATOR, data)) => {
+            MultiWithdraw::try_from((data, accounts))?.process()
+        }
+        Some((MultiJoin::DISCRIMINATOR, data)) => MultiJoin::try_from((data, accounts))?.process(),
+        Some((MultiExit::DISCRIMINATOR, data)) => MultiExit::try_from((data, accounts))?.process(),
+        Some((InitializeFarm::DISCRIMINATOR, data)) => {
+            InitializeFarm::try_from((data, accounts))?.process()
+        }
+        Some((Stake::DISCRIMINATOR, data)) => Stake::try_from((data, accounts))?.process(),
+        Some((Unstake::DISCRIMINATOR, data)) => Unstake::try_from((data, accounts))?.process(),
+        Some((ClaimRewards::DISCRIMINATOR, data)) => {
+            ClaimRewards::try_from((data, accounts))?.process()
+        }
+        Some((SetReferralFee::DISCRIMINATOR, data)) => {
+            SetReferralFee::try_from((data, accounts))?.process()
+        }
+        Some((Swap::DISCRIMINATOR_V2, data)) => Swap::try_from_v2((data, accounts))?.process(),
+        Some((Deposit::DISCRIMINATOR_V2, data)) => {
+            Deposit::try_from_v2((data, accounts))?.process()
+        }
+        Some((Withdraw::DISCRIMINATOR_V2, data)) => {
+            Withdraw::try_from_v2((data, accounts))?.process()
+        }
+        Some((InitializeAllowlist::DISCRIMINATOR, data)) => {
+            InitializeAllowlist::try_from((data, accounts))?.process()
+        }
+        Some((UpdateAllowlist::DISCRIMINATOR, data)) => {
+            UpdateAllowlist::try_from((data, accounts))?.process()
+        }
+        Some((SetGuardian::DISCRIMINATOR, data)) => {
+            SetGuardian::try_from((data, accounts))?.process()
+        }
+        Some((Pause::DISCRIMINATOR, data)) => Pause::try_from((data, accounts))?.process(),
+        Some((SetState::DISCRIMINATOR, data)) => SetState::try_from((data, accounts))?.process(),
+        Some((MigrateLiquidity::DISCRIMINATOR, data)) => {
+            MigrateLiquidity::try_from((data, accounts))?.process()
+        }
+        Some((LockLiquidity::DISCRIMINATOR, data)) => {
+            LockLiquidity::try_from((data, accounts))?.process()
+        }
+        Some((UnlockLiquidity::DISCRIMINATOR, data)) => {
+            UnlockLiquidity::try_from((data, accounts))?.process()
+        }
+        Some((StakeLocked::DISCRIMINATOR, data)) => {
+            StakeLocked::try_from((data, accounts))?.process()
+        }
+        Some((UnstakeLocked::DISCRIMINATOR, data)) => {
+            UnstakeLocked::try_from((data, accounts))?.process()
+        }
+        Some((IncreasePosition::DISCRIMINATOR, data)) => {
+            IncreasePosition::try_from((data, accounts))?.process()
+        }
+        Some((DecreasePosition::DISCRIMINATOR, data)) => {
+            DecreasePosition::try_from((data, accounts))?.process()
+        }
+        Some((ClaimFees::DISCRIMINATOR, data)) => ClaimFees::try_from((data, accounts))?.process(),
+        Some((InitializeLaunch::DISCRIMINATOR, data)) => {
+            InitializeLaunch::try_from((data, accounts))?.process()
+        }
+        Some((BuyLaunch::DISCRIMINATOR, data)) => BuyLaunch::try_from((data, accounts))?.process(),
+        Some((SellLaunch::DISCRIMINATOR, data)) => {
+            SellLaunch::try_from((data, accounts))?.process()
+        }
+        Some((Graduate::DISCRIMINATOR, data)) => Graduate::try_from((data, accounts))?.process(),
+        Some((PlaceRangeOrder::DISCRIMINATOR, data)) => {
+            PlaceRangeOrder::try_from((data, accounts))?.process()
+        }
+        Some((ClaimRangeOrder::DISCRIMINATOR, data)) => {
+            ClaimRangeOrder::try_from((data, accounts))?.process()
+        }
+        Some((CancelRangeOrder::DISCRIMINATOR, data)) => {
+            CancelRangeOrder::try_from((data, accounts))?.process()
+        }
+        Some((GetPoolState::DISCRIMINATOR, data)) => {
+            GetPoolState::try_from((data, accounts))?.process()
+        }
+        Some((CommitSwap::DISCRIMINATOR, data)) => {
+            CommitSwap::try_from((data, accounts))?.process()
+        }
+        Some((RevealSwap::DISCRIMINATOR, data)) => {
+            RevealSwap::try_from((data, accounts))?.process()
+        }
+        Some((SetBatchWindow::DISCRIMINATOR, data)) => {
+            SetBatchWindow::try_from((data, accounts))?.process()
+        }
+        Some((PlaceBatchOrder::DISCRIMINATOR, data)) => {
+            PlaceBatchOrder::try_from((data, accounts))?.process()
+        }
+        Some((SettleBatch::DISCRIMINATOR, data)) => {
+            SettleBatch::try_from((data, accounts))?.process()
+        }
+        Some((ClaimBatchOrder::DISCRIMINATOR, data)) => {
+            ClaimBatchOrder::try_from((data, accounts))?.process()
+        }
+        Some((SetSwapHook::DISCRIMINATOR, data)) => {
+            SetSwapHook::try_from((data, accounts))?.process()
+        }
         _ => Err(ProgramError::InvalidInstructionData),
     }
 }
\ No newline at end of file
@@ -0,0 +1,402 @@
+//! Constant product curve math on checked `u128` arithmetic.
+//!
+//! Every amount is rounded in favour of the pool: what the user pays in rounds up and
+//! what the user takes out rounds down. `Swap`, `Deposit` and `Withdraw` all go through
+//! these functions, so `x * y` never decreases across a swap and the reserves backing
+//! one LP token never decrease across a deposit or a withdrawal.
+
+use crate::errors::AmmError;
+use pinocchio::program_error::ProgramError;
+
+/// Failure of a curve computation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CurveError {
+    /// An intermediate value or the result does not fit its type.
+    Overflow,
+    /// The pool has no reserves or no LP supply to price against.
+    ZeroLiquidity,
+    /// The trade rounds down to nothing.
+    ZeroAmount,
+    /// The user would get less than the minimum they accept.
+    SlippageExceeded,
+    /// More LP is redeemed than exists.
+    InsufficientLiquidity,
+}
+
+impl From<CurveError> for ProgramError {
+    fn from(e: CurveError) -> Self {
+        match e {
+            CurveError::Overflow => AmmError::MathOverflow.into(),
+            CurveError::ZeroLiquidity => AmmError::ZeroLiquidity.into(),
+            CurveError::ZeroAmount => AmmError::ZeroAmount.into(),
+            CurveError::SlippageExceeded => AmmError::SlippageExceeded.into(),
+            CurveError::InsufficientLiquidity => AmmError::InsufficientLiquidity.into(),
+        }
+    }
+}
+
+/// Amounts moved by a swap, `fee` being the part of `deposit` left to the pool.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SwapResult {
+    pub deposit: u64,
+    pub withdraw: u64,
+    pub fee: u64,
+}
+
+/// Sell `amount_in` against `reserve_out`, charging `fee_bps` basis points of the input.
+///
+/// The fee rounds up and the output rounds down.
+pub fn swap(
+    reserve_in: u64,
+    reserve_out: u64,
+    amount_in: u64,
+    fee_bps: u16,
+    min_out: u64,
+) -> Result<SwapResult, CurveError> {
+    if reserve_in == 0 || reserve_out == 0 {
+        return Err(CurveError::ZeroLiquidity);
+    }
+    if fee_bps >= 10_000 {
+        return Err(CurveError::Overflow);
+    }
+
+    let fee = mul_div_ceil(amount_in, fee_bps as u64, 10_000)?;
+    let amount_in_after_fee = (amount_in - fee) as u128;
+
+    let withdraw = (reserve_out as u128)
+        .checked_mul(amount_in_after_fee)
+        .ok_or(CurveError::Overflow)?
+        .checked_div(
+            (reserve_in as u128)
+                .checked_add(amount_in_after_fee)
+                .ok_or(CurveError::Overflow)?,
+        )
+        .ok_or(CurveError::Overflow)?;
+    let withdraw = u64::try_from(withdraw).map_err(|_| CurveError::Overflow)?;
+
+    if withdraw == 0 {
+        return Err(CurveError::ZeroAmount);
+    }
+    if withdraw < min_out {
+        return Err(CurveError::SlippageExceeded);
+    }
+
+    Ok(SwapResult {
+        deposit: amount_in,
+        withdraw,
+        fee,
+    })
+}
+
+/// Tokens to pay in for `lp` new LP tokens, rounded up.
+pub fn deposit_amounts(
+    reserve_x: u64,
+    reserve_y: u64,
+    supply: u64,
+    lp: u64,
+) -> Result<(u64, u64), CurveError> {
+    if supply == 0 || reserve_x == 0 || reserve_y == 0 {
+        return Err(CurveError::ZeroLiquidity);
+    }
+    if lp == 0 {
+        return Err(CurveError::ZeroAmount);
+    }
+
+    Ok((
+        mul_div_ceil(reserve_x, lp, supply)?,
+        mul_div_ceil(reserve_y, lp, supply)?,
+    ))
+}
+
+/// Most LP tokens that `x` and `y` can pay for at the pool ratio, rounded down.
+pub fn lp_for_amounts(
+    reserve_x: u64,
+    reserve_y: u64,
+    supply: u64,
+    x: u64,
+    y: u64,
+) -> Result<u64, CurveError> {
+    if supply == 0 || reserve_x == 0 || reserve_y == 0 {
+        return Err(CurveError::ZeroLiquidity);
+    }
+
+    let lp = mul_div_floor(x, supply, reserve_x)?.min(mul_div_floor(y, supply, reserve_y)?);
+
+    match lp {
+        0 => Err(CurveError::ZeroAmount),
+        lp => Ok(lp),
+    }
+}
+
+/// Tokens paid out for burning `lp` LP tokens, rounded down.
+pub fn withdraw_amounts(
+    reserve_x: u64,
+    reserve_y: u64,
+    supply: u64,
+    lp: u64,
+) -> Result<(u64, u64), CurveError> {
+    if supply == 0 {
+        return Err(CurveError::ZeroLiquidity);
+    }
+    if lp == 0 {
+        return Err(CurveError::ZeroAmount);
+    }
+    if lp > supply {
+        return Err(CurveError::InsufficientLiquidity);
+    }
+
+    Ok((
+        mul_div_floor(reserve_x, lp, supply)?,
+        mul_div_floor(reserve_y, lp, supply)?,
+    ))
+}
+
+/// Tokens a batch pays out once cleared, `out_x` to the sellers of y and `out_y` to the
+/// sellers of x.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BatchResult {
+    pub out_x: u64,
+    pub out_y: u64,
+}
+
+/// Clear `in_x` sold for y against `in_y` sold for x at one price.
+///
+/// The two sides match each other and only the imbalance trades against the curve, sized
+/// so that its average price is also the price of the matched part. Selling `d` of x
+/// into the pool at `y / (x + d)` clears the batch when
+/// `d = (in_x * y - in_y * x) / (y + in_y)`, and symmetrically for y. The imbalance and
+/// its output round down, so the pool keeps `x * y` and the payouts fit the inputs.
+pub fn clear_batch(
+    reserve_x: u64,
+    reserve_y: u64,
+    in_x: u64,
+    in_y: u64,
+) -> Result<BatchResult, CurveError> {
+    if reserve_x == 0 || reserve_y == 0 {
+        return Err(CurveError::ZeroLiquidity);
+    }
+
+    let x_side = in_x as u128 * reserve_y as u128;
+    let y_side = in_y as u128 * reserve_x as u128;
+
+    match x_side >= y_side {
+        true => {
+            let (net_in, net_out) = net_trade(x_side - y_side, reserve_x, reserve_y, in_y)?;
+            Ok(BatchResult {
+                out_x: in_x - net_in,
+                out_y: in_y.checked_add(net_out).ok_or(CurveError::Overflow)?,
+            })
+        }
+        false => {
+            let (net_in, net_out) = net_trade(y_side - x_side, reserve_y, reserve_x, in_x)?;
+            Ok(BatchResult {
+                out_x: in_x.checked_add(net_out).ok_or(CurveError::Overflow)?,
+                out_y: in_y - net_in,
+            })
+        }
+    }
+}
+
+/// Imbalance of a batch sold into the pool and its output, given the excess of one side
+/// in the other token times the reserves.
+#[inline(always)]
+fn net_trade(
+    excess: u128,
+    reserve_in: u64,
+    reserve_out: u64,
+    in_other: u64,
+) -> Result<(u64, u64), CurveError> {
+    let net_in = u64::try_from(excess / (reserve_out as u128 + in_other as u128))
+        .map_err(|_| CurveError::Overflow)?;
+    let net_out = mul_div_floor(
+        reserve_out,
+        net_in,
+        reserve_in.checked_add(net_in).ok_or(CurveError::Overflow)?,
+    )?;
+    Ok((net_in, net_out))
+}
+
+/// `a * b / c`, rounded down.
+#[inline(always)]
+fn mul_div_floor(a: u64, b: u64, c: u64) -> Result<u64, CurveError> {
+    let r = (a as u128)
+        .checked_mul(b as u128)
+        .and_then(|r| r.checked_div(c as u128))
+        .ok_or(CurveError::Overflow)?;
+    u64::try_from(r).map_err(|_| CurveError::Overflow)
+}
+
+/// `a * b / c`, rounded up.
+#[inline(always)]
+fn mul_div_ceil(a: u64, b: u64, c: u64) -> Result<u64, CurveError> {
+    if c == 0 {
+        return Err(CurveError::Overflow);
+    }
+    let r = (a as u128)
+        .checked_mul(b as u128)
+        .ok_or(CurveError::Overflow)?
+        .div_ceil(c as u128);
+    u64::try_from(r).map_err(|_| CurveError::Overflow)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    /// Full 256-bit product of two `u128`, as `(high, low)`.
+    fn mul_wide(a: u128, b: u128) -> (u128, u128) {
+        const MASK: u128 = u64::MAX as u128;
+        let (a_hi, a_lo) = (a >> 64, a & MASK);
+        let (b_hi, b_lo) = (b >> 64, b & MASK);
+
+        let lo_lo = a_lo * b_lo;
+        let hi_lo = a_hi * b_lo;
+        let lo_hi = a_lo * b_hi;
+        let hi_hi = a_hi * b_hi;
+
+        let cross = (lo_lo >> 64) + (hi_lo & MASK) + (lo_hi & MASK);
+        let low = (cross << 64) | (lo_lo & MASK);
+        let high = hi_hi + (hi_lo >> 64) + (lo_hi >> 64) + (cross >> 64);
+        (high, low)
+    }
+
+    /// `x * y / supply^2` never decreases from `before` to `after`.
+    fn assert_k_per_lp_kept(before: (u64, u64, u64), after: (u64, u64, u64)) {
+        let k = |(x, y, _): (u64, u64, u64)| x as u128 * y as u128;
+        let supply_squared = |(_, _, s): (u64, u64, u64)| s as u128 * s as u128;
+
+        assert!(
+            mul_wide(k(after), supply_squared(before))
+                >= mul_wide(k(before), supply_squared(after)),
+            "k per LP decreased from {before:?} to {after:?}"
+        );
+    }
+
+    #[derive(Clone, Debug)]
+    enum Op {
+        Swap {
+            is_x: bool,
+            amount: u64,
+            fee_bps: u16,
+        },
+        Deposit {
+            lp: u64,
+        },
+        Withdraw {
+            lp: u64,
+        },
+    }
+
+    fn op() -> impl Strategy<Value = Op> {
+        prop_oneof![
+            (any::<bool>(), 1..1u64 << 40, 0..1_000u16).prop_map(|(is_x, amount, fee_bps)| {
+                Op::Swap {
+                    is_x,
+                    amount,
+                    fee_bps,
+                }
+            }),
+            (1..1u64 << 40).prop_map(|lp| Op::Deposit { lp }),
+            (1..1u64 << 40).prop_map(|lp| Op::Withdraw { lp }),
+        ]
+    }
+
+    proptest! {
+        #[test]
+        fn swap_never_decreases_k(
+            reserve_in in 1..u64::MAX >> 1,
+            reserve_out in 1..u64::MAX >> 1,
+            amount_in in 1..u64::MAX >> 1,
+            fee_bps in 0..10_000u16,
+        ) {
+            let Ok(result) = swap(reserve_in, reserve_out, amount_in, fee_bps, 0) else {
+                return Ok(());
+            };
+
+            prop_assert!(result.withdraw < reserve_out);
+            prop_assert!(result.fee <= amount_in);
+            prop_assert!(
+                (reserve_in as u128 + result.deposit as u128)
+                    * (reserve_out - result.withdraw) as u128
+                    >= reserve_in as u128 * reserve_out as u128
+            );
+        }
+
+        #[test]
+        fn deposit_then_withdraw_never_pays_out_more(
+            reserve_x in 1..u64::MAX >> 2,
+            reserve_y in 1..u64::MAX >> 2,
+            supply in 1..u64::MAX >> 2,
+            lp in 1..u64::MAX >> 2,
+        ) {
+            let Ok((x, y)) = deposit_amounts(reserve_x, reserve_y, supply, lp) else {
+                return Ok(());
+            };
+            let (Some(after_x), Some(after_y)) = (reserve_x.checked_add(x), reserve_y.checked_add(y))
+            else {
+                return Ok(());
+            };
+            let (out_x, out_y) = withdraw_amounts(after_x, after_y, supply + lp, lp).unwrap();
+
+            prop_assert!(out_x <= x && out_y <= y);
+        }
+
+        #[test]
+        fn clear_batch_never_decreases_k_or_pays_out_more(
+            reserve_x in 1..1u64 << 48,
+            reserve_y in 1..1u64 << 48,
+            in_x in 0..1u64 << 48,
+            in_y in 0..1u64 << 48,
+        ) {
+            let result = clear_batch(reserve_x, reserve_y, in_x, in_y).unwrap();
+
+            // What the pool gains in one token it pays out in the other
+            let x = reserve_x as i128 + in_x as i128 - result.out_x as i128;
+            let y = reserve_y as i128 + in_y as i128 - result.out_y as i128;
+
+            prop_assert!(x > 0 && y > 0);
+            prop_assert!(x * y >= reserve_x as i128 * reserve_y as i128);
+        }
+
+        #[test]
+        fn k_per_lp_never_decreases(
+            reserve_x in 1_000..1u64 << 40,
+            reserve_y in 1_000..1u64 << 40,
+            supply in 1_000..1u64 << 40,
+            ops in proptest::collection::vec(op(), 1..32),
+        ) {
+            let mut pool = (reserve_x, reserve_y, supply);
+
+            for op in ops {
+                let (x, y, supply) = pool;
+                // Operations that fail, or would overflow the pool, are skipped
+                let next = match op {
+                    Op::Swap { is_x: true, amount, fee_bps } => swap(x, y, amount, fee_bps, 0)
+                        .ok()
+                        .and_then(|r| Some((x.checked_add(r.deposit)?, y - r.withdraw, supply))),
+                    Op::Swap { is_x: false, amount, fee_bps } => swap(y, x, amount, fee_bps, 0)
+                        .ok()
+                        .and_then(|r| Some((x - r.withdraw, y.checked_add(r.deposit)?, supply))),
+                    Op::Deposit { lp } => deposit_amounts(x, y, supply, lp)
+                        .ok()
+                        .and_then(|(dx, dy)| {
+                            Some((x.checked_add(dx)?, y.checked_add(dy)?, supply.checked_add(lp)?))
+                        }),
+                    // Keep some supply so the ratio stays defined
+                    Op::Withdraw { lp } => withdraw_amounts(x, y, supply, lp.min(supply - 1))
+                        .ok()
+                        .map(|(dx, dy)| (x - dx, y - dy, supply - lp.min(supply - 1))),
+                };
+
+                let Some(next) = next else { continue };
+                if next.0 == 0 || next.1 == 0 || next.2 == 0 {
+                    continue;
+                }
+
+                assert_k_per_lp_kept(pool, next);
+                pool = next;
+            }
+        }
+    }
+}
@@ -0,0 +1,49 @@
+use pinocchio::pubkey::Pubkey;
+
+/// Size of a hash, and of each node in a proof.
+pub const HASH_LEN: usize = 32;
+
+/// SHA-256 of the concatenation of `vals`.
+#[inline(always)]
+pub fn hashv(vals: &[&[u8]]) -> [u8; HASH_LEN] {
+    #[cfg(target_os = "solana")]
+    {
+        let mut hash = core::mem::MaybeUninit::<[u8; HASH_LEN]>::uninit();
+
+        unsafe {
+            pinocchio::syscalls::sol_sha256(
+                vals as *const _ as *const u8,
+                vals.len() as u64,
+                hash.as_mut_ptr() as *mut _,
+            );
+            hash.assume_init()
+        }
+    }
+
+    #[cfg(not(target_os = "solana"))]
+    {
+        core::hint::black_box(vals);
+        panic!("hashv is only available on target `solana`")
+    }
+}
+
+/// Check that `key` is a leaf of the merkle tree with `root`.
+///
+/// Leaves are `sha256(key)` and each parent hashes its two children in ascending order,
+/// so `proof` is the list of siblings from the leaf up, without any direction bits.
+pub fn verify_proof(root: &[u8; HASH_LEN], key: &Pubkey, proof: &[u8]) -> bool {
+    if !proof.len().is_multiple_of(HASH_LEN) {
+        return false;
+    }
+
+    let node = proof
+        .chunks_exact(HASH_LEN)
+        .fold(hashv(&[key]), |node, sibling| {
+            match node.as_slice() <= sibling {
+                true => hashv(&[&node, sibling]),
+                false => hashv(&[sibling, &node]),
+            }
+        });
+
+    node.eq(root)
+}
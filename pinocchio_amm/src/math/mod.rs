@@ -0,0 +1,7 @@
+pub mod constant_product;
+
+pub mod merkle;
+pub use merkle::*;
+
+pub mod weighted;
+pub use weighted::*;
@@ -0,0 +1,408 @@
+//! Fixed-point math for weighted pools, on `ONE` = 1e18 scaled `u128` values.
+
+/// Fixed-point one.
+pub const ONE: u128 = 1_000_000_000_000_000_000;
+
+/// ln(2) scaled by `ONE`.
+const LN_2: u128 = 693_147_180_559_945_309;
+
+/// Upper bound of the relative error of `pow`, added to every power so the rounding
+/// always favours the pool. The error of the series stays around 1e-14 for exponents up
+/// to 100, the bound of 1e-12 leaves a wide margin over it.
+const MAX_POW_RELATIVE_ERROR: u128 = 1_000_000;
+
+/// Natural logarithm of `x >= ONE`.
+fn ln(x: u128) -> Option<u128> {
+    if x < ONE {
+        return None;
+    }
+
+    // x = m * 2^k with m in [ONE, 2 * ONE)
+    let k = (x / ONE).ilog2();
+    let m = x >> k;
+
+    // ln(m) = 2 * atanh(z) = 2 * (z + z^3 / 3 + z^5 / 5 + ...), z = (m - 1) / (m + 1) < 1/3
+    let z = (m - ONE).checked_mul(ONE)? / (m + ONE);
+    let z_squared = z * z / ONE;
+
+    let mut term = z;
+    let mut sum = z;
+    let mut n = 3;
+    while term != 0 {
+        term = term * z_squared / ONE;
+        sum += term / n;
+        n += 2;
+    }
+
+    (k as u128).checked_mul(LN_2)?.checked_add(sum * 2)
+}
+
+/// e^(-y) for `y >= 0`.
+fn exp_neg(y: u128) -> Option<u128> {
+    // e^(-y) = e^(-r) / 2^k with r in [0, ln(2))
+    let k = y / LN_2;
+    if k >= 128 {
+        return Some(0);
+    }
+    let r = y - k * LN_2;
+
+    // e^r = 1 + r + r^2 / 2! + ...
+    let mut term = ONE;
+    let mut sum = ONE;
+    let mut n = 1;
+    while term != 0 {
+        term = term * r / ONE / n;
+        sum += term;
+        n += 1;
+    }
+
+    Some((ONE.checked_mul(ONE)? / sum) >> k)
+}
+
+/// `base ^ exponent` for `base <= ONE`, rounded up.
+pub fn pow_up(base: u128, exponent: u128) -> Option<u128> {
+    if base > ONE {
+        return None;
+    }
+    if base == 0 {
+        return Some(0);
+    }
+
+    // base^e = e^(-e * ln(1 / base))
+    let ln_inverse = ln(ONE.checked_mul(ONE)?.div_ceil(base))?;
+    let exponent_ln = (exponent / ONE)
+        .checked_mul(ln_inverse)?
+        .checked_add(exponent % ONE * ln_inverse / ONE)?;
+    let power = exp_neg(exponent_ln)?;
+
+    Some(
+        power
+            .checked_add(power.checked_mul(MAX_POW_RELATIVE_ERROR)?.div_ceil(ONE))?
+            .checked_add(1)?
+            .min(ONE),
+    )
+}
+
+/// Weight in basis points, interpolated linearly from `start` at `start_time` to `end` at
+/// `end_time`.
+pub fn interpolate_weight(start: u16, end: u16, start_time: i64, end_time: i64, now: i64) -> u16 {
+    if now <= start_time || end_time <= start_time {
+        return start;
+    }
+    if now >= end_time {
+        return end;
+    }
+
+    let elapsed = (now - start_time) as i128;
+    let duration = (end_time - start_time) as i128;
+    let delta = (end as i128 - start as i128) * elapsed / duration;
+
+    (start as i128 + delta) as u16
+}
+
+/// Amount of the out token paid for `amount_in`, after fees, on the weighted invariant
+/// `B_x^W_x * B_y^W_y = k`, rounded down:
+///
+/// `out = B_out * (1 - (B_in / (B_in + amount_in)) ^ (W_in / W_out))`
+pub fn out_given_in(
+    balance_in: u64,
+    weight_in: u16,
+    balance_out: u64,
+    weight_out: u16,
+    amount_in: u64,
+) -> Option<u64> {
+    if balance_in == 0 || balance_out == 0 || weight_in == 0 || weight_out == 0 {
+        return None;
+    }
+
+    let denominator = (balance_in as u128).checked_add(amount_in as u128)?;
+    let base = (balance_in as u128 * ONE).div_ceil(denominator);
+    let exponent = weight_in as u128 * ONE / weight_out as u128;
+
+    let power = pow_up(base, exponent)?;
+
+    u64::try_from(balance_out as u128 * (ONE - power) / ONE).ok()
+}
+
+/// Amount of the in token that takes its spot price from `price` down to `target`, on the
+/// weighted invariant, rounded down:
+///
+/// `amount = B_in * ((price / target) ^ (W_out / (W_in + W_out)) - 1)`
+///
+/// Both prices only need to share a scale. Returns `0` when the price is already at or
+/// below `target`.
+pub fn in_given_price(
+    balance_in: u64,
+    weight_in: u16,
+    weight_out: u16,
+    price: u128,
+    target: u128,
+) -> Option<u64> {
+    if weight_in == 0 || weight_out == 0 || target == 0 {
+        return None;
+    }
+    if price <= target {
+        return Some(0);
+    }
+
+    // target / price rounded up, both shifted down to fit when `target * ONE` would not
+    let shift = (u128::BITS - target.leading_zeros()).saturating_sub(68);
+    let lost = target & ((1u128 << shift) - 1) != 0;
+    let base = ((target >> shift) + lost as u128)
+        .checked_mul(ONE)?
+        .div_ceil(price >> shift)
+        .min(ONE);
+    let exponent = weight_out as u128 * ONE / (weight_in as u128 + weight_out as u128);
+
+    let power = pow_up(base, exponent)?;
+    if power == 0 {
+        return None;
+    }
+
+    let amount = (balance_in as u128 * ONE / power).checked_sub(balance_in as u128)?;
+    u64::try_from(amount).ok()
+}
+
+/// Price of x in y, `(B_y / W_y) / (B_x / W_x)`, scaled by `scale`, rounded down.
+///
+/// The balance ratio is scaled before the weights apply, so scales up to 1e18 fit `u128`
+/// for any pair of balances.
+pub fn spot_price(
+    balance_x: u64,
+    weight_x: u16,
+    balance_y: u64,
+    weight_y: u16,
+    scale: u128,
+) -> Option<u128> {
+    if balance_x == 0 || weight_y == 0 {
+        return None;
+    }
+
+    let ratio = (balance_y as u128).checked_mul(scale)? / balance_x as u128;
+    let (quotient, remainder) = (ratio / weight_y as u128, ratio % weight_y as u128);
+
+    quotient
+        .checked_mul(weight_x as u128)?
+        .checked_add(remainder * weight_x as u128 / weight_y as u128)
+}
+
+/// LP minted for a single-asset join of `amount_in`, after fees, rounded down:
+///
+/// `lp = supply * (((B + amount_in) / B) ^ W - 1)`
+pub fn lp_out_given_in(balance: u64, weight: u16, supply: u64, amount_in: u64) -> Option<u64> {
+    if balance == 0 || supply == 0 || weight == 0 {
+        return None;
+    }
+
+    let denominator = (balance as u128).checked_add(amount_in as u128)?;
+    let base = (balance as u128 * ONE).div_ceil(denominator);
+    let exponent = weight as u128 * ONE / 10_000;
+
+    let power = pow_up(base, exponent)?;
+    if power == 0 {
+        return None;
+    }
+
+    let lp = (supply as u128 * ONE / power).checked_sub(supply as u128)?;
+    u64::try_from(lp).ok()
+}
+
+/// Amount paid for a single-asset exit burning `lp_in`, before fees, rounded down:
+///
+/// `out = B * (1 - ((supply - lp_in) / supply) ^ (1 / W))`
+pub fn out_given_lp_in(balance: u64, weight: u16, supply: u64, lp_in: u64) -> Option<u64> {
+    if supply == 0 || weight == 0 || lp_in > supply {
+        return None;
+    }
+
+    let base = ((supply - lp_in) as u128 * ONE).div_ceil(supply as u128);
+    let exponent = 10_000 * ONE / weight as u128;
+
+    let power = pow_up(base, exponent)?;
+
+    u64::try_from(balance as u128 * (ONE - power) / ONE).ok()
+}
+
+/// Part of `amount` charged as fee on a single-asset join or exit: only the share of
+/// the amount implicitly swapped into the other tokens, `1 - W`, pays the fee.
+pub fn single_asset_fee(amount: u64, weight: u16, fee: u16) -> u64 {
+    (amount as u128 * (10_000 - weight as u128) * fee as u128).div_ceil(10_000 * 10_000) as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::MIN_WEIGHT;
+    use proptest::prelude::*;
+
+    /// Distance between `a` and `b`, relative to `b`.
+    fn relative_error(a: u128, b: u128) -> f64 {
+        a.abs_diff(b) as f64 / b as f64
+    }
+
+    /// `(balance_in + amount_in)^a * (balance_out - out)^b` against the same product
+    /// before the swap, for weights in the ratio `a:b`.
+    fn assert_invariant_kept(
+        (a, b): (u32, u32),
+        (balance_in, balance_out): (u64, u64),
+        amount_in: u64,
+        out: u64,
+    ) {
+        let invariant = |x: u64, y: u64| (x as u128).pow(a) * (y as u128).pow(b);
+
+        let before = invariant(balance_in, balance_out);
+        let after = invariant(balance_in + amount_in, balance_out - out);
+
+        assert!(
+            after >= before,
+            "invariant decreased from {before} to {after}"
+        );
+    }
+
+    #[test]
+    fn ln_matches_known_values() {
+        // ln(2), ln(e) and ln(10), scaled by ONE
+        for (x, expected) in [
+            (2 * ONE, LN_2),
+            (2_718_281_828_459_045_235, ONE),
+            (10 * ONE, 2_302_585_092_994_045_684),
+        ] {
+            assert!(relative_error(ln(x).unwrap(), expected) < 1e-15);
+        }
+
+        assert_eq!(ln(ONE), Some(0));
+        assert_eq!(ln(ONE - 1), None);
+    }
+
+    #[test]
+    fn exp_neg_matches_known_values() {
+        // e^-1, e^-(ln 2) and e^-10, scaled by ONE
+        for (y, expected) in [
+            (ONE, 367_879_441_171_442_321),
+            (LN_2, ONE / 2),
+            (10 * ONE, 45_399_929_762_484),
+        ] {
+            assert!(relative_error(exp_neg(y).unwrap(), expected) < 1e-12);
+        }
+
+        assert_eq!(exp_neg(0), Some(ONE));
+    }
+
+    proptest! {
+        #[test]
+        fn exp_neg_inverts_ln(x in ONE..ONE << 32) {
+            let inverse = exp_neg(ln(x).unwrap()).unwrap();
+
+            prop_assert!(relative_error(inverse, ONE * ONE / x) < 1e-12);
+        }
+
+        #[test]
+        fn pow_up_never_rounds_down(
+            base in 1..=ONE,
+            exponent in 1..ONE * 100,
+        ) {
+            let power = pow_up(base, exponent).unwrap();
+            // Close to ONE, f64 holds the distance to ONE far more precisely than the
+            // base itself, whose error large exponents amplify
+            let ln_base = match base < ONE / 2 {
+                true => (base as f64 / ONE as f64).ln(),
+                false => (-((ONE - base) as f64 / ONE as f64)).ln_1p(),
+            };
+            let exact = (exponent as f64 / ONE as f64 * ln_base).exp();
+
+            prop_assert!(power <= ONE);
+            prop_assert!(power as f64 / ONE as f64 >= exact);
+        }
+
+        #[test]
+        fn swap_never_decreases_weighted_invariant(
+            // Weights in bps, with their exponents in lowest terms
+            (weights, exponents) in prop::sample::select(vec![
+                ((5_000, 5_000), (1, 1)),
+                ((8_000, 2_000), (4, 1)),
+                ((2_000, 8_000), (1, 4)),
+                ((6_000, 4_000), (3, 2)),
+                ((4_000, 6_000), (2, 3)),
+            ]),
+            balance_in in 1..1u64 << 24,
+            balance_out in 1..1u64 << 24,
+            amount_in in 0..1u64 << 24,
+        ) {
+            let out = out_given_in(balance_in, weights.0, balance_out, weights.1, amount_in)
+                .unwrap();
+
+            prop_assert!(out < balance_out);
+            assert_invariant_kept(exponents, (balance_in, balance_out), amount_in, out);
+        }
+
+        #[test]
+        fn out_given_in_rounds_in_pool_favour(
+            balance_in in 1..1u64 << 40,
+            weight_in in MIN_WEIGHT..=10_000 - MIN_WEIGHT,
+            balance_out in 1..1u64 << 40,
+            amount_in in 0..1u64 << 40,
+        ) {
+            let weight_out = 10_000 - weight_in;
+            let out = out_given_in(balance_in, weight_in, balance_out, weight_out, amount_in)
+                .unwrap();
+
+            let ratio = balance_in as f64 / (balance_in as f64 + amount_in as f64);
+            let exact = balance_out as f64
+                * (1.0 - ratio.powf(weight_in as f64 / weight_out as f64));
+
+            // Leave room for the f64 error of the reference value
+            prop_assert!((out as f64) <= exact + 1e-2);
+        }
+
+        #[test]
+        fn join_then_exit_never_pays_out_more(
+            balance in 1..1u64 << 40,
+            weight in MIN_WEIGHT..=10_000 - MIN_WEIGHT,
+            supply in 1..1u64 << 40,
+            amount_in in 1..1u64 << 40,
+            fee in 0..1_000u16,
+        ) {
+            // Mirrors MultiJoin followed by MultiExit of the minted LP
+            let join_fee = single_asset_fee(amount_in, weight, fee);
+            let Some(lp) = lp_out_given_in(balance, weight, supply, amount_in - join_fee) else {
+                return Ok(());
+            };
+
+            let Some(supply_after) = supply.checked_add(lp) else {
+                return Ok(());
+            };
+
+            let out = out_given_lp_in(balance + amount_in, weight, supply_after, lp).unwrap();
+            let out = out - single_asset_fee(out, weight, fee);
+
+            prop_assert!(out <= amount_in);
+        }
+
+        #[test]
+        fn interpolate_weight_stays_between_endpoints(
+            start in 0..=10_000u16,
+            end in 0..=10_000u16,
+            start_time in -(1i64 << 40)..1i64 << 40,
+            duration in 1..1i64 << 32,
+            now in -(1i64 << 41)..1i64 << 41,
+        ) {
+            let end_time = start_time + duration;
+            let weight = interpolate_weight(start, end, start_time, end_time, now);
+
+            prop_assert!(start.min(end) <= weight && weight <= start.max(end));
+            prop_assert_eq!(interpolate_weight(start, end, start_time, end_time, start_time), start);
+            prop_assert_eq!(interpolate_weight(start, end, start_time, end_time, end_time), end);
+        }
+
+        #[test]
+        fn spot_price_of_equal_weights_is_the_balance_ratio(
+            balance_x in 1..u64::MAX,
+            balance_y in 0..u64::MAX,
+            weight in 1..10_000u16,
+        ) {
+            let price = spot_price(balance_x, weight, balance_y, weight, ONE).unwrap();
+
+            prop_assert_eq!(price, balance_y as u128 * ONE / balance_x as u128);
+        }
+    }
+}
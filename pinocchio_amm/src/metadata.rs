@@ -0,0 +1,139 @@
+use pinocchio::{
+    account_info::AccountInfo,
+    cpi::invoke_signed,
+    instruction::{AccountMeta, Instruction, Signer},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    ProgramResult,
+};
+
+// metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s
+pub const TOKEN_METADATA_ID: Pubkey = [
+    0x0b, 0x70, 0x65, 0xb1, 0xe3, 0xd1, 0x7c, 0x45, 0x38, 0x9d, 0x52, 0x7f, 0x6b, 0x04, 0xc3, 0xcd,
+    0x58, 0xb8, 0x6c, 0x73, 0x1a, 0xa0, 0xfd, 0xb5, 0x49, 0xb6, 0xd1, 0xbc, 0x03, 0xf8, 0x29, 0x46,
+];
+
+pub const MAX_NAME_LEN: usize = 32;
+pub const MAX_SYMBOL_LEN: usize = 10;
+pub const MAX_URI_LEN: usize = 200;
+
+/// Name, symbol and URI attached to an LP mint.
+///
+/// Encoded as three strings, each prefixed by its `u8` length.
+pub struct LpMetadata<'a> {
+    pub name: &'a str,
+    pub symbol: &'a str,
+    pub uri: &'a str,
+}
+
+impl<'a> TryFrom<&'a [u8]> for LpMetadata<'a> {
+    type Error = ProgramError;
+
+    fn try_from(data: &'a [u8]) -> Result<Self, Self::Error> {
+        let (name, data) = read_str(data, MAX_NAME_LEN)?;
+        let (symbol, data) = read_str(data, MAX_SYMBOL_LEN)?;
+        let (uri, data) = read_str(data, MAX_URI_LEN)?;
+
+        if !data.is_empty() {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        Ok(Self { name, symbol, uri })
+    }
+}
+
+#[inline(always)]
+fn read_str(data: &[u8], max_len: usize) -> Result<(&str, &[u8]), ProgramError> {
+    let (len, data) = data
+        .split_first()
+        .ok_or(ProgramError::InvalidInstructionData)?;
+    let len = *len as usize;
+
+    if len > max_len || data.len() < len {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+
+    let (value, data) = data.split_at(len);
+    let value = core::str::from_utf8(value).map_err(|_| ProgramError::InvalidInstructionData)?;
+
+    Ok((value, data))
+}
+
+/// Create the metadata account of `mint` through the token metadata program.
+///
+/// ### Accounts:
+///   0. `[WRITE]` Metadata account
+///   1. `[]` Mint account
+///   2. `[SIGNER]` Mint authority
+///   3. `[WRITE, SIGNER]` Payer
+///   4. `[]` Update authority
+///   5. `[]` System program
+pub struct CreateMetadataAccountV3<'a> {
+    pub metadata: &'a AccountInfo,
+    pub mint: &'a AccountInfo,
+    pub mint_authority: &'a AccountInfo,
+    pub payer: &'a AccountInfo,
+    pub update_authority: &'a AccountInfo,
+    pub system_program: &'a AccountInfo,
+    pub lp_metadata: &'a LpMetadata<'a>,
+}
+
+impl CreateMetadataAccountV3<'_> {
+    const DISCRIMINATOR: u8 = 33;
+
+    pub fn invoke_signed(&self, signers: &[Signer]) -> ProgramResult {
+        let account_metas = [
+            AccountMeta::writable(self.metadata.key()),
+            AccountMeta::readonly(self.mint.key()),
+            AccountMeta::readonly_signer(self.mint_authority.key()),
+            AccountMeta::writable_signer(self.payer.key()),
+            AccountMeta::readonly(self.update_authority.key()),
+            AccountMeta::readonly(self.system_program.key()),
+        ];
+
+        // discriminator + 3 borsh strings + seller fee + 3 empty options
+        // + is_mutable + empty collection details
+        let mut data =
+            [0u8; 1 + 4 * 3 + MAX_NAME_LEN + MAX_SYMBOL_LEN + MAX_URI_LEN + 2 + 3 + 1 + 1];
+        data[0] = Self::DISCRIMINATOR;
+
+        let mut offset = 1;
+        for value in [
+            self.lp_metadata.name,
+            self.lp_metadata.symbol,
+            self.lp_metadata.uri,
+        ] {
+            data[offset..offset + 4].copy_from_slice(&(value.len() as u32).to_le_bytes());
+            offset += 4;
+            data[offset..offset + value.len()].copy_from_slice(value.as_bytes());
+            offset += value.len();
+        }
+
+        // No seller fee, creators, collection or uses
+        offset += 2 + 3;
+        // Mutable
+        data[offset] = 1;
+        offset += 1;
+        // No collection details
+        offset += 1;
+
+        let instruction = Instruction {
+            program_id: &TOKEN_METADATA_ID,
+            accounts: &account_metas,
+            data: &data[..offset],
+        };
+
+        invoke_signed(
+            &instruction,
+            &[
+                self.metadata,
+                self.mint,
+                self.mint_authority,
+                self.payer,
+                self.update_authority,
+                self.system_program,
+            ],
+            signers,
+        )
+    }
+}
@@ -0,0 +1,51 @@
+use crate::errors::AmmError;
+use core::mem::size_of;
+use pinocchio::{account_info::AccountInfo, program_error::ProgramError};
+
+/// Fixed-point scale of every price handled by the pool.
+///
+/// A price is the amount of `mint_y` base units paid for one base unit of `mint_x`,
+/// multiplied by `PRICE_SCALE`. The 18 decimals keep pairs priced far below one base
+/// unit, such as a 9 decimal token against a 6 decimal stablecoin, away from zero.
+pub const PRICE_SCALE: u128 = 1_000_000_000_000_000_000;
+
+/// Price feed account read by `Swap` when the pool has an oracle configured.
+///
+/// Any program can act as the feed, only the account key stored in `Config` is trusted.
+/// All fields are little-endian:
+///
+/// | offset | size | field        | description                                              |
+/// |--------|------|--------------|----------------------------------------------------------|
+/// | 0      | 8    | `magic`      | `PriceFeed::MAGIC`                                       |
+/// | 8      | 16   | `price`      | `u128` price of `mint_x` in `mint_y`, see `PRICE_SCALE` |
+/// | 24     | 8    | `updated_at` | `i64` unix timestamp of the last update                  |
+#[repr(C)]
+pub struct PriceFeed {
+    magic: [u8; 8],
+    price: [u8; 16],
+    updated_at: [u8; 8],
+}
+
+impl PriceFeed {
+    pub const LEN: usize = size_of::<[u8; 8]>() + size_of::<u128>() + size_of::<i64>();
+
+    pub const MAGIC: [u8; 8] = *b"AMMPRICE";
+
+    /// Read the feed, the account must be at least `PriceFeed::LEN` bytes long and hold a
+    /// non-zero price.
+    #[inline(always)]
+    pub fn read(account_info: &AccountInfo) -> Result<(u128, i64), ProgramError> {
+        let data = account_info.try_borrow_data()?;
+        if data.len().lt(&Self::LEN) {
+            return Err(AmmError::InvalidOracle.into());
+        }
+
+        let feed = unsafe { &*(data.as_ptr() as *const PriceFeed) };
+        let price = u128::from_le_bytes(feed.price);
+        if feed.magic.ne(&Self::MAGIC) || price == 0 {
+            return Err(AmmError::InvalidOracle.into());
+        }
+
+        Ok((price, i64::from_le_bytes(feed.updated_at)))
+    }
+}
@@ -0,0 +1,163 @@
+use crate::math::{verify_proof, HASH_LEN};
+use core::mem::size_of;
+use pinocchio::{
+    account_info::{AccountInfo, Ref, RefMut},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+/// Most keys an allowlist in `Keys` mode can hold.
+pub const MAX_ALLOWLIST_KEYS: usize = 64;
+
+/// Wallets allowed to deposit, swap and withdraw on a permissioned pool.
+///
+/// In `Keys` mode only the first `count` entries of `keys` are in use, in `MerkleRoot`
+/// mode users prove their membership against `root` in the instruction data.
+#[repr(C)]
+pub struct Allowlist {
+    config: Pubkey,
+    mode: u8,
+    bump: [u8; 1],
+    root: [u8; HASH_LEN],
+    count: u8,
+    keys: [Pubkey; MAX_ALLOWLIST_KEYS],
+}
+
+#[repr(u8)]
+pub enum AllowlistMode {
+    Keys = 0u8,
+    MerkleRoot = 1u8,
+}
+
+//Reading helpers
+impl Allowlist {
+    pub const LEN: usize = size_of::<Pubkey>()
+        + size_of::<u8>()
+        + size_of::<u8>()
+        + HASH_LEN
+        + size_of::<u8>()
+        + size_of::<Pubkey>() * MAX_ALLOWLIST_KEYS;
+
+    #[inline(always)]
+    pub fn load(account_info: &AccountInfo) -> Result<Ref<'_, Self>, ProgramError> {
+        if account_info.data_len() != Self::LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        if account_info.owner().ne(&crate::ID) {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+        Ok(Ref::map(account_info.try_borrow_data()?, |data| unsafe {
+            &*(data.as_ptr() as *const Self)
+        }))
+    }
+
+    #[inline(always)]
+    pub fn config(&self) -> &Pubkey {
+        &self.config
+    }
+
+    #[inline(always)]
+    pub fn mode(&self) -> u8 {
+        self.mode
+    }
+
+    #[inline(always)]
+    pub fn bump(&self) -> [u8; 1] {
+        self.bump
+    }
+
+    #[inline(always)]
+    pub fn root(&self) -> &[u8; HASH_LEN] {
+        &self.root
+    }
+
+    #[inline(always)]
+    pub fn keys(&self) -> &[Pubkey] {
+        &self.keys[..self.count as usize]
+    }
+
+    /// Whether `user` is allowed, `proof` only being used in `MerkleRoot` mode.
+    #[inline(always)]
+    pub fn is_allowed(&self, user: &Pubkey, proof: &[u8]) -> bool {
+        match self.mode() == AllowlistMode::MerkleRoot as u8 {
+            true => verify_proof(self.root(), user, proof),
+            false => self.keys().contains(user),
+        }
+    }
+}
+
+//Writing helpers
+impl Allowlist {
+    #[inline(always)]
+    pub fn load_mut(account_info: &AccountInfo) -> Result<RefMut<'_, Self>, ProgramError> {
+        if account_info.data_len() != Self::LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        if account_info.owner().ne(&crate::ID) {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+        Ok(RefMut::map(
+            account_info.try_borrow_mut_data()?,
+            |data| unsafe { &mut *(data.as_mut_ptr() as *mut Self) },
+        ))
+    }
+
+    #[inline(always)]
+    pub fn set_inner(
+        &mut self,
+        config: Pubkey,
+        mode: u8,
+        bump: [u8; 1],
+    ) -> Result<(), ProgramError> {
+        if mode.gt(&(AllowlistMode::MerkleRoot as u8)) {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+        self.config = config;
+        self.mode = mode;
+        self.bump = bump;
+        self.root = [0; HASH_LEN];
+        self.count = 0;
+        Ok(())
+    }
+
+    #[inline(always)]
+    pub fn add_key(&mut self, key: Pubkey) -> Result<(), ProgramError> {
+        if self.mode() != AllowlistMode::Keys as u8 {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        if self.keys().contains(&key) {
+            return Err(ProgramError::InvalidArgument);
+        }
+        if self.count as usize == MAX_ALLOWLIST_KEYS {
+            return Err(ProgramError::AccountDataTooSmall);
+        }
+        self.keys[self.count as usize] = key;
+        self.count += 1;
+        Ok(())
+    }
+
+    #[inline(always)]
+    pub fn remove_key(&mut self, key: &Pubkey) -> Result<(), ProgramError> {
+        let index = self
+            .keys()
+            .iter()
+            .position(|k| k.eq(key))
+            .ok_or(ProgramError::InvalidArgument)?;
+
+        // Move the last key into the freed slot
+        let last = self.count as usize - 1;
+        self.keys[index] = self.keys[last];
+        self.keys[last] = [0; 32];
+        self.count -= 1;
+        Ok(())
+    }
+
+    #[inline(always)]
+    pub fn set_root(&mut self, root: [u8; HASH_LEN]) -> Result<(), ProgramError> {
+        if self.mode() != AllowlistMode::MerkleRoot as u8 {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        self.root = root;
+        Ok(())
+    }
+}
@@ -0,0 +1,470 @@
+use core::mem::size_of;
+use pinocchio::{
+    account_info::{AccountInfo, Ref, RefMut},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+/// Most orders one batch can queue.
+pub const MAX_BATCH_ORDERS: usize = 32;
+
+/// Most orders one owner can queue in a batch.
+pub const MAX_OWNER_BATCH_ORDERS: usize = 2;
+
+/// Lamports each order locks in the batch until it is claimed, so that filling the batch
+/// from many wallets ties up 0.32 SOL per batch.
+pub const BATCH_ORDER_DEPOSIT: u64 = 10_000_000;
+
+/// Slots after `end_slot` past which the orders of a batch nobody settled can be
+/// cancelled, about ten minutes.
+pub const BATCH_GRACE_SLOTS: u64 = 1_500;
+
+/// Order of `owner` selling `amount` of x for y, or of y for x, in a batch, for at least
+/// `min_out` of the other token.
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct BatchOrder {
+    owner: Pubkey,
+    is_x: u8,
+    amount: [u8; 8],
+    min_out: [u8; 8],
+    claimed: u8,
+    refunded: u8,
+}
+
+impl BatchOrder {
+    #[inline(always)]
+    pub fn owner(&self) -> &Pubkey {
+        &self.owner
+    }
+
+    #[inline(always)]
+    pub fn is_x(&self) -> bool {
+        self.is_x == 1
+    }
+
+    #[inline(always)]
+    pub fn amount(&self) -> u64 {
+        u64::from_le_bytes(self.amount)
+    }
+
+    #[inline(always)]
+    pub fn min_out(&self) -> u64 {
+        u64::from_le_bytes(self.min_out)
+    }
+
+    #[inline(always)]
+    pub fn claimed(&self) -> bool {
+        self.claimed == 1
+    }
+
+    /// Whether the order was left out of the batch, and is paid back what it sold.
+    #[inline(always)]
+    pub fn refunded(&self) -> bool {
+        self.refunded == 1
+    }
+}
+
+/// Orders of one epoch of a pool in batch mode, queued until `end_slot` and then cleared
+/// together at one price by `SettleBatch`.
+///
+/// The batch holds the orders in its own token accounts. Once settled, the sellers of x
+/// share `out_y` and the sellers of y share `out_x` in proportion to what they sold.
+/// Orders refunded at settlement, or cancelled, no longer count in `in_x` and `in_y`.
+/// The account is closed to `payer`, who opened it, once every order is claimed.
+#[repr(C)]
+pub struct Batch {
+    state: u8,
+    config: Pubkey,
+    payer: Pubkey,
+    epoch: [u8; 8],
+    end_slot: [u8; 8],
+    in_x: [u8; 8],
+    in_y: [u8; 8],
+    out_x: [u8; 8],
+    out_y: [u8; 8],
+    bump: [u8; 1],
+    count: u8,
+    orders: [BatchOrder; MAX_BATCH_ORDERS],
+}
+
+#[repr(u8)]
+pub enum BatchState {
+    Uninitialized = 0u8,
+    Open = 1u8,
+    Settled = 2u8,
+}
+
+//Reading helpers
+impl Batch {
+    pub const LEN: usize = size_of::<u8>()
+        + size_of::<Pubkey>() * 2
+        + size_of::<u64>()
+        + size_of::<u64>()
+        + size_of::<u64>() * 4
+        + size_of::<u8>()
+        + size_of::<u8>()
+        + size_of::<BatchOrder>() * MAX_BATCH_ORDERS;
+
+    #[inline(always)]
+    pub fn load(account_info: &AccountInfo) -> Result<Ref<'_, Self>, ProgramError> {
+        if account_info.data_len() != Self::LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        if account_info.owner().ne(&crate::ID) {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+        Ok(Ref::map(account_info.try_borrow_data()?, |data| unsafe {
+            &*(data.as_ptr() as *const Self)
+        }))
+    }
+
+    #[inline(always)]
+    pub fn state(&self) -> u8 {
+        self.state
+    }
+
+    #[inline(always)]
+    pub fn config(&self) -> &Pubkey {
+        &self.config
+    }
+
+    /// Account that paid the rent of the batch.
+    #[inline(always)]
+    pub fn payer(&self) -> &Pubkey {
+        &self.payer
+    }
+
+    #[inline(always)]
+    pub fn epoch(&self) -> u64 {
+        u64::from_le_bytes(self.epoch)
+    }
+
+    /// First slot at which the batch stops taking orders and can be settled.
+    #[inline(always)]
+    pub fn end_slot(&self) -> u64 {
+        u64::from_le_bytes(self.end_slot)
+    }
+
+    /// Total x sold by the orders not refunded.
+    #[inline(always)]
+    pub fn in_x(&self) -> u64 {
+        u64::from_le_bytes(self.in_x)
+    }
+
+    /// Total y sold by the orders not refunded.
+    #[inline(always)]
+    pub fn in_y(&self) -> u64 {
+        u64::from_le_bytes(self.in_y)
+    }
+
+    /// x shared by the sellers of y once settled.
+    #[inline(always)]
+    pub fn out_x(&self) -> u64 {
+        u64::from_le_bytes(self.out_x)
+    }
+
+    /// y shared by the sellers of x once settled.
+    #[inline(always)]
+    pub fn out_y(&self) -> u64 {
+        u64::from_le_bytes(self.out_y)
+    }
+
+    #[inline(always)]
+    pub fn bump(&self) -> [u8; 1] {
+        self.bump
+    }
+
+    #[inline(always)]
+    pub fn orders(&self) -> &[BatchOrder] {
+        &self.orders[..self.count as usize]
+    }
+
+    /// Whether every order was claimed or cancelled.
+    #[inline(always)]
+    pub fn is_claimed(&self) -> bool {
+        self.orders().iter().all(|order| order.claimed())
+    }
+
+    /// Whether the orders of the open batch can be cancelled at `slot`, once the pool no
+    /// longer trades or `BATCH_GRACE_SLOTS` passed without a settlement.
+    #[inline(always)]
+    pub fn is_stranded(&self, pool_initialized: bool, slot: u64) -> bool {
+        self.state() == BatchState::Open as u8
+            && (!pool_initialized || slot >= self.end_slot().saturating_add(BATCH_GRACE_SLOTS))
+    }
+}
+
+//Writing helpers
+impl Batch {
+    #[inline(always)]
+    pub fn load_mut(account_info: &AccountInfo) -> Result<RefMut<'_, Self>, ProgramError> {
+        if account_info.data_len() != Self::LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        if account_info.owner().ne(&crate::ID) {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+        Ok(RefMut::map(
+            account_info.try_borrow_mut_data()?,
+            |data| unsafe { &mut *(data.as_mut_ptr() as *mut Self) },
+        ))
+    }
+
+    #[inline(always)]
+    pub fn set_inner(
+        &mut self,
+        config: Pubkey,
+        payer: Pubkey,
+        epoch: u64,
+        end_slot: u64,
+        bump: [u8; 1],
+    ) {
+        self.state = BatchState::Open as u8;
+        self.config = config;
+        self.payer = payer;
+        self.epoch = epoch.to_le_bytes();
+        self.end_slot = end_slot.to_le_bytes();
+        self.in_x = 0u64.to_le_bytes();
+        self.in_y = 0u64.to_le_bytes();
+        self.out_x = 0u64.to_le_bytes();
+        self.out_y = 0u64.to_le_bytes();
+        self.bump = bump;
+        self.count = 0;
+    }
+
+    /// Queue an order selling `amount` of x, or of y, for at least `min_out`.
+    #[inline(always)]
+    pub fn push(
+        &mut self,
+        owner: Pubkey,
+        is_x: bool,
+        amount: u64,
+        min_out: u64,
+    ) -> Result<(), ProgramError> {
+        if self.state() != BatchState::Open as u8 {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        if amount == 0 {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+        if self.count as usize == MAX_BATCH_ORDERS {
+            return Err(ProgramError::AccountDataTooSmall);
+        }
+        if self
+            .orders()
+            .iter()
+            .filter(|order| order.owner().eq(&owner))
+            .count()
+            == MAX_OWNER_BATCH_ORDERS
+        {
+            return Err(ProgramError::InvalidArgument);
+        }
+
+        let total = match is_x {
+            true => &mut self.in_x,
+            false => &mut self.in_y,
+        };
+        *total = u64::from_le_bytes(*total)
+            .checked_add(amount)
+            .ok_or(ProgramError::ArithmeticOverflow)?
+            .to_le_bytes();
+
+        self.orders[self.count as usize] = BatchOrder {
+            owner,
+            is_x: is_x as u8,
+            amount: amount.to_le_bytes(),
+            min_out: min_out.to_le_bytes(),
+            claimed: 0,
+            refunded: 0,
+        };
+        self.count += 1;
+        Ok(())
+    }
+
+    /// Take the order at `index` out of the totals, to be paid back what it sold.
+    #[inline(always)]
+    fn refund(&mut self, index: usize) {
+        let order = &mut self.orders[index];
+        order.refunded = 1;
+
+        let (is_x, amount) = (order.is_x(), order.amount());
+        let total = match is_x {
+            true => &mut self.in_x,
+            false => &mut self.in_y,
+        };
+        *total = (u64::from_le_bytes(*total) - amount).to_le_bytes();
+    }
+
+    /// Refund the orders whose share of `out_x` or `out_y` falls short of their
+    /// `min_out`. Returns whether any was refunded, as the batch then clears at
+    /// another price.
+    #[inline(always)]
+    pub fn refund_unmet(&mut self, out_x: u64, out_y: u64) -> bool {
+        let (in_x, in_y) = (self.in_x(), self.in_y());
+        let mut refunded = false;
+
+        for index in 0..self.count as usize {
+            let order = self.orders[index];
+            if order.refunded() {
+                continue;
+            }
+
+            let (out, total_in) = match order.is_x() {
+                true => (out_y, in_x),
+                false => (out_x, in_y),
+            };
+
+            if (order.amount() as u128 * out as u128 / total_in as u128) < order.min_out() as u128 {
+                self.refund(index);
+                refunded = true;
+            }
+        }
+
+        refunded
+    }
+
+    /// Cancel the open order at `index` of `owner`, for a stranded batch. Returns whether
+    /// it is paid back in x, and what it sold.
+    ///
+    /// The batch counts as settled, with nothing to share, once every order is cancelled.
+    #[inline(always)]
+    pub fn cancel(&mut self, index: usize, owner: &Pubkey) -> Result<(bool, u64), ProgramError> {
+        if self.state() != BatchState::Open as u8 {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        let order = *self
+            .orders()
+            .get(index)
+            .ok_or(ProgramError::InvalidInstructionData)?;
+
+        if order.owner().ne(owner) || order.claimed() {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        self.refund(index);
+        self.orders[index].claimed = 1;
+
+        if self.is_claimed() {
+            self.settle(0, 0);
+        }
+
+        Ok((order.is_x(), order.amount()))
+    }
+
+    #[inline(always)]
+    pub fn settle(&mut self, out_x: u64, out_y: u64) {
+        self.state = BatchState::Settled as u8;
+        self.out_x = out_x.to_le_bytes();
+        self.out_y = out_y.to_le_bytes();
+    }
+
+    /// Mark the order at `index` of `owner` claimed. Returns whether it is paid in x, and
+    /// its share of the output rounded down, or what it sold if it was refunded.
+    #[inline(always)]
+    pub fn claim(&mut self, index: usize, owner: &Pubkey) -> Result<(bool, u64), ProgramError> {
+        if self.state() != BatchState::Settled as u8 {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        let order = *self
+            .orders()
+            .get(index)
+            .ok_or(ProgramError::InvalidInstructionData)?;
+
+        if order.owner().ne(owner) || order.claimed() {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        if order.refunded() {
+            self.orders[index].claimed = 1;
+            return Ok((order.is_x(), order.amount()));
+        }
+
+        let (is_x, out, total_in) = match order.is_x() {
+            true => (false, self.out_y(), self.in_x()),
+            false => (true, self.out_x(), self.in_y()),
+        };
+
+        let amount = u64::try_from(order.amount() as u128 * out as u128 / total_in as u128)
+            .map_err(|_| ProgramError::ArithmeticOverflow)?;
+
+        self.orders[index].claimed = 1;
+        Ok((is_x, amount))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const END_SLOT: u64 = 100;
+
+    /// An open batch ending at `END_SLOT` in the zeroed `bytes`, with an order selling x
+    /// from `[1; 32]` and one selling y from `[2; 32]`.
+    fn batch(bytes: &mut [u8; Batch::LEN]) -> &mut Batch {
+        let batch = unsafe { &mut *(bytes.as_mut_ptr() as *mut Batch) };
+        batch.set_inner([0; 32], [1; 32], 0, END_SLOT, [0]);
+        batch.push([1; 32], true, 1_000, 0).unwrap();
+        batch.push([2; 32], false, 2_000, 0).unwrap();
+        batch
+    }
+
+    #[test]
+    fn an_unsettled_batch_is_stranded_after_the_grace_period() {
+        let mut bytes = [0; Batch::LEN];
+        let batch = batch(&mut bytes);
+
+        assert!(!batch.is_stranded(true, END_SLOT));
+        assert!(!batch.is_stranded(true, END_SLOT + BATCH_GRACE_SLOTS - 1));
+        assert!(batch.is_stranded(true, END_SLOT + BATCH_GRACE_SLOTS));
+        assert!(batch.is_stranded(false, END_SLOT - 1));
+
+        batch.settle(0, 0);
+        assert!(!batch.is_stranded(false, END_SLOT + BATCH_GRACE_SLOTS));
+    }
+
+    #[test]
+    fn cancelling_every_order_settles_the_batch() {
+        let mut bytes = [0; Batch::LEN];
+        let batch = batch(&mut bytes);
+
+        assert_eq!(batch.cancel(0, &[1; 32]), Ok((true, 1_000)));
+        assert_eq!(batch.state(), BatchState::Open as u8);
+        assert!(!batch.is_claimed());
+        assert_eq!(
+            batch.cancel(0, &[1; 32]),
+            Err(ProgramError::InvalidAccountData)
+        );
+
+        assert_eq!(batch.cancel(1, &[2; 32]), Ok((false, 2_000)));
+        assert_eq!(batch.state(), BatchState::Settled as u8);
+        assert!(batch.is_claimed());
+        assert_eq!((batch.in_x(), batch.in_y()), (0, 0));
+    }
+
+    #[test]
+    fn claiming_every_order_claims_the_batch() {
+        let mut bytes = [0; Batch::LEN];
+        let batch = batch(&mut bytes);
+        batch.settle(1_900, 950);
+
+        assert_eq!(batch.claim(0, &[1; 32]), Ok((false, 950)));
+        assert!(!batch.is_claimed());
+        assert_eq!(batch.claim(1, &[2; 32]), Ok((true, 1_900)));
+        assert!(batch.is_claimed());
+    }
+
+    #[test]
+    fn an_owner_queues_at_most_two_orders() {
+        let mut bytes = [0; Batch::LEN];
+        let batch = batch(&mut bytes);
+
+        batch.push([1; 32], true, 1, 0).unwrap();
+        assert_eq!(
+            batch.push([1; 32], false, 1, 0),
+            Err(ProgramError::InvalidArgument)
+        );
+    }
+}
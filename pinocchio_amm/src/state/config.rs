@@ -0,0 +1,1142 @@
+use crate::math::interpolate_weight;
+use pinocchio::{
+    account_info::{AccountInfo, Ref, RefMut},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+/// Lowest weight, in basis points, a weighted pool accepts for either token.
+pub const MIN_WEIGHT: u16 = 100;
+
+/// Scale of `Config::fee_growth_x` and `Config::fee_growth_y`.
+pub const FEE_GROWTH_PRECISION: u128 = 1_000_000_000_000;
+
+/// Length of the buckets `Config::day_high_price` and `Config::day_low_price` cover.
+pub const SECONDS_PER_DAY: i64 = 86_400;
+
+#[repr(C)]
+pub struct Config {
+    state: u8,
+    seed: [u8; 8],
+    authority: Pubkey,
+    mint_x: Pubkey,
+    mint_y: Pubkey,
+    fee: [u8; 2],
+    bump: [u8; 1],
+    oracle: Pubkey,
+    oracle_max_deviation_bps: [u8; 2],
+    oracle_max_age: [u8; 8],
+    max_slot_move_bps: [u8; 2],
+    last_slot: [u8; 8],
+    slot_start_price: [u8; 16],
+    slot_move_bps: [u8; 8],
+    max_fee: [u8; 2],
+    volatility_fee_factor: [u8; 2],
+    volatility_half_life: [u8; 8],
+    volatility: [u8; 8],
+    volatility_updated_at: [u8; 8],
+    curve: u8,
+    start_weight_x: [u8; 2],
+    end_weight_x: [u8; 2],
+    weights_start_time: [u8; 8],
+    weights_end_time: [u8; 8],
+    max_referral_bps: [u8; 2],
+    allowlist: Pubkey,
+    guardian: Pubkey,
+    lp_supply: [u8; 8],
+    positioned_lp: [u8; 8],
+    fee_growth_x: [u8; 16],
+    fee_growth_y: [u8; 16],
+    fees_x: [u8; 8],
+    fees_y: [u8; 8],
+    orders_x: [u8; 8],
+    orders_y: [u8; 8],
+    volume_x: [u8; 16],
+    volume_y: [u8; 16],
+    cumulative_fees_x: [u8; 16],
+    cumulative_fees_y: [u8; 16],
+    trade_count: [u8; 8],
+    last_trade_slot: [u8; 8],
+    day_start: [u8; 8],
+    day_high_price: [u8; 16],
+    day_low_price: [u8; 16],
+    batch_window: [u8; 8],
+    open_batches: [u8; 8],
+    swap_hook: Pubkey,
+}
+
+#[repr(u8)]
+pub enum AmmState {
+    Uninitialized = 0u8,
+    Initialized = 1u8,
+    Disabled = 2u8,
+    WithdrawOnly = 3u8,
+}
+
+#[repr(u8)]
+pub enum CurveType {
+    ConstantProduct = 0u8,
+    Weighted = 1u8,
+}
+
+//Reading helpers
+impl Config {
+    pub const LEN: usize = size_of::<u8>()
+        + size_of::<u64>()
+        + size_of::<Pubkey>() * 3
+        + size_of::<u16>()
+        + size_of::<u8>()
+        + size_of::<Pubkey>()
+        + size_of::<u16>()
+        + size_of::<i64>()
+        + size_of::<u16>()
+        + size_of::<u64>()
+        + size_of::<u128>()
+        + size_of::<u64>()
+        + size_of::<u16>()
+        + size_of::<u16>()
+        + size_of::<i64>()
+        + size_of::<u64>()
+        + size_of::<i64>()
+        + size_of::<u8>()
+        + size_of::<u16>()
+        + size_of::<u16>()
+        + size_of::<i64>()
+        + size_of::<i64>()
+        + size_of::<u16>()
+        + size_of::<Pubkey>()
+        + size_of::<Pubkey>()
+        + size_of::<u64>()
+        + size_of::<u64>()
+        + size_of::<u128>()
+        + size_of::<u128>()
+        + size_of::<u64>()
+        + size_of::<u64>()
+        + size_of::<u64>()
+        + size_of::<u64>()
+        + size_of::<u128>() * 4
+        + size_of::<u64>()
+        + size_of::<u64>()
+        + size_of::<i64>()
+        + size_of::<u128>()
+        + size_of::<u128>()
+        + size_of::<u64>()
+        + size_of::<u64>()
+        + size_of::<Pubkey>();
+
+    #[inline(always)]
+    pub fn load(account_info: &AccountInfo) -> Result<Ref<'_, Self>, ProgramError> {
+        if account_info.data_len() != Self::LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        if account_info.owner().ne(&crate::ID) {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+        Ok(Ref::map(account_info.try_borrow_data()?, |data| unsafe {
+            Self::from_bytes_unchecked(data)
+        }))
+    }
+
+    /// Return a `Config` from the given account info, without borrow tracking.
+    ///
+    /// # Safety
+    ///
+    /// The caller must ensure that no mutable borrow of the account data is alive while
+    /// the returned reference is used.
+    #[inline(always)]
+    pub unsafe fn load_unchecked(account_info: &AccountInfo) -> Result<&Self, ProgramError> {
+        if account_info.data_len() != Self::LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        if account_info.owner() != &crate::ID {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+        Ok(Self::from_bytes_unchecked(
+            account_info.borrow_data_unchecked(),
+        ))
+    }
+
+    /// Return a `Config` from the given bytes.
+    ///
+    /// # Safety
+    ///
+    /// The caller must ensure that `bytes` contains a valid representation of `Config`, and
+    /// it is properly aligned to be interpreted as an instance of `Config`.
+    /// At the moment `Config` has an alignment of 1 byte.
+    /// This method does not perform a length validation.
+    #[inline(always)]
+    pub unsafe fn from_bytes_unchecked(bytes: &[u8]) -> &Self {
+        &*(bytes.as_ptr() as *const Config)
+    }
+
+    /// Return a mutable `Config` reference from the given bytes.
+    ///
+    /// # Safety
+    ///
+    /// The caller must ensure that `bytes` contains a valid representation of `Config`.
+    #[inline(always)]
+    pub unsafe fn from_bytes_unchecked_mut(bytes: &mut [u8]) -> &mut Self {
+        &mut *(bytes.as_mut_ptr() as *mut Config)
+    }
+
+    /// Return a mutable `Config` from the given account info, without borrow tracking.
+    ///
+    /// # Safety
+    ///
+    /// The caller must ensure that no other borrow of the account data is alive while
+    /// the returned reference is used.
+    #[allow(clippy::mut_from_ref)]
+    #[inline(always)]
+    pub unsafe fn load_mut_unchecked(
+        account_info: &AccountInfo,
+    ) -> Result<&mut Self, ProgramError> {
+        if account_info.data_len() != Self::LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        if account_info.owner() != &crate::ID {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        Ok(Self::from_bytes_unchecked_mut(
+            account_info.borrow_mut_data_unchecked(),
+        ))
+    }
+
+    // Getter methods for safe field access
+    #[inline(always)]
+    pub fn state(&self) -> u8 {
+        self.state
+    }
+
+    #[inline(always)]
+    pub fn seed(&self) -> u64 {
+        u64::from_le_bytes(self.seed)
+    }
+
+    #[inline(always)]
+    pub fn authority(&self) -> &Pubkey {
+        &self.authority
+    }
+
+    #[inline(always)]
+    pub fn mint_x(&self) -> &Pubkey {
+        &self.mint_x
+    }
+
+    #[inline(always)]
+    pub fn mint_y(&self) -> &Pubkey {
+        &self.mint_y
+    }
+
+    #[inline(always)]
+    pub fn fee(&self) -> u16 {
+        u16::from_le_bytes(self.fee)
+    }
+
+    #[inline(always)]
+    pub fn config_bump(&self) -> [u8; 1] {
+        self.bump
+    }
+
+    #[inline(always)]
+    pub fn oracle(&self) -> &Pubkey {
+        &self.oracle
+    }
+
+    #[inline(always)]
+    pub fn oracle_max_deviation_bps(&self) -> u16 {
+        u16::from_le_bytes(self.oracle_max_deviation_bps)
+    }
+
+    #[inline(always)]
+    pub fn oracle_max_age(&self) -> i64 {
+        i64::from_le_bytes(self.oracle_max_age)
+    }
+
+    #[inline(always)]
+    pub fn max_slot_move_bps(&self) -> u16 {
+        u16::from_le_bytes(self.max_slot_move_bps)
+    }
+
+    #[inline(always)]
+    pub fn last_slot(&self) -> u64 {
+        u64::from_le_bytes(self.last_slot)
+    }
+
+    #[inline(always)]
+    pub fn slot_start_price(&self) -> u128 {
+        u128::from_le_bytes(self.slot_start_price)
+    }
+
+    #[inline(always)]
+    pub fn slot_move_bps(&self) -> u64 {
+        u64::from_le_bytes(self.slot_move_bps)
+    }
+
+    #[inline(always)]
+    pub fn max_fee(&self) -> u16 {
+        u16::from_le_bytes(self.max_fee)
+    }
+
+    #[inline(always)]
+    pub fn volatility_fee_factor(&self) -> u16 {
+        u16::from_le_bytes(self.volatility_fee_factor)
+    }
+
+    #[inline(always)]
+    pub fn volatility_half_life(&self) -> i64 {
+        i64::from_le_bytes(self.volatility_half_life)
+    }
+
+    #[inline(always)]
+    pub fn volatility(&self) -> u64 {
+        u64::from_le_bytes(self.volatility)
+    }
+
+    #[inline(always)]
+    pub fn volatility_updated_at(&self) -> i64 {
+        i64::from_le_bytes(self.volatility_updated_at)
+    }
+
+    #[inline(always)]
+    pub fn curve(&self) -> u8 {
+        self.curve
+    }
+
+    #[inline(always)]
+    pub fn start_weight_x(&self) -> u16 {
+        u16::from_le_bytes(self.start_weight_x)
+    }
+
+    #[inline(always)]
+    pub fn end_weight_x(&self) -> u16 {
+        u16::from_le_bytes(self.end_weight_x)
+    }
+
+    #[inline(always)]
+    pub fn weights_start_time(&self) -> i64 {
+        i64::from_le_bytes(self.weights_start_time)
+    }
+
+    #[inline(always)]
+    pub fn weights_end_time(&self) -> i64 {
+        i64::from_le_bytes(self.weights_end_time)
+    }
+
+    #[inline(always)]
+    pub fn max_referral_bps(&self) -> u16 {
+        u16::from_le_bytes(self.max_referral_bps)
+    }
+
+    #[inline(always)]
+    pub fn allowlist(&self) -> &Pubkey {
+        &self.allowlist
+    }
+
+    #[inline(always)]
+    pub fn guardian(&self) -> &Pubkey {
+        &self.guardian
+    }
+
+    /// LP supply tracked by the program, resynced from the LP mint by `Deposit`,
+    /// `Withdraw` and `MigrateLiquidity`, the only instructions minting or burning LP.
+    ///
+    /// The config is the mint authority, so the tracked supply is never below the real
+    /// one. It is above it once holders burn LP through the token program, until the next
+    /// resync, which only makes the fee growth of `accrue_fee` smaller. `Swap` leaves it
+    /// as it is, since it does not take the LP mint.
+    #[inline(always)]
+    pub fn lp_supply(&self) -> u64 {
+        u64::from_le_bytes(self.lp_supply)
+    }
+
+    /// LP held in fee positions, whose share of the fees is kept out of the reserves.
+    #[inline(always)]
+    pub fn positioned_lp(&self) -> u64 {
+        u64::from_le_bytes(self.positioned_lp)
+    }
+
+    /// Swap fees in x earned per LP since the pool opened, scaled by
+    /// `FEE_GROWTH_PRECISION`.
+    #[inline(always)]
+    pub fn fee_growth_x(&self) -> u128 {
+        u128::from_le_bytes(self.fee_growth_x)
+    }
+
+    /// Swap fees in y earned per LP since the pool opened, scaled by
+    /// `FEE_GROWTH_PRECISION`.
+    #[inline(always)]
+    pub fn fee_growth_y(&self) -> u128 {
+        u128::from_le_bytes(self.fee_growth_y)
+    }
+
+    /// Unclaimed position fees in x, held by the vault but not part of the reserves.
+    #[inline(always)]
+    pub fn fees_x(&self) -> u64 {
+        u64::from_le_bytes(self.fees_x)
+    }
+
+    /// Unclaimed position fees in y, held by the vault but not part of the reserves.
+    #[inline(always)]
+    pub fn fees_y(&self) -> u64 {
+        u64::from_le_bytes(self.fees_y)
+    }
+
+    /// Range order funds in x, unfilled or filled, held by the vault but not part of the
+    /// reserves.
+    #[inline(always)]
+    pub fn orders_x(&self) -> u64 {
+        u64::from_le_bytes(self.orders_x)
+    }
+
+    /// Range order funds in y, unfilled or filled, held by the vault but not part of the
+    /// reserves.
+    #[inline(always)]
+    pub fn orders_y(&self) -> u64 {
+        u64::from_le_bytes(self.orders_y)
+    }
+
+    /// Amount of x swapped in or out since the pool opened.
+    #[inline(always)]
+    pub fn volume_x(&self) -> u128 {
+        u128::from_le_bytes(self.volume_x)
+    }
+
+    /// Amount of y swapped in or out since the pool opened.
+    #[inline(always)]
+    pub fn volume_y(&self) -> u128 {
+        u128::from_le_bytes(self.volume_y)
+    }
+
+    /// Swap fees charged in x since the pool opened, referral shares included.
+    #[inline(always)]
+    pub fn cumulative_fees_x(&self) -> u128 {
+        u128::from_le_bytes(self.cumulative_fees_x)
+    }
+
+    /// Swap fees charged in y since the pool opened, referral shares included.
+    #[inline(always)]
+    pub fn cumulative_fees_y(&self) -> u128 {
+        u128::from_le_bytes(self.cumulative_fees_y)
+    }
+
+    /// Swaps, deposits and withdrawals the pool has processed.
+    #[inline(always)]
+    pub fn trade_count(&self) -> u64 {
+        u64::from_le_bytes(self.trade_count)
+    }
+
+    #[inline(always)]
+    pub fn last_trade_slot(&self) -> u64 {
+        u64::from_le_bytes(self.last_trade_slot)
+    }
+
+    /// Unix timestamp of the start of the day `day_high_price` and `day_low_price` cover.
+    #[inline(always)]
+    pub fn day_start(&self) -> i64 {
+        i64::from_le_bytes(self.day_start)
+    }
+
+    /// Highest price of x in y after a trade of the day, scaled by `PRICE_SCALE`.
+    #[inline(always)]
+    pub fn day_high_price(&self) -> u128 {
+        u128::from_le_bytes(self.day_high_price)
+    }
+
+    /// Lowest price of x in y after a trade of the day, scaled by `PRICE_SCALE`.
+    #[inline(always)]
+    pub fn day_low_price(&self) -> u128 {
+        u128::from_le_bytes(self.day_low_price)
+    }
+
+    /// Slots each batch of a pool in batch mode takes orders for, `0` when batch mode is
+    /// off.
+    #[inline(always)]
+    pub fn batch_window(&self) -> u64 {
+        u64::from_le_bytes(self.batch_window)
+    }
+
+    /// Batches opened and not settled yet.
+    #[inline(always)]
+    pub fn open_batches(&self) -> u64 {
+        u64::from_le_bytes(self.open_batches)
+    }
+
+    /// Program `Swap` calls once the trade settled, the default pubkey when there is none.
+    #[inline(always)]
+    pub fn swap_hook(&self) -> &Pubkey {
+        &self.swap_hook
+    }
+
+    /// Reserves backing the LP given the vault balances, without the unclaimed position
+    /// fees and the range order funds.
+    #[inline(always)]
+    pub fn reserves(&self, vault_x: u64, vault_y: u64) -> (u64, u64) {
+        (
+            vault_x
+                .saturating_sub(self.fees_x())
+                .saturating_sub(self.orders_x()),
+            vault_y
+                .saturating_sub(self.fees_y())
+                .saturating_sub(self.orders_y()),
+        )
+    }
+
+    /// Weights of x and y in basis points at `now`, even for constant product pools.
+    #[inline(always)]
+    pub fn weights(&self, now: i64) -> (u16, u16) {
+        if self.curve().ne(&(CurveType::Weighted as u8)) {
+            return (5_000, 5_000);
+        }
+
+        let weight_x = interpolate_weight(
+            self.start_weight_x(),
+            self.end_weight_x(),
+            self.weights_start_time(),
+            self.weights_end_time(),
+            now,
+        );
+        (weight_x, 10_000 - weight_x)
+    }
+}
+
+//Writing helpers
+
+impl Config {
+    pub fn set_seed(&mut self, seed: u64) {
+        self.seed = seed.to_le_bytes();
+    }
+
+    pub fn set_authority(&mut self, authority: Pubkey) {
+        self.authority = authority;
+    }
+
+    pub fn set_mint_x(&mut self, mint_x: Pubkey) {
+        self.mint_x = mint_x;
+    }
+
+    pub fn set_mint_y(&mut self, mint_y: Pubkey) {
+        self.mint_y = mint_y;
+    }
+
+    pub fn set_config_bump(&mut self, config_bump: [u8; 1]) {
+        self.bump = config_bump;
+    }
+
+    #[inline(always)]
+    pub fn load_mut(account_info: &AccountInfo) -> Result<RefMut<'_, Self>, ProgramError> {
+        if account_info.data_len() != Self::LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        if account_info.owner().ne(&crate::ID) {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+        Ok(RefMut::map(
+            account_info.try_borrow_mut_data()?,
+            |data| unsafe { Self::from_bytes_unchecked_mut(data) },
+        ))
+    }
+
+    #[inline(always)]
+    pub fn set_state(&mut self, state: u8) -> Result<(), ProgramError> {
+        if state.gt(&(AmmState::WithdrawOnly as u8)) {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        self.state = state;
+        Ok(())
+    }
+
+    #[inline(always)]
+    pub fn set_fee(&mut self, fee: u16) -> Result<(), ProgramError> {
+        if fee.ge(&10_000) {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        self.fee = fee.to_le_bytes();
+        Ok(())
+    }
+
+    #[inline(always)]
+    pub fn set_oracle(
+        &mut self,
+        oracle: Pubkey,
+        max_deviation_bps: u16,
+        max_age: i64,
+    ) -> Result<(), ProgramError> {
+        if max_deviation_bps.gt(&10_000) || max_age.lt(&0) {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        self.oracle = oracle;
+        self.oracle_max_deviation_bps = max_deviation_bps.to_le_bytes();
+        self.oracle_max_age = max_age.to_le_bytes();
+        Ok(())
+    }
+
+    /// Cap the share of the swap fee, in basis points, paid out to referrers.
+    #[inline(always)]
+    pub fn set_max_referral_bps(&mut self, max_referral_bps: u16) -> Result<(), ProgramError> {
+        if max_referral_bps.gt(&10_000) {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        self.max_referral_bps = max_referral_bps.to_le_bytes();
+        Ok(())
+    }
+
+    #[inline(always)]
+    pub fn set_allowlist(&mut self, allowlist: Pubkey) {
+        self.allowlist = allowlist;
+    }
+
+    #[inline(always)]
+    pub fn set_guardian(&mut self, guardian: Pubkey) {
+        self.guardian = guardian;
+    }
+
+    #[inline(always)]
+    pub fn set_swap_hook(&mut self, swap_hook: Pubkey) -> Result<(), ProgramError> {
+        // The program cannot call itself back as a hook
+        if swap_hook.eq(&crate::ID) {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+        self.swap_hook = swap_hook;
+        Ok(())
+    }
+
+    #[inline(always)]
+    pub fn set_lp_supply(&mut self, lp_supply: u64) {
+        self.lp_supply = lp_supply.to_le_bytes();
+    }
+
+    #[inline(always)]
+    pub fn set_positioned_lp(&mut self, positioned_lp: u64) {
+        self.positioned_lp = positioned_lp.to_le_bytes();
+    }
+
+    /// Grow the fee counter of the input token by `fee` over the LP supply, and set
+    /// aside the share of positioned LP. Returns the amount set aside.
+    ///
+    /// The share is rounded up so the claims of every position always fit in it. It never
+    /// exceeds `fee`, as positioned LP are real tokens and `lp_supply` is at least the real
+    /// supply.
+    #[inline(always)]
+    pub fn accrue_fee(&mut self, is_x: bool, fee: u64) -> Result<u64, ProgramError> {
+        if self.lp_supply() == 0 || fee == 0 {
+            return Ok(0);
+        }
+
+        let growth = (fee as u128)
+            .checked_mul(FEE_GROWTH_PRECISION)
+            .map(|g| g / self.lp_supply() as u128)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        let owed = growth
+            .checked_mul(self.positioned_lp() as u128)
+            .map(|o| o.div_ceil(FEE_GROWTH_PRECISION))
+            .and_then(|o| u64::try_from(o).ok())
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+
+        let (fee_growth, fees) = match is_x {
+            true => (&mut self.fee_growth_x, &mut self.fees_x),
+            false => (&mut self.fee_growth_y, &mut self.fees_y),
+        };
+
+        *fee_growth = u128::from_le_bytes(*fee_growth)
+            .wrapping_add(growth)
+            .to_le_bytes();
+        *fees = u64::from_le_bytes(*fees)
+            .checked_add(owed)
+            .ok_or(ProgramError::ArithmeticOverflow)?
+            .to_le_bytes();
+        Ok(owed)
+    }
+
+    /// Release `x` and `y` of the unclaimed position fees once they are paid out.
+    #[inline(always)]
+    pub fn take_fees(&mut self, x: u64, y: u64) -> Result<(), ProgramError> {
+        let (Some(fees_x), Some(fees_y)) =
+            (self.fees_x().checked_sub(x), self.fees_y().checked_sub(y))
+        else {
+            return Err(ProgramError::InsufficientFunds);
+        };
+        self.fees_x = fees_x.to_le_bytes();
+        self.fees_y = fees_y.to_le_bytes();
+        Ok(())
+    }
+
+    #[inline(always)]
+    pub fn set_batch_window(&mut self, batch_window: u64) -> Result<(), ProgramError> {
+        // Batches clear on the constant product curve
+        if batch_window != 0 && self.curve() != CurveType::ConstantProduct as u8 {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        self.batch_window = batch_window.to_le_bytes();
+        Ok(())
+    }
+
+    /// Count a batch opened by its first order.
+    #[inline(always)]
+    pub fn open_batch(&mut self) -> Result<(), ProgramError> {
+        self.open_batches = self
+            .open_batches()
+            .checked_add(1)
+            .ok_or(ProgramError::ArithmeticOverflow)?
+            .to_le_bytes();
+        Ok(())
+    }
+
+    /// Count a batch settled, or whose orders were all cancelled.
+    #[inline(always)]
+    pub fn close_batch(&mut self) -> Result<(), ProgramError> {
+        self.open_batches = self
+            .open_batches()
+            .checked_sub(1)
+            .ok_or(ProgramError::InvalidAccountData)?
+            .to_le_bytes();
+        Ok(())
+    }
+
+    /// Set aside `amount` of range order funds in x or y.
+    #[inline(always)]
+    pub fn add_orders(&mut self, is_x: bool, amount: u64) -> Result<(), ProgramError> {
+        let orders = match is_x {
+            true => &mut self.orders_x,
+            false => &mut self.orders_y,
+        };
+        *orders = u64::from_le_bytes(*orders)
+            .checked_add(amount)
+            .ok_or(ProgramError::ArithmeticOverflow)?
+            .to_le_bytes();
+        Ok(())
+    }
+
+    /// Release `amount` of range order funds in x or y once they are paid out or
+    /// converted.
+    #[inline(always)]
+    pub fn take_orders(&mut self, is_x: bool, amount: u64) -> Result<(), ProgramError> {
+        let orders = match is_x {
+            true => &mut self.orders_x,
+            false => &mut self.orders_y,
+        };
+        *orders = u64::from_le_bytes(*orders)
+            .checked_sub(amount)
+            .ok_or(ProgramError::InsufficientFunds)?
+            .to_le_bytes();
+        Ok(())
+    }
+
+    /// Add a swap of `amount_in` for `amount_out` to the volumes, `fee` being charged on
+    /// the input.
+    #[inline(always)]
+    pub fn record_swap(&mut self, is_x: bool, amount_in: u64, amount_out: u64, fee: u64) {
+        let (volume_in, volume_out, fees) = match is_x {
+            true => (
+                &mut self.volume_x,
+                &mut self.volume_y,
+                &mut self.cumulative_fees_x,
+            ),
+            false => (
+                &mut self.volume_y,
+                &mut self.volume_x,
+                &mut self.cumulative_fees_y,
+            ),
+        };
+
+        for (counter, amount) in [
+            (volume_in, amount_in),
+            (volume_out, amount_out),
+            (fees, fee),
+        ] {
+            *counter = u128::from_le_bytes(*counter)
+                .saturating_add(amount as u128)
+                .to_le_bytes();
+        }
+    }
+
+    /// Count a trade landing at `slot` and `now`, leaving the pool at `price` when it
+    /// still has one.
+    ///
+    /// The high and low restart from `price` with the first trade of each UTC day.
+    #[inline(always)]
+    pub fn record_trade(&mut self, slot: u64, now: i64, price: Option<u128>) {
+        self.trade_count = self.trade_count().saturating_add(1).to_le_bytes();
+        self.last_trade_slot = slot.to_le_bytes();
+
+        let Some(price) = price.filter(|price| *price != 0) else {
+            return;
+        };
+
+        let day_start = now - now.rem_euclid(SECONDS_PER_DAY);
+        let (high, low) = match day_start == self.day_start() && self.day_low_price() != 0 {
+            true => (
+                self.day_high_price().max(price),
+                self.day_low_price().min(price),
+            ),
+            false => (price, price),
+        };
+
+        self.day_start = day_start.to_le_bytes();
+        self.day_high_price = high.to_le_bytes();
+        self.day_low_price = low.to_le_bytes();
+    }
+
+    pub fn set_max_slot_move_bps(&mut self, max_slot_move_bps: u16) {
+        self.max_slot_move_bps = max_slot_move_bps.to_le_bytes();
+    }
+
+    /// Track the move of the price within `slot` once a trade took it from
+    /// `price_before` to `price_after`, starting a new window when `slot` differs from
+    /// the tracked one.
+    ///
+    /// The move is the distance from the price at the start of the slot to `price_after`,
+    /// in basis points of the start price rounded up, so splitting a move across small
+    /// trades measures the same as making it at once. A start price of `0`, only reached
+    /// below 1e-18 y per x, cannot be measured against and counts as no move. Returns the
+    /// move of the slot.
+    #[inline(always)]
+    pub fn track_slot_move(&mut self, slot: u64, price_before: u128, price_after: u128) -> u64 {
+        if slot.ne(&self.last_slot()) {
+            self.last_slot = slot.to_le_bytes();
+            self.slot_start_price = price_before.to_le_bytes();
+        }
+
+        let start_price = self.slot_start_price();
+        if start_price == 0 {
+            self.slot_move_bps = 0u64.to_le_bytes();
+            return 0;
+        }
+
+        // Whole multiples of the start price and the rest are scaled apart to stay within
+        // u128, a rest too large to scale is divided by a rounded down divisor instead
+        let distance = price_after.abs_diff(start_price);
+        let (multiples, rest) = (distance / start_price, distance % start_price);
+        let rest_bps = match rest.checked_mul(10_000) {
+            Some(rest) => rest.div_ceil(start_price),
+            None => rest.div_ceil(start_price / 10_000),
+        };
+
+        let slot_move_bps = multiples
+            .saturating_mul(10_000)
+            .saturating_add(rest_bps)
+            .try_into()
+            .unwrap_or(u64::MAX);
+
+        self.slot_move_bps = slot_move_bps.to_le_bytes();
+        slot_move_bps
+    }
+
+    /// Enable the dynamic fee mode, a `max_fee` of `0` falls back to the static `fee`.
+    #[inline(always)]
+    pub fn set_dynamic_fee(
+        &mut self,
+        max_fee: u16,
+        volatility_fee_factor: u16,
+        volatility_half_life: i64,
+    ) -> Result<(), ProgramError> {
+        if max_fee.ge(&10_000)
+            || (max_fee.ne(&0) && max_fee.lt(&self.fee()))
+            || volatility_half_life.le(&0)
+        {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        self.max_fee = max_fee.to_le_bytes();
+        self.volatility_fee_factor = volatility_fee_factor.to_le_bytes();
+        self.volatility_half_life = volatility_half_life.to_le_bytes();
+        self.volatility = 0u64.to_le_bytes();
+        Ok(())
+    }
+
+    /// Whole `volatility_half_life` periods since the volatility was last decayed.
+    #[inline(always)]
+    fn volatility_half_lives(&self, now: i64) -> i64 {
+        now.saturating_sub(self.volatility_updated_at()) / self.volatility_half_life().max(1)
+    }
+
+    /// Volatility accumulator decayed to `now`, without storing it.
+    #[inline(always)]
+    pub fn volatility_at(&self, now: i64) -> u64 {
+        match self.volatility_half_lives(now) {
+            0 => self.volatility(),
+            half_lives @ 1..64 => self.volatility() >> half_lives,
+            _ => 0,
+        }
+    }
+
+    /// Decay the volatility accumulator to `now`, halving it every `volatility_half_life`
+    /// seconds.
+    #[inline(always)]
+    pub fn decay_volatility(&mut self, now: i64) {
+        let half_lives = self.volatility_half_lives(now);
+
+        self.volatility = self.volatility_at(now).to_le_bytes();
+        if half_lives > 0 {
+            self.volatility_updated_at = now.to_le_bytes();
+        }
+    }
+
+    /// Add a price move, in basis points, to the volatility accumulator.
+    #[inline(always)]
+    pub fn accumulate_volatility(&mut self, move_bps: u64) {
+        self.volatility = self.volatility().saturating_add(move_bps).to_le_bytes();
+    }
+
+    /// Fee charged by `Swap`: the base `fee` plus `volatility_fee_factor` basis points of
+    /// the accumulated volatility, capped at `max_fee`.
+    #[inline(always)]
+    pub fn effective_fee(&self) -> u16 {
+        self.fee_with_volatility(self.volatility())
+    }
+
+    /// Fee a swap at `now` would be charged, with the volatility decayed to `now` first,
+    /// for readers that leave the config as it is.
+    #[inline(always)]
+    pub fn effective_fee_at(&self, now: i64) -> u16 {
+        self.fee_with_volatility(self.volatility_at(now))
+    }
+
+    #[inline(always)]
+    fn fee_with_volatility(&self, volatility: u64) -> u16 {
+        if self.max_fee() == 0 {
+            return self.fee();
+        }
+
+        let volatility_fee = volatility as u128 * self.volatility_fee_factor() as u128 / 10_000;
+
+        (self.fee() as u128 + volatility_fee).min(self.max_fee() as u128) as u16
+    }
+
+    /// Switch the pool to the weighted curve, the weight of y is `10_000 - weight_x`.
+    ///
+    /// Rejected in batch mode and while a batch is open, as batches settle on the constant
+    /// product curve.
+    #[inline(always)]
+    pub fn set_weights(
+        &mut self,
+        start_weight_x: u16,
+        end_weight_x: u16,
+        start_time: i64,
+        end_time: i64,
+    ) -> Result<(), ProgramError> {
+        if !(MIN_WEIGHT..=10_000 - MIN_WEIGHT).contains(&start_weight_x)
+            || !(MIN_WEIGHT..=10_000 - MIN_WEIGHT).contains(&end_weight_x)
+            || start_time.ge(&end_time)
+            || self.batch_window() != 0
+            || self.open_batches() != 0
+        {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        self.curve = CurveType::Weighted as u8;
+        self.start_weight_x = start_weight_x.to_le_bytes();
+        self.end_weight_x = end_weight_x.to_le_bytes();
+        self.weights_start_time = start_time.to_le_bytes();
+        self.weights_end_time = end_time.to_le_bytes();
+        Ok(())
+    }
+
+    #[inline(always)]
+    pub fn set_inner(
+        &mut self,
+        seed: u64,
+        authority: Pubkey,
+        mint_x: Pubkey,
+        mint_y: Pubkey,
+        fee: u16,
+        config_bump: [u8; 1],
+    ) -> Result<(), ProgramError> {
+        self.set_state(AmmState::Initialized as u8)?;
+        self.set_seed(seed);
+        self.set_authority(authority);
+        self.set_mint_x(mint_x);
+        self.set_mint_y(mint_y);
+        self.set_fee(fee)?;
+        self.set_config_bump(config_bump);
+        Ok(())
+    }
+
+    #[inline(always)]
+    pub fn has_authority(&self) -> Option<Pubkey> {
+        is_set(&self.authority).then_some(self.authority)
+    }
+
+    #[inline(always)]
+    pub fn has_oracle(&self) -> Option<Pubkey> {
+        is_set(&self.oracle).then_some(self.oracle)
+    }
+
+    #[inline(always)]
+    pub fn has_allowlist(&self) -> Option<Pubkey> {
+        is_set(&self.allowlist).then_some(self.allowlist)
+    }
+
+    #[inline(always)]
+    pub fn has_swap_hook(&self) -> Option<Pubkey> {
+        is_set(&self.swap_hook).then_some(self.swap_hook)
+    }
+
+    #[inline(always)]
+    pub fn has_guardian(&self) -> Option<Pubkey> {
+        is_set(&self.guardian).then_some(self.guardian)
+    }
+}
+
+/// Whether an optional key was set, unset keys being all zeroes.
+#[inline(always)]
+fn is_set(key: &Pubkey) -> bool {
+    *key != [0u8; 32]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    const ONE_PRICE: u128 = 1_000_000_000_000_000_000;
+
+    /// Move of `price` from `start` in basis points of `start`, rounded up.
+    fn move_bps(start: u128, price: u128) -> u64 {
+        (price.abs_diff(start) * 10_000).div_ceil(start) as u64
+    }
+
+    #[test]
+    fn small_trades_add_up_to_the_slot_move() {
+        let mut bytes = [0u8; Config::LEN];
+        let config = unsafe { Config::from_bytes_unchecked_mut(&mut bytes) };
+
+        // A hundred trades of half a basis point each
+        let step = ONE_PRICE / 20_000;
+        let mut price = ONE_PRICE;
+        for _ in 0..100 {
+            config.track_slot_move(1, price, price + step);
+            price += step;
+        }
+
+        assert_eq!(config.slot_move_bps(), 50);
+    }
+
+    #[test]
+    fn a_new_slot_restarts_from_the_price_before() {
+        let mut bytes = [0u8; Config::LEN];
+        let config = unsafe { Config::from_bytes_unchecked_mut(&mut bytes) };
+
+        assert_eq!(config.track_slot_move(1, ONE_PRICE, ONE_PRICE * 2), 10_000);
+        assert_eq!(
+            config.track_slot_move(2, ONE_PRICE * 2, ONE_PRICE * 3),
+            5_000
+        );
+        assert_eq!(config.slot_start_price(), ONE_PRICE * 2);
+    }
+
+    #[test]
+    fn a_zero_start_price_counts_as_no_move() {
+        let mut bytes = [0u8; Config::LEN];
+        let config = unsafe { Config::from_bytes_unchecked_mut(&mut bytes) };
+
+        assert_eq!(config.track_slot_move(1, 0, ONE_PRICE), 0);
+    }
+
+    #[test]
+    fn weights_wait_for_open_batches() {
+        let mut bytes = [0u8; Config::LEN];
+        let config = unsafe { Config::from_bytes_unchecked_mut(&mut bytes) };
+
+        config.set_batch_window(10).unwrap();
+        config.open_batch().unwrap();
+        config.set_batch_window(0).unwrap();
+        assert_eq!(
+            config.set_weights(5_000, 8_000, 0, 1),
+            Err(ProgramError::InvalidAccountData)
+        );
+
+        config.close_batch().unwrap();
+        config.set_weights(5_000, 8_000, 0, 1).unwrap();
+        assert_eq!(config.close_batch(), Err(ProgramError::InvalidAccountData));
+    }
+
+    /// A pool charging 30 bps, up to 100 bps, plus half a basis point per basis point of
+    /// volatility, halving every 60 seconds.
+    fn dynamic_fee(bytes: &mut [u8; Config::LEN]) -> &mut Config {
+        let config = unsafe { Config::from_bytes_unchecked_mut(bytes) };
+        config.set_fee(30).unwrap();
+        config.set_dynamic_fee(100, 5_000, 60).unwrap();
+        config
+    }
+
+    #[test]
+    fn volatility_raises_the_fee_up_to_the_cap() {
+        let mut bytes = [0u8; Config::LEN];
+        let config = dynamic_fee(&mut bytes);
+
+        assert_eq!(config.effective_fee(), 30);
+        config.accumulate_volatility(40);
+        assert_eq!(config.effective_fee(), 50);
+        config.accumulate_volatility(1_000);
+        assert_eq!(config.effective_fee(), 100);
+    }
+
+    #[test]
+    fn volatility_halves_every_half_life() {
+        let mut bytes = [0u8; Config::LEN];
+        let config = dynamic_fee(&mut bytes);
+        config.accumulate_volatility(80);
+
+        assert_eq!(config.effective_fee_at(59), 70);
+        assert_eq!(config.effective_fee_at(60), 50);
+        assert_eq!(config.effective_fee_at(150), 40);
+        assert_eq!(config.effective_fee_at(i64::MAX), 30);
+
+        // Reading the decayed fee leaves the accumulator as it is
+        assert_eq!(config.volatility(), 80);
+
+        config.decay_volatility(150);
+        assert_eq!(config.volatility(), 20);
+        assert_eq!(config.volatility_updated_at(), 150);
+        assert_eq!(config.effective_fee(), config.effective_fee_at(150));
+    }
+
+    #[test]
+    fn dynamic_fee_is_rejected_below_the_base_fee() {
+        let mut bytes = [0u8; Config::LEN];
+        let config = dynamic_fee(&mut bytes);
+
+        assert_eq!(
+            config.set_dynamic_fee(20, 5_000, 60),
+            Err(ProgramError::InvalidAccountData)
+        );
+        assert_eq!(
+            config.set_dynamic_fee(100, 5_000, 0),
+            Err(ProgramError::InvalidAccountData)
+        );
+
+        // A max fee of 0 turns the dynamic fee off
+        config.accumulate_volatility(80);
+        config.set_dynamic_fee(0, 5_000, 60).unwrap();
+        config.accumulate_volatility(80);
+        assert_eq!(config.effective_fee_at(0), 30);
+    }
+
+    proptest! {
+        #[test]
+        fn slot_move_is_the_distance_from_the_slot_start(
+            start in 1..1u128 << 100,
+            prices in prop::collection::vec(0..1u128 << 100, 1..20),
+        ) {
+            let mut bytes = [0u8; Config::LEN];
+            let config = unsafe { Config::from_bytes_unchecked_mut(&mut bytes) };
+
+            let mut before = start;
+            for price in prices {
+                let slot_move_bps = config.track_slot_move(7, before, price);
+
+                prop_assert_eq!(slot_move_bps, move_bps(start, price));
+                prop_assert_eq!(config.slot_move_bps(), slot_move_bps);
+                before = price;
+            }
+        }
+
+        #[test]
+        fn any_slot_move_rounds_up(start in 1..u128::MAX, price in 0..u128::MAX) {
+            let mut bytes = [0u8; Config::LEN];
+            let config = unsafe { Config::from_bytes_unchecked_mut(&mut bytes) };
+
+            let slot_move_bps = config.track_slot_move(1, start, price);
+
+            prop_assert_eq!(slot_move_bps == 0, price == start);
+        }
+    }
+}
@@ -0,0 +1,439 @@
+use core::mem::size_of;
+use pinocchio::{
+    account_info::{AccountInfo, Ref, RefMut},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+/// Scale of `Farm::acc_reward_per_share`.
+pub const REWARD_PRECISION: u128 = 1_000_000_000_000;
+
+/// Liquidity mining campaign paying `reward_rate` reward tokens per second, until
+/// `end_time`, to the LP tokens of `config` staked in the farm. Emissions pause while
+/// nothing is staked, and `end_time` is pushed later by the time they paused.
+#[repr(C)]
+pub struct Farm {
+    config: Pubkey,
+    mint_lp: Pubkey,
+    reward_mint: Pubkey,
+    seed: [u8; 8],
+    reward_rate: [u8; 8],
+    end_time: [u8; 8],
+    last_update: [u8; 8],
+    acc_reward_per_share: [u8; 16],
+    total_staked: [u8; 8],
+    bump: [u8; 1],
+}
+
+/// Stake of `owner` in `farm`, with the standard reward-per-share debt.
+#[repr(C)]
+pub struct UserStake {
+    farm: Pubkey,
+    owner: Pubkey,
+    amount: [u8; 8],
+    reward_debt: [u8; 16],
+    rewards_owed: [u8; 8],
+    bump: [u8; 1],
+}
+
+//Reading helpers
+impl Farm {
+    pub const LEN: usize = size_of::<Pubkey>() * 3
+        + size_of::<u64>()
+        + size_of::<u64>()
+        + size_of::<i64>()
+        + size_of::<i64>()
+        + size_of::<u128>()
+        + size_of::<u64>()
+        + size_of::<u8>();
+
+    #[inline(always)]
+    pub fn load(account_info: &AccountInfo) -> Result<Ref<'_, Self>, ProgramError> {
+        if account_info.data_len() != Self::LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        if account_info.owner().ne(&crate::ID) {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+        Ok(Ref::map(account_info.try_borrow_data()?, |data| unsafe {
+            &*(data.as_ptr() as *const Self)
+        }))
+    }
+
+    #[inline(always)]
+    pub fn config(&self) -> &Pubkey {
+        &self.config
+    }
+
+    #[inline(always)]
+    pub fn mint_lp(&self) -> &Pubkey {
+        &self.mint_lp
+    }
+
+    #[inline(always)]
+    pub fn reward_mint(&self) -> &Pubkey {
+        &self.reward_mint
+    }
+
+    #[inline(always)]
+    pub fn seed(&self) -> u64 {
+        u64::from_le_bytes(self.seed)
+    }
+
+    #[inline(always)]
+    pub fn reward_rate(&self) -> u64 {
+        u64::from_le_bytes(self.reward_rate)
+    }
+
+    #[inline(always)]
+    pub fn end_time(&self) -> i64 {
+        i64::from_le_bytes(self.end_time)
+    }
+
+    #[inline(always)]
+    pub fn last_update(&self) -> i64 {
+        i64::from_le_bytes(self.last_update)
+    }
+
+    #[inline(always)]
+    pub fn acc_reward_per_share(&self) -> u128 {
+        u128::from_le_bytes(self.acc_reward_per_share)
+    }
+
+    #[inline(always)]
+    pub fn total_staked(&self) -> u64 {
+        u64::from_le_bytes(self.total_staked)
+    }
+
+    #[inline(always)]
+    pub fn bump(&self) -> [u8; 1] {
+        self.bump
+    }
+}
+
+//Writing helpers
+impl Farm {
+    #[inline(always)]
+    pub fn load_mut(account_info: &AccountInfo) -> Result<RefMut<'_, Self>, ProgramError> {
+        if account_info.data_len() != Self::LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        if account_info.owner().ne(&crate::ID) {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+        Ok(RefMut::map(
+            account_info.try_borrow_mut_data()?,
+            |data| unsafe { &mut *(data.as_mut_ptr() as *mut Self) },
+        ))
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    #[inline(always)]
+    pub fn set_inner(
+        &mut self,
+        config: Pubkey,
+        mint_lp: Pubkey,
+        reward_mint: Pubkey,
+        seed: u64,
+        reward_rate: u64,
+        start_time: i64,
+        end_time: i64,
+        bump: [u8; 1],
+    ) -> Result<(), ProgramError> {
+        if reward_rate.eq(&0) || end_time.le(&start_time) {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        self.config = config;
+        self.mint_lp = mint_lp;
+        self.reward_mint = reward_mint;
+        self.seed = seed.to_le_bytes();
+        self.reward_rate = reward_rate.to_le_bytes();
+        self.end_time = end_time.to_le_bytes();
+        self.last_update = start_time.to_le_bytes();
+        self.acc_reward_per_share = 0u128.to_le_bytes();
+        self.total_staked = 0u64.to_le_bytes();
+        self.bump = bump;
+        Ok(())
+    }
+
+    /// Accrue the rewards emitted since the last update to the staked LP.
+    ///
+    /// Nothing is emitted while nothing is staked. `end_time` is pushed later by the idle
+    /// time instead, so the schedule runs for as long as the authority funded it with
+    /// LP staked, and every funded reward is still paid out.
+    #[inline(always)]
+    pub fn update(&mut self, now: i64) -> Result<(), ProgramError> {
+        let last_update = self.last_update();
+        if now <= last_update || last_update >= self.end_time() {
+            return Ok(());
+        }
+
+        if self.total_staked() == 0 {
+            let end_time = self
+                .end_time()
+                .checked_add(now - last_update)
+                .ok_or(ProgramError::ArithmeticOverflow)?;
+            self.end_time = end_time.to_le_bytes();
+            self.last_update = now.to_le_bytes();
+            return Ok(());
+        }
+
+        let until = now.min(self.end_time());
+        let elapsed = (until - last_update) as u128;
+        let acc_reward_per_share = (self.reward_rate() as u128)
+            .checked_mul(elapsed)
+            .and_then(|r| r.checked_mul(REWARD_PRECISION))
+            .map(|r| r / self.total_staked() as u128)
+            .and_then(|r| r.checked_add(self.acc_reward_per_share()))
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+
+        self.acc_reward_per_share = acc_reward_per_share.to_le_bytes();
+        self.last_update = until.to_le_bytes();
+        Ok(())
+    }
+
+    #[inline(always)]
+    pub fn set_total_staked(&mut self, total_staked: u64) {
+        self.total_staked = total_staked.to_le_bytes();
+    }
+
+    /// Add `amount` LP to `user_stake`, settling the rewards it earned so far.
+    #[inline(always)]
+    pub fn stake(&mut self, user_stake: &mut UserStake, amount: u64) -> Result<(), ProgramError> {
+        let staked = user_stake
+            .amount()
+            .checked_add(amount)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        user_stake.settle(self.acc_reward_per_share(), staked)?;
+
+        let total_staked = self
+            .total_staked()
+            .checked_add(amount)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        self.set_total_staked(total_staked);
+        Ok(())
+    }
+
+    /// Remove `amount` LP from `user_stake` and return every reward it is owed.
+    #[inline(always)]
+    pub fn unstake(
+        &mut self,
+        user_stake: &mut UserStake,
+        amount: u64,
+    ) -> Result<u64, ProgramError> {
+        let staked = user_stake
+            .amount()
+            .checked_sub(amount)
+            .ok_or(ProgramError::InsufficientFunds)?;
+        user_stake.settle(self.acc_reward_per_share(), staked)?;
+
+        self.set_total_staked(self.total_staked() - amount);
+        Ok(user_stake.take_rewards())
+    }
+}
+
+//Reading helpers
+impl UserStake {
+    pub const LEN: usize = size_of::<Pubkey>() * 2
+        + size_of::<u64>()
+        + size_of::<u128>()
+        + size_of::<u64>()
+        + size_of::<u8>();
+
+    #[inline(always)]
+    pub fn load(account_info: &AccountInfo) -> Result<Ref<'_, Self>, ProgramError> {
+        if account_info.data_len() != Self::LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        if account_info.owner().ne(&crate::ID) {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+        Ok(Ref::map(account_info.try_borrow_data()?, |data| unsafe {
+            &*(data.as_ptr() as *const Self)
+        }))
+    }
+
+    #[inline(always)]
+    pub fn farm(&self) -> &Pubkey {
+        &self.farm
+    }
+
+    #[inline(always)]
+    pub fn owner(&self) -> &Pubkey {
+        &self.owner
+    }
+
+    #[inline(always)]
+    pub fn amount(&self) -> u64 {
+        u64::from_le_bytes(self.amount)
+    }
+
+    #[inline(always)]
+    pub fn reward_debt(&self) -> u128 {
+        u128::from_le_bytes(self.reward_debt)
+    }
+
+    #[inline(always)]
+    pub fn rewards_owed(&self) -> u64 {
+        u64::from_le_bytes(self.rewards_owed)
+    }
+
+    #[inline(always)]
+    pub fn bump(&self) -> [u8; 1] {
+        self.bump
+    }
+}
+
+//Writing helpers
+impl UserStake {
+    #[inline(always)]
+    pub fn load_mut(account_info: &AccountInfo) -> Result<RefMut<'_, Self>, ProgramError> {
+        if account_info.data_len() != Self::LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        if account_info.owner().ne(&crate::ID) {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+        Ok(RefMut::map(
+            account_info.try_borrow_mut_data()?,
+            |data| unsafe { &mut *(data.as_mut_ptr() as *mut Self) },
+        ))
+    }
+
+    #[inline(always)]
+    pub fn set_inner(&mut self, farm: Pubkey, owner: Pubkey, bump: [u8; 1]) {
+        self.farm = farm;
+        self.owner = owner;
+        self.amount = 0u64.to_le_bytes();
+        self.reward_debt = 0u128.to_le_bytes();
+        self.rewards_owed = 0u64.to_le_bytes();
+        self.bump = bump;
+    }
+
+    /// Move the rewards earned up to `acc_reward_per_share` into `rewards_owed`, then
+    /// set the staked amount to `amount`.
+    #[inline(always)]
+    pub fn settle(&mut self, acc_reward_per_share: u128, amount: u64) -> Result<(), ProgramError> {
+        let earned = (self.amount() as u128)
+            .checked_mul(acc_reward_per_share)
+            .map(|r| r / REWARD_PRECISION)
+            .and_then(|r| r.checked_sub(self.reward_debt()))
+            .and_then(|r| u64::try_from(r).ok())
+            .and_then(|r| r.checked_add(self.rewards_owed()))
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+
+        let reward_debt = (amount as u128)
+            .checked_mul(acc_reward_per_share)
+            .map(|r| r / REWARD_PRECISION)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+
+        self.rewards_owed = earned.to_le_bytes();
+        self.amount = amount.to_le_bytes();
+        self.reward_debt = reward_debt.to_le_bytes();
+        Ok(())
+    }
+
+    /// Clear and return the rewards owed.
+    #[inline(always)]
+    pub fn take_rewards(&mut self) -> u64 {
+        let rewards = self.rewards_owed();
+        self.rewards_owed = 0u64.to_le_bytes();
+        rewards
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RATE: u64 = 100;
+    const END_TIME: i64 = 1_000;
+    const FUNDED: u64 = RATE * END_TIME as u64;
+
+    /// A farm paying `RATE` per second from 0 to `END_TIME` in the zeroed `bytes`.
+    fn farm(bytes: &mut [u8; Farm::LEN]) -> &mut Farm {
+        let farm = unsafe { &mut *(bytes.as_mut_ptr() as *mut Farm) };
+        farm.set_inner([0; 32], [0; 32], [0; 32], 0, RATE, 0, END_TIME, [0])
+            .unwrap();
+        farm
+    }
+
+    fn user_stake(bytes: &mut [u8; UserStake::LEN]) -> &mut UserStake {
+        unsafe { &mut *(bytes.as_mut_ptr() as *mut UserStake) }
+    }
+
+    /// Stake `amount` of `user_stake` at `now`.
+    fn stake(farm: &mut Farm, user_stake: &mut UserStake, amount: u64, now: i64) {
+        farm.update(now).unwrap();
+        farm.stake(user_stake, amount).unwrap();
+    }
+
+    /// Unstake all of `user_stake` at `now`, returning its rewards.
+    fn unstake(farm: &mut Farm, user_stake: &mut UserStake, now: i64) -> u64 {
+        farm.update(now).unwrap();
+        farm.unstake(user_stake, user_stake.amount()).unwrap()
+    }
+
+    #[test]
+    fn an_idle_farm_ends_later_by_the_idle_time() {
+        let mut bytes = [0; Farm::LEN];
+        let farm = farm(&mut bytes);
+
+        farm.update(100).unwrap();
+        farm.update(250).unwrap();
+
+        assert_eq!(farm.end_time(), END_TIME + 250);
+        assert_eq!(farm.last_update(), 250);
+        assert_eq!(farm.acc_reward_per_share(), 0);
+    }
+
+    #[test]
+    fn a_single_staker_earns_the_whole_rate() {
+        let (mut bytes, mut stake_bytes) = ([0; Farm::LEN], [0; UserStake::LEN]);
+        let (farm, user_stake) = (farm(&mut bytes), user_stake(&mut stake_bytes));
+
+        stake(farm, user_stake, 10, 0);
+
+        assert_eq!(unstake(farm, user_stake, 500), 500 * RATE);
+        assert_eq!(farm.end_time(), END_TIME);
+    }
+
+    #[test]
+    fn stakers_share_the_rate_in_proportion_to_their_stake() {
+        let mut bytes = [0; Farm::LEN];
+        let mut stake_bytes = [[0; UserStake::LEN]; 2];
+        let farm = farm(&mut bytes);
+        let [first, second] = stake_bytes.each_mut().map(user_stake);
+
+        stake(farm, first, 10, 0);
+        stake(farm, second, 40, 200);
+
+        // 200 seconds alone, then a fifth of 400 seconds
+        assert_eq!(unstake(farm, first, 600), 200 * RATE + 80 * RATE);
+        // Four fifths of 400 seconds, then alone until the end
+        assert_eq!(unstake(farm, second, 2_000), 320 * RATE + 400 * RATE);
+    }
+
+    #[test]
+    fn rewards_stop_at_the_end_time() {
+        let (mut bytes, mut stake_bytes) = ([0; Farm::LEN], [0; UserStake::LEN]);
+        let (farm, user_stake) = (farm(&mut bytes), user_stake(&mut stake_bytes));
+
+        stake(farm, user_stake, 10, 0);
+        farm.update(END_TIME + 500).unwrap();
+
+        assert_eq!(farm.last_update(), END_TIME);
+        assert_eq!(unstake(farm, user_stake, END_TIME + 1_000), FUNDED);
+    }
+
+    #[test]
+    fn idle_time_still_pays_out_every_funded_reward() {
+        let (mut bytes, mut stake_bytes) = ([0; Farm::LEN], [0; UserStake::LEN]);
+        let (farm, user_stake) = (farm(&mut bytes), user_stake(&mut stake_bytes));
+
+        stake(farm, user_stake, 10, 300);
+
+        assert_eq!(farm.end_time(), END_TIME + 300);
+        assert_eq!(unstake(farm, user_stake, 10 * END_TIME), FUNDED);
+    }
+}
@@ -0,0 +1,257 @@
+use crate::math::constant_product::{self, SwapResult};
+use core::mem::size_of;
+use pinocchio::{
+    account_info::{AccountInfo, Ref, RefMut},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+/// Token launch selling `mint` for `quote_mint` along a constant product curve on
+/// virtual reserves, until `target_raise` quote tokens are raised.
+///
+/// The curve reserves are `virtual_quote + quote_raised` and
+/// `virtual_token - tokens_sold`. Once the raise completes, `Graduate` moves the raised
+/// quote tokens and the unsold supply into a regular pool.
+#[repr(C)]
+pub struct Launch {
+    state: u8,
+    creator: Pubkey,
+    mint: Pubkey,
+    quote_mint: Pubkey,
+    virtual_quote: [u8; 8],
+    virtual_token: [u8; 8],
+    sale_supply: [u8; 8],
+    tokens_sold: [u8; 8],
+    quote_raised: [u8; 8],
+    target_raise: [u8; 8],
+    pool_fee: [u8; 2],
+    lp_lock_duration: [u8; 8],
+    config: Pubkey,
+    bump: [u8; 1],
+}
+
+#[repr(u8)]
+pub enum LaunchState {
+    Uninitialized = 0u8,
+    Trading = 1u8,
+    Completed = 2u8,
+    Graduated = 3u8,
+}
+
+//Reading helpers
+impl Launch {
+    pub const LEN: usize = size_of::<u8>()
+        + size_of::<Pubkey>() * 3
+        + size_of::<u64>()
+        + size_of::<u64>()
+        + size_of::<u64>()
+        + size_of::<u64>()
+        + size_of::<u64>()
+        + size_of::<u64>()
+        + size_of::<u16>()
+        + size_of::<i64>()
+        + size_of::<Pubkey>()
+        + size_of::<u8>();
+
+    #[inline(always)]
+    pub fn load(account_info: &AccountInfo) -> Result<Ref<'_, Self>, ProgramError> {
+        if account_info.data_len() != Self::LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        if account_info.owner().ne(&crate::ID) {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+        Ok(Ref::map(account_info.try_borrow_data()?, |data| unsafe {
+            &*(data.as_ptr() as *const Self)
+        }))
+    }
+
+    #[inline(always)]
+    pub fn state(&self) -> u8 {
+        self.state
+    }
+
+    #[inline(always)]
+    pub fn creator(&self) -> &Pubkey {
+        &self.creator
+    }
+
+    #[inline(always)]
+    pub fn mint(&self) -> &Pubkey {
+        &self.mint
+    }
+
+    #[inline(always)]
+    pub fn quote_mint(&self) -> &Pubkey {
+        &self.quote_mint
+    }
+
+    #[inline(always)]
+    pub fn virtual_quote(&self) -> u64 {
+        u64::from_le_bytes(self.virtual_quote)
+    }
+
+    #[inline(always)]
+    pub fn virtual_token(&self) -> u64 {
+        u64::from_le_bytes(self.virtual_token)
+    }
+
+    #[inline(always)]
+    pub fn sale_supply(&self) -> u64 {
+        u64::from_le_bytes(self.sale_supply)
+    }
+
+    #[inline(always)]
+    pub fn tokens_sold(&self) -> u64 {
+        u64::from_le_bytes(self.tokens_sold)
+    }
+
+    #[inline(always)]
+    pub fn quote_raised(&self) -> u64 {
+        u64::from_le_bytes(self.quote_raised)
+    }
+
+    #[inline(always)]
+    pub fn target_raise(&self) -> u64 {
+        u64::from_le_bytes(self.target_raise)
+    }
+
+    #[inline(always)]
+    pub fn pool_fee(&self) -> u16 {
+        u16::from_le_bytes(self.pool_fee)
+    }
+
+    #[inline(always)]
+    pub fn lp_lock_duration(&self) -> i64 {
+        i64::from_le_bytes(self.lp_lock_duration)
+    }
+
+    /// Pool the launch graduated into, zero until then.
+    #[inline(always)]
+    pub fn config(&self) -> &Pubkey {
+        &self.config
+    }
+
+    #[inline(always)]
+    pub fn bump(&self) -> [u8; 1] {
+        self.bump
+    }
+
+    /// Curve reserves of quote and launched tokens.
+    #[inline(always)]
+    pub fn reserves(&self) -> (u64, u64) {
+        (
+            self.virtual_quote() + self.quote_raised(),
+            self.virtual_token() - self.tokens_sold(),
+        )
+    }
+}
+
+//Writing helpers
+impl Launch {
+    #[inline(always)]
+    pub fn load_mut(account_info: &AccountInfo) -> Result<RefMut<'_, Self>, ProgramError> {
+        if account_info.data_len() != Self::LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        if account_info.owner().ne(&crate::ID) {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+        Ok(RefMut::map(
+            account_info.try_borrow_mut_data()?,
+            |data| unsafe { &mut *(data.as_mut_ptr() as *mut Self) },
+        ))
+    }
+
+    /// Set up the launch, checking that raising `target_raise` sells no more than
+    /// `sale_supply`.
+    #[allow(clippy::too_many_arguments)]
+    #[inline(always)]
+    pub fn set_inner(
+        &mut self,
+        creator: Pubkey,
+        mint: Pubkey,
+        quote_mint: Pubkey,
+        virtual_quote: u64,
+        virtual_token: u64,
+        sale_supply: u64,
+        target_raise: u64,
+        pool_fee: u16,
+        lp_lock_duration: i64,
+        bump: [u8; 1],
+    ) -> Result<(), ProgramError> {
+        if mint.eq(&quote_mint) || pool_fee.ge(&10_000) || lp_lock_duration.le(&0) {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let sold_at_target =
+            constant_product::swap(virtual_quote, virtual_token, target_raise, 0, 0)?.withdraw;
+        if sold_at_target > sale_supply || sale_supply > virtual_token {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        self.state = LaunchState::Trading as u8;
+        self.creator = creator;
+        self.mint = mint;
+        self.quote_mint = quote_mint;
+        self.virtual_quote = virtual_quote.to_le_bytes();
+        self.virtual_token = virtual_token.to_le_bytes();
+        self.sale_supply = sale_supply.to_le_bytes();
+        self.tokens_sold = 0u64.to_le_bytes();
+        self.quote_raised = 0u64.to_le_bytes();
+        self.target_raise = target_raise.to_le_bytes();
+        self.pool_fee = pool_fee.to_le_bytes();
+        self.lp_lock_duration = lp_lock_duration.to_le_bytes();
+        self.config = [0u8; 32];
+        self.bump = bump;
+        Ok(())
+    }
+
+    /// Sell launched tokens for up to `amount` quote tokens, capped at what is left to
+    /// raise. Completes the launch once the target is raised.
+    #[inline(always)]
+    pub fn buy(&mut self, amount: u64, min: u64) -> Result<SwapResult, ProgramError> {
+        let amount = amount.min(self.target_raise() - self.quote_raised());
+        let (quote, token) = self.reserves();
+        let result = constant_product::swap(quote, token, amount, 0, min)?;
+
+        let tokens_sold = self.tokens_sold() + result.withdraw;
+        if tokens_sold > self.sale_supply() {
+            return Err(ProgramError::InsufficientFunds);
+        }
+
+        let quote_raised = self.quote_raised() + result.deposit;
+        self.tokens_sold = tokens_sold.to_le_bytes();
+        self.quote_raised = quote_raised.to_le_bytes();
+
+        if quote_raised == self.target_raise() {
+            self.state = LaunchState::Completed as u8;
+        }
+        Ok(result)
+    }
+
+    /// Buy `amount` launched tokens back for quote tokens.
+    #[inline(always)]
+    pub fn sell(&mut self, amount: u64, min: u64) -> Result<SwapResult, ProgramError> {
+        let (quote, token) = self.reserves();
+        let result = constant_product::swap(token, quote, amount, 0, min)?;
+
+        let (Some(tokens_sold), Some(quote_raised)) = (
+            self.tokens_sold().checked_sub(result.deposit),
+            self.quote_raised().checked_sub(result.withdraw),
+        ) else {
+            return Err(ProgramError::InsufficientFunds);
+        };
+
+        self.tokens_sold = tokens_sold.to_le_bytes();
+        self.quote_raised = quote_raised.to_le_bytes();
+        Ok(result)
+    }
+
+    /// Record the pool the launch graduated into.
+    #[inline(always)]
+    pub fn graduate(&mut self, config: Pubkey) {
+        self.state = LaunchState::Graduated as u8;
+        self.config = config;
+    }
+}
@@ -0,0 +1,191 @@
+use core::mem::size_of;
+use pinocchio::{
+    account_info::{AccountInfo, Ref, RefMut},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+/// LP tokens escrowed by `owner` that vest linearly from `start_time` to `end_time`,
+/// nothing being unlockable before `cliff_time`.
+///
+/// The escrowed LP sits in the associated token account of the lock, or in a farm the
+/// lock staked it into. Integers are little-endian:
+///
+/// | Offset | Size | Field        |
+/// |--------|------|--------------|
+/// | 0      | 32   | `config`     |
+/// | 32     | 32   | `owner`      |
+/// | 64     | 32   | `mint_lp`    |
+/// | 96     | 8    | `seed`       |
+/// | 104    | 8    | `amount`     |
+/// | 112    | 8    | `withdrawn`  |
+/// | 120    | 8    | `start_time` |
+/// | 128    | 8    | `cliff_time` |
+/// | 136    | 8    | `end_time`   |
+/// | 144    | 1    | `bump`       |
+#[repr(C)]
+pub struct Lock {
+    config: Pubkey,
+    owner: Pubkey,
+    mint_lp: Pubkey,
+    seed: [u8; 8],
+    amount: [u8; 8],
+    withdrawn: [u8; 8],
+    start_time: [u8; 8],
+    cliff_time: [u8; 8],
+    end_time: [u8; 8],
+    bump: [u8; 1],
+}
+
+//Reading helpers
+impl Lock {
+    pub const LEN: usize = size_of::<Pubkey>() * 3
+        + size_of::<u64>()
+        + size_of::<u64>()
+        + size_of::<u64>()
+        + size_of::<i64>()
+        + size_of::<i64>()
+        + size_of::<i64>()
+        + size_of::<u8>();
+
+    #[inline(always)]
+    pub fn load(account_info: &AccountInfo) -> Result<Ref<'_, Self>, ProgramError> {
+        if account_info.data_len() != Self::LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        if account_info.owner().ne(&crate::ID) {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+        Ok(Ref::map(account_info.try_borrow_data()?, |data| unsafe {
+            &*(data.as_ptr() as *const Self)
+        }))
+    }
+
+    #[inline(always)]
+    pub fn config(&self) -> &Pubkey {
+        &self.config
+    }
+
+    #[inline(always)]
+    pub fn owner(&self) -> &Pubkey {
+        &self.owner
+    }
+
+    #[inline(always)]
+    pub fn mint_lp(&self) -> &Pubkey {
+        &self.mint_lp
+    }
+
+    #[inline(always)]
+    pub fn seed(&self) -> u64 {
+        u64::from_le_bytes(self.seed)
+    }
+
+    #[inline(always)]
+    pub fn amount(&self) -> u64 {
+        u64::from_le_bytes(self.amount)
+    }
+
+    #[inline(always)]
+    pub fn withdrawn(&self) -> u64 {
+        u64::from_le_bytes(self.withdrawn)
+    }
+
+    #[inline(always)]
+    pub fn start_time(&self) -> i64 {
+        i64::from_le_bytes(self.start_time)
+    }
+
+    #[inline(always)]
+    pub fn cliff_time(&self) -> i64 {
+        i64::from_le_bytes(self.cliff_time)
+    }
+
+    #[inline(always)]
+    pub fn end_time(&self) -> i64 {
+        i64::from_le_bytes(self.end_time)
+    }
+
+    #[inline(always)]
+    pub fn bump(&self) -> [u8; 1] {
+        self.bump
+    }
+
+    /// LP vested at `now`, withdrawn or not.
+    #[inline(always)]
+    pub fn vested(&self, now: i64) -> u64 {
+        if now < self.cliff_time() {
+            return 0;
+        }
+        if now >= self.end_time() {
+            return self.amount();
+        }
+
+        let elapsed = (now - self.start_time()) as u128;
+        let duration = (self.end_time() - self.start_time()) as u128;
+        (self.amount() as u128 * elapsed / duration) as u64
+    }
+
+    /// LP the owner can withdraw at `now`.
+    #[inline(always)]
+    pub fn unlockable(&self, now: i64) -> u64 {
+        self.vested(now) - self.withdrawn()
+    }
+}
+
+//Writing helpers
+impl Lock {
+    #[inline(always)]
+    pub fn load_mut(account_info: &AccountInfo) -> Result<RefMut<'_, Self>, ProgramError> {
+        if account_info.data_len() != Self::LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        if account_info.owner().ne(&crate::ID) {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+        Ok(RefMut::map(
+            account_info.try_borrow_mut_data()?,
+            |data| unsafe { &mut *(data.as_mut_ptr() as *mut Self) },
+        ))
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    #[inline(always)]
+    pub fn set_inner(
+        &mut self,
+        config: Pubkey,
+        owner: Pubkey,
+        mint_lp: Pubkey,
+        seed: u64,
+        amount: u64,
+        start_time: i64,
+        cliff_time: i64,
+        end_time: i64,
+        bump: [u8; 1],
+    ) -> Result<(), ProgramError> {
+        if cliff_time < start_time || end_time < cliff_time || end_time <= start_time {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+        self.config = config;
+        self.owner = owner;
+        self.mint_lp = mint_lp;
+        self.seed = seed.to_le_bytes();
+        self.amount = amount.to_le_bytes();
+        self.withdrawn = 0u64.to_le_bytes();
+        self.start_time = start_time.to_le_bytes();
+        self.cliff_time = cliff_time.to_le_bytes();
+        self.end_time = end_time.to_le_bytes();
+        self.bump = bump;
+        Ok(())
+    }
+
+    /// Record the withdrawal of `amount` vested LP at `now`.
+    #[inline(always)]
+    pub fn withdraw(&mut self, amount: u64, now: i64) -> Result<(), ProgramError> {
+        if amount > self.unlockable(now) {
+            return Err(ProgramError::InsufficientFunds);
+        }
+        self.withdrawn = (self.withdrawn() + amount).to_le_bytes();
+        Ok(())
+    }
+}
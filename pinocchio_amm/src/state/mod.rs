@@ -0,0 +1,29 @@
+pub mod config;
+pub use config::*;
+
+pub mod multi_pool;
+pub use multi_pool::*;
+
+pub mod farm;
+pub use farm::*;
+
+pub mod allowlist;
+pub use allowlist::*;
+
+pub mod lock;
+pub use lock::*;
+
+pub mod position;
+pub use position::*;
+
+pub mod launch;
+pub use launch::*;
+
+pub mod range_order;
+pub use range_order::*;
+
+pub mod swap_commit;
+pub use swap_commit::*;
+
+pub mod batch;
+pub use batch::*;
@@ -0,0 +1,173 @@
+use crate::state::{AmmState, MIN_WEIGHT};
+use core::mem::size_of;
+use pinocchio::{
+    account_info::{AccountInfo, Ref, RefMut},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+/// Most tokens a multi-asset pool can hold.
+pub const MAX_TOKENS: usize = 8;
+
+/// Weighted pool of `count` tokens, whose weights in basis points add up to `10_000`.
+///
+/// Only the first `count` entries of `mints` and `weights` are in use, the vault of each
+/// mint is the associated token account of the pool.
+#[repr(C)]
+pub struct MultiPool {
+    state: u8,
+    seed: [u8; 8],
+    authority: Pubkey,
+    fee: [u8; 2],
+    bump: [u8; 1],
+    count: u8,
+    mints: [Pubkey; MAX_TOKENS],
+    weights: [[u8; 2]; MAX_TOKENS],
+}
+
+//Reading helpers
+impl MultiPool {
+    pub const LEN: usize = size_of::<u8>()
+        + size_of::<u64>()
+        + size_of::<Pubkey>()
+        + size_of::<u16>()
+        + size_of::<u8>()
+        + size_of::<u8>()
+        + size_of::<Pubkey>() * MAX_TOKENS
+        + size_of::<u16>() * MAX_TOKENS;
+
+    #[inline(always)]
+    pub fn load(account_info: &AccountInfo) -> Result<Ref<'_, Self>, ProgramError> {
+        if account_info.data_len() != Self::LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        if account_info.owner().ne(&crate::ID) {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+        Ok(Ref::map(account_info.try_borrow_data()?, |data| unsafe {
+            Self::from_bytes_unchecked(data)
+        }))
+    }
+
+    /// Return a `MultiPool` from the given bytes.
+    ///
+    /// # Safety
+    ///
+    /// The caller must ensure that `bytes` contains a valid representation of `MultiPool`.
+    /// `MultiPool` has an alignment of 1 byte and no length validation is performed.
+    #[inline(always)]
+    pub unsafe fn from_bytes_unchecked(bytes: &[u8]) -> &Self {
+        &*(bytes.as_ptr() as *const MultiPool)
+    }
+
+    /// Return a mutable `MultiPool` reference from the given bytes.
+    ///
+    /// # Safety
+    ///
+    /// The caller must ensure that `bytes` contains a valid representation of `MultiPool`.
+    #[inline(always)]
+    pub unsafe fn from_bytes_unchecked_mut(bytes: &mut [u8]) -> &mut Self {
+        &mut *(bytes.as_mut_ptr() as *mut MultiPool)
+    }
+
+    #[inline(always)]
+    pub fn state(&self) -> u8 {
+        self.state
+    }
+
+    #[inline(always)]
+    pub fn seed(&self) -> u64 {
+        u64::from_le_bytes(self.seed)
+    }
+
+    #[inline(always)]
+    pub fn authority(&self) -> &Pubkey {
+        &self.authority
+    }
+
+    #[inline(always)]
+    pub fn fee(&self) -> u16 {
+        u16::from_le_bytes(self.fee)
+    }
+
+    #[inline(always)]
+    pub fn bump(&self) -> [u8; 1] {
+        self.bump
+    }
+
+    #[inline(always)]
+    pub fn count(&self) -> usize {
+        self.count as usize
+    }
+
+    #[inline(always)]
+    pub fn mints(&self) -> &[Pubkey] {
+        &self.mints[..self.count()]
+    }
+
+    #[inline(always)]
+    pub fn weight(&self, index: usize) -> u16 {
+        u16::from_le_bytes(self.weights[index])
+    }
+}
+
+//Writing helpers
+impl MultiPool {
+    #[inline(always)]
+    pub fn load_mut(account_info: &AccountInfo) -> Result<RefMut<'_, Self>, ProgramError> {
+        if account_info.data_len() != Self::LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        if account_info.owner().ne(&crate::ID) {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+        Ok(RefMut::map(
+            account_info.try_borrow_mut_data()?,
+            |data| unsafe { Self::from_bytes_unchecked_mut(data) },
+        ))
+    }
+
+    #[inline(always)]
+    pub fn set_inner(
+        &mut self,
+        seed: u64,
+        authority: Pubkey,
+        fee: u16,
+        bump: [u8; 1],
+        mints: &[Pubkey],
+        weights: &[u16],
+    ) -> Result<(), ProgramError> {
+        if fee.ge(&10_000) || !(2..=MAX_TOKENS).contains(&mints.len()) {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        if mints.len().ne(&weights.len())
+            || weights.iter().any(|weight| weight.lt(&MIN_WEIGHT))
+            || weights
+                .iter()
+                .map(|&weight| weight as u32)
+                .sum::<u32>()
+                .ne(&10_000)
+        {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        if mints
+            .iter()
+            .enumerate()
+            .any(|(i, mint)| mints[i + 1..].contains(mint))
+        {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        self.state = AmmState::Initialized as u8;
+        self.seed = seed.to_le_bytes();
+        self.authority = authority;
+        self.fee = fee.to_le_bytes();
+        self.bump = bump;
+        self.count = mints.len() as u8;
+        self.mints[..mints.len()].copy_from_slice(mints);
+        for (slot, weight) in self.weights.iter_mut().zip(weights) {
+            *slot = weight.to_le_bytes();
+        }
+        Ok(())
+    }
+}
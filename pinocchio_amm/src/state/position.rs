@@ -0,0 +1,165 @@
+use crate::state::FEE_GROWTH_PRECISION;
+use core::mem::size_of;
+use pinocchio::{
+    account_info::{AccountInfo, Ref, RefMut},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+/// LP of `owner` escrowed in `config` to earn its swap fees apart from the principal.
+///
+/// The fee growth of the pool is snapshot at every change, the fees earned in between
+/// accumulate in `fees_owed_x` and `fees_owed_y` until claimed. `bump` is the canonical
+/// bump of the position address, found by `IncreasePosition` when it creates it.
+#[repr(C)]
+pub struct Position {
+    config: Pubkey,
+    owner: Pubkey,
+    amount: [u8; 8],
+    fee_growth_x: [u8; 16],
+    fee_growth_y: [u8; 16],
+    fees_owed_x: [u8; 8],
+    fees_owed_y: [u8; 8],
+    bump: [u8; 1],
+}
+
+//Reading helpers
+impl Position {
+    pub const LEN: usize = size_of::<Pubkey>() * 2
+        + size_of::<u64>()
+        + size_of::<u128>()
+        + size_of::<u128>()
+        + size_of::<u64>()
+        + size_of::<u64>()
+        + size_of::<u8>();
+
+    #[inline(always)]
+    pub fn load(account_info: &AccountInfo) -> Result<Ref<'_, Self>, ProgramError> {
+        if account_info.data_len() != Self::LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        if account_info.owner().ne(&crate::ID) {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+        Ok(Ref::map(account_info.try_borrow_data()?, |data| unsafe {
+            &*(data.as_ptr() as *const Self)
+        }))
+    }
+
+    #[inline(always)]
+    pub fn config(&self) -> &Pubkey {
+        &self.config
+    }
+
+    #[inline(always)]
+    pub fn owner(&self) -> &Pubkey {
+        &self.owner
+    }
+
+    #[inline(always)]
+    pub fn amount(&self) -> u64 {
+        u64::from_le_bytes(self.amount)
+    }
+
+    #[inline(always)]
+    pub fn fee_growth_x(&self) -> u128 {
+        u128::from_le_bytes(self.fee_growth_x)
+    }
+
+    #[inline(always)]
+    pub fn fee_growth_y(&self) -> u128 {
+        u128::from_le_bytes(self.fee_growth_y)
+    }
+
+    #[inline(always)]
+    pub fn fees_owed_x(&self) -> u64 {
+        u64::from_le_bytes(self.fees_owed_x)
+    }
+
+    #[inline(always)]
+    pub fn fees_owed_y(&self) -> u64 {
+        u64::from_le_bytes(self.fees_owed_y)
+    }
+
+    #[inline(always)]
+    pub fn bump(&self) -> [u8; 1] {
+        self.bump
+    }
+}
+
+//Writing helpers
+impl Position {
+    #[inline(always)]
+    pub fn load_mut(account_info: &AccountInfo) -> Result<RefMut<'_, Self>, ProgramError> {
+        if account_info.data_len() != Self::LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        if account_info.owner().ne(&crate::ID) {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+        Ok(RefMut::map(
+            account_info.try_borrow_mut_data()?,
+            |data| unsafe { &mut *(data.as_mut_ptr() as *mut Self) },
+        ))
+    }
+
+    #[inline(always)]
+    pub fn set_inner(
+        &mut self,
+        config: Pubkey,
+        owner: Pubkey,
+        fee_growth_x: u128,
+        fee_growth_y: u128,
+        bump: [u8; 1],
+    ) {
+        self.config = config;
+        self.owner = owner;
+        self.amount = 0u64.to_le_bytes();
+        self.fee_growth_x = fee_growth_x.to_le_bytes();
+        self.fee_growth_y = fee_growth_y.to_le_bytes();
+        self.fees_owed_x = 0u64.to_le_bytes();
+        self.fees_owed_y = 0u64.to_le_bytes();
+        self.bump = bump;
+    }
+
+    /// Move the fees earned up to the given pool fee growth into the owed fees, then set
+    /// the escrowed amount to `amount`.
+    ///
+    /// The counters may wrap, so the growth since the snapshot is taken modulo 2^128.
+    #[inline(always)]
+    pub fn settle(
+        &mut self,
+        fee_growth_x: u128,
+        fee_growth_y: u128,
+        amount: u64,
+    ) -> Result<(), ProgramError> {
+        let earned = |growth: u128, snapshot: u128, owed: u64| {
+            growth
+                .wrapping_sub(snapshot)
+                .checked_mul(self.amount() as u128)
+                .map(|f| f / FEE_GROWTH_PRECISION)
+                .and_then(|f| u64::try_from(f).ok())
+                .and_then(|f| f.checked_add(owed))
+                .ok_or(ProgramError::ArithmeticOverflow)
+        };
+
+        let fees_owed_x = earned(fee_growth_x, self.fee_growth_x(), self.fees_owed_x())?;
+        let fees_owed_y = earned(fee_growth_y, self.fee_growth_y(), self.fees_owed_y())?;
+
+        self.fees_owed_x = fees_owed_x.to_le_bytes();
+        self.fees_owed_y = fees_owed_y.to_le_bytes();
+        self.fee_growth_x = fee_growth_x.to_le_bytes();
+        self.fee_growth_y = fee_growth_y.to_le_bytes();
+        self.amount = amount.to_le_bytes();
+        Ok(())
+    }
+
+    /// Clear and return the fees owed in x and y.
+    #[inline(always)]
+    pub fn take_fees(&mut self) -> (u64, u64) {
+        let fees = (self.fees_owed_x(), self.fees_owed_y());
+        self.fees_owed_x = 0u64.to_le_bytes();
+        self.fees_owed_y = 0u64.to_le_bytes();
+        fees
+    }
+}
@@ -0,0 +1,353 @@
+use crate::math::{in_given_price, out_given_in};
+use crate::oracle::PRICE_SCALE;
+use core::mem::size_of;
+use pinocchio::{
+    account_info::{AccountInfo, Ref, RefMut},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+/// Limit order of `owner` resting in `config` as single-sided liquidity.
+///
+/// The deposited token is held by the pool vault but kept out of the reserves. Once a
+/// swap moves the price of x in y across `price`, the pool buys as much of the order as
+/// its curve would trade to bring the price back to `price`, paying `price` for it, and
+/// the proceeds wait in `filled` until claimed. An order may fill over several swaps.
+#[repr(C)]
+pub struct RangeOrder {
+    config: Pubkey,
+    owner: Pubkey,
+    is_x: u8,
+    price: [u8; 16],
+    amount: [u8; 8],
+    filled: [u8; 8],
+    bump: [u8; 1],
+}
+
+//Reading helpers
+impl RangeOrder {
+    pub const LEN: usize = size_of::<Pubkey>() * 2
+        + size_of::<u8>()
+        + size_of::<u128>()
+        + size_of::<u64>()
+        + size_of::<u64>()
+        + size_of::<u8>();
+
+    #[inline(always)]
+    pub fn load(account_info: &AccountInfo) -> Result<Ref<'_, Self>, ProgramError> {
+        if account_info.data_len() != Self::LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        if account_info.owner().ne(&crate::ID) {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+        Ok(Ref::map(account_info.try_borrow_data()?, |data| unsafe {
+            &*(data.as_ptr() as *const Self)
+        }))
+    }
+
+    #[inline(always)]
+    pub fn config(&self) -> &Pubkey {
+        &self.config
+    }
+
+    #[inline(always)]
+    pub fn owner(&self) -> &Pubkey {
+        &self.owner
+    }
+
+    /// Whether the order sells x for y, rather than y for x.
+    #[inline(always)]
+    pub fn is_x(&self) -> bool {
+        self.is_x == 1
+    }
+
+    /// Price of x in y the order converts at, scaled by `PRICE_SCALE`.
+    #[inline(always)]
+    pub fn price(&self) -> u128 {
+        u128::from_le_bytes(self.price)
+    }
+
+    /// Deposited amount not converted yet.
+    #[inline(always)]
+    pub fn amount(&self) -> u64 {
+        u64::from_le_bytes(self.amount)
+    }
+
+    /// Proceeds of the conversion, in the other token.
+    #[inline(always)]
+    pub fn filled(&self) -> u64 {
+        u64::from_le_bytes(self.filled)
+    }
+
+    #[inline(always)]
+    pub fn bump(&self) -> [u8; 1] {
+        self.bump
+    }
+
+    /// Whether a pool price of `price` has crossed the order.
+    #[inline(always)]
+    pub fn is_crossed(&self, price: u128) -> bool {
+        match self.is_x() {
+            true => price >= self.price(),
+            false => price <= self.price(),
+        }
+    }
+
+    /// Proceeds of converting `amount` of the order at the order price, rounded down.
+    #[inline(always)]
+    pub fn proceeds(&self, amount: u64) -> Result<u64, ProgramError> {
+        let amount = amount as u128;
+
+        // Splitting the price keeps x orders within u128 at any price
+        let proceeds = match self.is_x() {
+            true => (self.price() / PRICE_SCALE)
+                .checked_mul(amount)
+                .and_then(|p| p.checked_add(self.price() % PRICE_SCALE * amount / PRICE_SCALE)),
+            false => Some(amount * PRICE_SCALE / self.price()),
+        };
+
+        proceeds
+            .and_then(|p| u64::try_from(p).ok())
+            .ok_or(ProgramError::ArithmeticOverflow)
+    }
+
+    /// Amount of the order the pool buys at reserves `(x, y)` weighted `(weight_x,
+    /// weight_y)` and spot price `price`, and what it pays for it, `(0, 0)` when the
+    /// order is not crossed.
+    ///
+    /// The pool buys what its curve would trade to take the price back to the order
+    /// price, at most the unfilled amount, and pays the order price for it capped at the
+    /// curve output, so the invariant never decreases.
+    #[inline(always)]
+    pub fn fill_amounts(
+        &self,
+        (x, y): (u64, u64),
+        (weight_x, weight_y): (u16, u16),
+        price: u128,
+    ) -> Result<(u64, u64), ProgramError> {
+        if self.amount() == 0 || !self.is_crossed(price) {
+            return Ok((0, 0));
+        }
+
+        // The in token price falls from `from` to `to`, y orders invert the x price
+        let (balance_in, weight_in, balance_out, weight_out, from, to) = match self.is_x() {
+            true => (x, weight_x, y, weight_y, price, self.price()),
+            false => (y, weight_y, x, weight_x, self.price(), price),
+        };
+
+        let sold = in_given_price(balance_in, weight_in, weight_out, from, to)
+            .ok_or(ProgramError::ArithmeticOverflow)?
+            .min(self.amount());
+        if sold == 0 {
+            return Ok((0, 0));
+        }
+
+        let curve_out = out_given_in(balance_in, weight_in, balance_out, weight_out, sold)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+
+        Ok((sold, self.proceeds(sold)?.min(curve_out)))
+    }
+}
+
+//Writing helpers
+impl RangeOrder {
+    #[inline(always)]
+    pub fn load_mut(account_info: &AccountInfo) -> Result<RefMut<'_, Self>, ProgramError> {
+        if account_info.data_len() != Self::LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        if account_info.owner().ne(&crate::ID) {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+        Ok(RefMut::map(
+            account_info.try_borrow_mut_data()?,
+            |data| unsafe { &mut *(data.as_mut_ptr() as *mut Self) },
+        ))
+    }
+
+    #[inline(always)]
+    pub fn set_inner(
+        &mut self,
+        config: Pubkey,
+        owner: Pubkey,
+        is_x: bool,
+        price: u128,
+        amount: u64,
+        bump: [u8; 1],
+    ) -> Result<(), ProgramError> {
+        if price == 0 || amount == 0 {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        self.config = config;
+        self.owner = owner;
+        self.is_x = is_x as u8;
+        self.price = price.to_le_bytes();
+        self.amount = amount.to_le_bytes();
+        self.filled = 0u64.to_le_bytes();
+        self.bump = bump;
+        Ok(())
+    }
+
+    /// Convert `sold` of the unfilled amount into `proceeds`.
+    #[inline(always)]
+    pub fn fill(&mut self, sold: u64, proceeds: u64) -> Result<(), ProgramError> {
+        let amount = self
+            .amount()
+            .checked_sub(sold)
+            .ok_or(ProgramError::InsufficientFunds)?;
+        let filled = self
+            .filled()
+            .checked_add(proceeds)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+
+        self.amount = amount.to_le_bytes();
+        self.filled = filled.to_le_bytes();
+        Ok(())
+    }
+
+    /// Clear the proceeds once they are paid out.
+    #[inline(always)]
+    pub fn clear_filled(&mut self) {
+        self.filled = 0u64.to_le_bytes();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::spot_price;
+    use proptest::prelude::*;
+
+    /// An order of `amount` selling x, or y, at `price` in the zeroed `bytes`.
+    fn order(
+        bytes: &mut [u8; RangeOrder::LEN],
+        is_x: bool,
+        price: u128,
+        amount: u64,
+    ) -> &mut RangeOrder {
+        let order = unsafe { &mut *(bytes.as_mut_ptr() as *mut RangeOrder) };
+        order
+            .set_inner([0; 32], [0; 32], is_x, price, amount, [0])
+            .unwrap();
+        order
+    }
+
+    fn price(x: u64, y: u64) -> u128 {
+        spot_price(x, 5_000, y, 5_000, PRICE_SCALE).unwrap()
+    }
+
+    /// Fill `order` against a constant product pool of reserves `(x, y)`, returning the
+    /// reserves after the fill.
+    fn fill(order: &mut RangeOrder, (x, y): (u64, u64)) -> (u64, u64) {
+        let (sold, proceeds) = order
+            .fill_amounts((x, y), (5_000, 5_000), price(x, y))
+            .unwrap();
+        order.fill(sold, proceeds).unwrap();
+
+        match order.is_x() {
+            true => (x + sold, y - proceeds),
+            false => (x - proceeds, y + sold),
+        }
+    }
+
+    // A 1_000_000 / 1_000_000 pool after a trader bought 100_000 x, at a price of 1.2345
+    const PUSHED: (u64, u64) = (900_000, 1_111_112);
+
+    #[test]
+    fn a_large_order_fills_up_to_its_price() {
+        let mut bytes = [0; RangeOrder::LEN];
+        let target = PRICE_SCALE * 101 / 100;
+        let order = order(&mut bytes, true, target, 900_000);
+
+        let (x, y) = fill(order, PUSHED);
+
+        // The curve takes about 95_000 x to fall back to 1.01, the rest keeps resting
+        let sold = 900_000 - order.amount();
+        assert!((95_000..95_100).contains(&sold), "{sold}");
+
+        // Paying the order price rather than the curve average leaves the LPs the spread
+        assert_eq!(order.filled(), order.proceeds(sold).unwrap());
+        assert!(target <= price(x, y) && price(x, y) < price(PUSHED.0, PUSHED.1));
+        assert!(x as u128 * y as u128 > PUSHED.0 as u128 * PUSHED.1 as u128);
+    }
+
+    #[test]
+    fn a_small_order_fills_whole_at_its_price() {
+        let mut bytes = [0; RangeOrder::LEN];
+        let order = order(&mut bytes, true, PRICE_SCALE * 101 / 100, 10_000);
+
+        fill(order, PUSHED);
+
+        assert_eq!(order.amount(), 0);
+        assert_eq!(order.filled(), 10_100);
+    }
+
+    #[test]
+    fn an_order_the_price_did_not_cross_stays_unfilled() {
+        let mut bytes = [0; RangeOrder::LEN];
+        let order = order(&mut bytes, true, PRICE_SCALE * 13 / 10, 10_000);
+
+        assert_eq!(fill(order, PUSHED), PUSHED);
+        assert_eq!(order.amount(), 10_000);
+        assert_eq!(order.filled(), 0);
+    }
+
+    #[test]
+    fn orders_at_different_prices_fill_in_turn() {
+        let targets = [115, 110, 105].map(|percent| PRICE_SCALE * percent / 100);
+        let mut reserves = PUSHED;
+
+        for target in targets {
+            let mut bytes = [0; RangeOrder::LEN];
+            let order = order(&mut bytes, true, target, 1_000_000);
+
+            let (x, y) = fill(order, reserves);
+
+            assert!(0 < order.amount() && order.amount() < 1_000_000);
+            assert!(target <= price(x, y) && price(x, y) < price(reserves.0, reserves.1));
+            assert!(x as u128 * y as u128 >= reserves.0 as u128 * reserves.1 as u128);
+            reserves = (x, y);
+        }
+
+        // An order above the price the others left is not crossed any more
+        let mut bytes = [0; RangeOrder::LEN];
+        let order = order(&mut bytes, true, targets[0], 1_000_000);
+        assert_eq!(fill(order, reserves), reserves);
+    }
+
+    proptest! {
+        #[test]
+        fn filling_never_decreases_k_nor_passes_the_order_price(
+            x in 1_000..1u64 << 40,
+            y in 1_000..1u64 << 40,
+            is_x: bool,
+            distance_bps in 1..9_000u128,
+            amount in 1..1u64 << 40,
+        ) {
+            let spot = price(x, y);
+            let target = match is_x {
+                true => spot * (10_000 - distance_bps) / 10_000,
+                false => spot * (10_000 + distance_bps) / 10_000,
+            };
+            prop_assume!(target != 0);
+
+            let mut bytes = [0; RangeOrder::LEN];
+            let order = order(&mut bytes, is_x, target, amount);
+
+            let (next_x, next_y) = fill(order, (x, y));
+            let sold = amount - order.amount();
+
+            prop_assert!(next_x as u128 * next_y as u128 >= x as u128 * y as u128);
+            prop_assert!(order.filled() <= order.proceeds(sold).unwrap());
+
+            // Rounding the price down may land it a hair past the order price
+            let next_price = price(next_x, next_y);
+            match is_x {
+                true => prop_assert!(next_price + target / 1_000_000_000 >= target),
+                false => prop_assert!(next_price <= target + target / 1_000_000_000),
+            }
+        }
+    }
+}
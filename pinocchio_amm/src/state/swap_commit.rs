@@ -0,0 +1,112 @@
+use crate::math::HASH_LEN;
+use core::mem::size_of;
+use pinocchio::{
+    account_info::{AccountInfo, Ref, RefMut},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+/// Fewest slots a commit may ask to pass between `CommitSwap` and the `RevealSwap`
+/// executing it.
+pub const MIN_REVEAL_DELAY: u64 = 2;
+
+/// Swap of `user` in `config` committed to as `hash` at `slot`, revealable once
+/// `reveal_delay` slots have passed.
+///
+/// The hash is the SHA-256 of the version 2 swap data, the keys of the accounts `Swap`
+/// always takes, in order, the keys of its remaining accounts and a salt. The remaining
+/// keys are folded into one hash, starting from zeroes and hashing the running hash with
+/// each key in order.
+#[repr(C)]
+pub struct SwapCommit {
+    config: Pubkey,
+    user: Pubkey,
+    hash: [u8; HASH_LEN],
+    slot: [u8; 8],
+    reveal_delay: [u8; 8],
+    bump: [u8; 1],
+}
+
+//Reading helpers
+impl SwapCommit {
+    pub const LEN: usize =
+        size_of::<Pubkey>() * 2 + HASH_LEN + size_of::<u64>() * 2 + size_of::<u8>();
+
+    #[inline(always)]
+    pub fn load(account_info: &AccountInfo) -> Result<Ref<'_, Self>, ProgramError> {
+        if account_info.data_len() != Self::LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        if account_info.owner().ne(&crate::ID) {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+        Ok(Ref::map(account_info.try_borrow_data()?, |data| unsafe {
+            &*(data.as_ptr() as *const Self)
+        }))
+    }
+
+    #[inline(always)]
+    pub fn config(&self) -> &Pubkey {
+        &self.config
+    }
+
+    #[inline(always)]
+    pub fn user(&self) -> &Pubkey {
+        &self.user
+    }
+
+    #[inline(always)]
+    pub fn hash(&self) -> &[u8; HASH_LEN] {
+        &self.hash
+    }
+
+    #[inline(always)]
+    pub fn slot(&self) -> u64 {
+        u64::from_le_bytes(self.slot)
+    }
+
+    #[inline(always)]
+    pub fn reveal_delay(&self) -> u64 {
+        u64::from_le_bytes(self.reveal_delay)
+    }
+
+    #[inline(always)]
+    pub fn bump(&self) -> [u8; 1] {
+        self.bump
+    }
+}
+
+//Writing helpers
+impl SwapCommit {
+    #[inline(always)]
+    pub fn load_mut(account_info: &AccountInfo) -> Result<RefMut<'_, Self>, ProgramError> {
+        if account_info.data_len() != Self::LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        if account_info.owner().ne(&crate::ID) {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+        Ok(RefMut::map(
+            account_info.try_borrow_mut_data()?,
+            |data| unsafe { &mut *(data.as_mut_ptr() as *mut Self) },
+        ))
+    }
+
+    #[inline(always)]
+    pub fn set_inner(
+        &mut self,
+        config: Pubkey,
+        user: Pubkey,
+        hash: [u8; HASH_LEN],
+        slot: u64,
+        reveal_delay: u64,
+        bump: [u8; 1],
+    ) {
+        self.config = config;
+        self.user = user;
+        self.hash = hash;
+        self.slot = slot.to_le_bytes();
+        self.reveal_delay = reveal_delay.to_le_bytes();
+        self.bump = bump;
+    }
+}
@@ -0,0 +1,51 @@
+//! Stand-in price feed for the LiteSVM tests.
+//!
+//! Its only instruction publishes a `u128` price, stamped with the current unix
+//! timestamp, into a feed account in the `PriceFeed` layout `Swap` reads. The feed is
+//! created by the caller with `PriceFeed::LEN` bytes and owned by this program. Anyone
+//! can publish, the program only stands in for a real feed.
+//!
+//! ### Accounts:
+//!   0. `[WRITE]` Feed
+
+#![allow(unexpected_cfgs)]
+
+use pinocchio::{
+    account_info::AccountInfo,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvars::{clock::Clock, Sysvar},
+    ProgramResult,
+};
+use pinocchio_amm::PriceFeed;
+
+pinocchio::entrypoint!(process_instruction);
+
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let [feed] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    let price: [u8; 16] = instruction_data
+        .try_into()
+        .map_err(|_| ProgramError::InvalidInstructionData)?;
+
+    if feed.owner().ne(program_id) {
+        return Err(ProgramError::InvalidAccountOwner);
+    }
+
+    let mut data = feed.try_borrow_mut_data()?;
+    if data.len().ne(&PriceFeed::LEN) {
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    data[0..8].copy_from_slice(&PriceFeed::MAGIC);
+    data[8..24].copy_from_slice(&price);
+    data[24..32].copy_from_slice(&Clock::get()?.unix_timestamp.to_le_bytes());
+
+    Ok(())
+}
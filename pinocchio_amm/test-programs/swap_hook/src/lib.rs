@@ -0,0 +1,66 @@
+//! Stand-in swap hook for the LiteSVM tests.
+//!
+//! It checks the call `Swap` makes against the layout `SwapHook` documents: the
+//! discriminator and the `SwapEvent` of the trade, the config and user accounts the
+//! event names, and the hook authority of the pool signing. It hands the event back as
+//! return data, and fails swaps of `REJECTED_AMOUNT` to stand in for a broken hook.
+//!
+//! ### Accounts:
+//!   0. `[SIGNER]` Hook authority of the pool
+//!   1. `[]` Pool config
+//!   2. `[]` User
+
+#![allow(unexpected_cfgs)]
+
+use pinocchio::{
+    account_info::AccountInfo,
+    cpi::set_return_data,
+    program_error::ProgramError,
+    pubkey::{find_program_address, Pubkey},
+    ProgramResult,
+};
+use pinocchio_amm::{SwapEvent, SwapHook};
+
+/// Input amount of the swaps the hook fails.
+pub const REJECTED_AMOUNT: u64 = 4_242;
+
+pinocchio::entrypoint!(process_instruction);
+
+pub fn process_instruction(
+    _program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let [authority, config, user] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    if instruction_data.len().ne(&(8 + SwapEvent::LEN)) {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+
+    let (discriminator, event) = instruction_data.split_at(8);
+    if discriminator.ne(&SwapHook::DISCRIMINATOR) {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+    if event[0..32].ne(config.key()) || event[32..64].ne(user.key()) {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+
+    let (expected, _) = find_program_address(&[b"hook", config.key()], &pinocchio_amm::ID);
+    if authority.key().ne(&expected) {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    if !authority.is_signer() {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let amount_in = u64::from_le_bytes(event[65..73].try_into().unwrap());
+    if amount_in == REJECTED_AMOUNT {
+        return Err(ProgramError::Custom(0));
+    }
+
+    set_return_data(event);
+
+    Ok(())
+}
@@ -0,0 +1,153 @@
+//! `InitializeAllowlist`, `UpdateAllowlist` and the allowlist check of `Swap`.
+
+use super::*;
+use pinocchio_amm::{
+    state::{AllowlistMode, MAX_ALLOWLIST_KEYS},
+    AllowlistAction, InitializeAllowlist, UpdateAllowlist,
+};
+use solana_sdk::hash::hashv;
+
+impl Env {
+    fn allowlist(&self) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[b"allowlist", self.config.as_ref()], &program_id())
+    }
+
+    /// A funded pool restricted to an allowlist in `mode`.
+    fn permissioned(mode: AllowlistMode) -> Self {
+        let mut env = Self::funded();
+        let (allowlist, bump) = env.allowlist();
+
+        let initialize_allowlist = Instruction::new_with_bytes(
+            program_id(),
+            &[*InitializeAllowlist::DISCRIMINATOR, mode as u8, bump],
+            vec![
+                AccountMeta::new(env.authority.pubkey(), true),
+                AccountMeta::new(env.config, false),
+                AccountMeta::new(allowlist, false),
+                AccountMeta::new_readonly(solana_sdk::system_program::ID, false),
+            ],
+        );
+        env.send_as_authority("InitializeAllowlist", initialize_allowlist)
+            .unwrap();
+
+        env
+    }
+
+    fn update_allowlist(&self, action: AllowlistAction, value: &[u8; 32]) -> Instruction {
+        Instruction::new_with_bytes(
+            program_id(),
+            &[&[*UpdateAllowlist::DISCRIMINATOR, action as u8], &value[..]].concat(),
+            vec![
+                AccountMeta::new_readonly(self.authority.pubkey(), true),
+                AccountMeta::new_readonly(self.config, false),
+                AccountMeta::new(self.allowlist().0, false),
+            ],
+        )
+    }
+
+    /// A swap selling 1_000 of x, passing the allowlist and `proof`.
+    fn allowlisted_swap(&self, proof: &[u8]) -> Instruction {
+        let mut swap = self.swap(true, 1_000, 1, Deadline::Slot(u64::MAX));
+        swap.data.extend_from_slice(proof);
+        swap.accounts
+            .push(AccountMeta::new_readonly(self.allowlist().0, false));
+        swap
+    }
+}
+
+#[test]
+fn swap_requires_a_listed_key() {
+    let mut env = Env::permissioned(AllowlistMode::Keys);
+
+    assert_eq!(
+        env.send_as_user("Swap", env.allowlisted_swap(&[])),
+        Err(custom(AmmError::NotAllowlisted))
+    );
+
+    let user = env.user.pubkey().to_bytes();
+    env.send_as_authority(
+        "UpdateAllowlist",
+        env.update_allowlist(AllowlistAction::AddKey, &user),
+    )
+    .unwrap();
+    env.send_as_user("Swap", env.allowlisted_swap(&[])).unwrap();
+
+    env.send_as_authority(
+        "UpdateAllowlist",
+        env.update_allowlist(AllowlistAction::RemoveKey, &user),
+    )
+    .unwrap();
+    assert_eq!(
+        env.send_as_user("Swap", env.allowlisted_swap(&[])),
+        Err(custom(AmmError::NotAllowlisted))
+    );
+}
+
+#[test]
+fn swap_on_a_permissioned_pool_requires_the_allowlist() {
+    let mut env = Env::permissioned(AllowlistMode::Keys);
+
+    assert_eq!(
+        env.send_as_user("Swap", env.swap(true, 1_000, 1, Deadline::Slot(u64::MAX))),
+        Err(InstructionError::NotEnoughAccountKeys)
+    );
+}
+
+#[test]
+fn swap_proves_membership_of_a_merkle_allowlist() {
+    let mut env = Env::permissioned(AllowlistMode::MerkleRoot);
+
+    // Two leaves, each the proof of the other, hashed in ascending order
+    let leaf = hashv(&[env.user.pubkey().as_ref()]).to_bytes();
+    let sibling = hashv(&[Pubkey::new_unique().as_ref()]).to_bytes();
+    let root = hashv(&[leaf.min(sibling).as_ref(), leaf.max(sibling).as_ref()]).to_bytes();
+    env.send_as_authority(
+        "UpdateAllowlist",
+        env.update_allowlist(AllowlistAction::SetRoot, &root),
+    )
+    .unwrap();
+
+    env.send_as_user("Swap", env.allowlisted_swap(&sibling))
+        .unwrap();
+    assert_eq!(
+        env.send_as_user("Swap", env.allowlisted_swap(&leaf)),
+        Err(custom(AmmError::NotAllowlisted))
+    );
+}
+
+#[test]
+fn update_allowlist_requires_the_authority() {
+    let mut env = Env::permissioned(AllowlistMode::Keys);
+
+    let mut update_allowlist =
+        env.update_allowlist(AllowlistAction::AddKey, &env.user.pubkey().to_bytes());
+    update_allowlist.accounts[0].pubkey = env.user.pubkey();
+
+    assert_eq!(
+        env.send_as_user("UpdateAllowlist", update_allowlist),
+        Err(InstructionError::IncorrectAuthority)
+    );
+}
+
+#[test]
+fn update_allowlist_holds_at_most_the_key_limit() {
+    let mut env = Env::permissioned(AllowlistMode::Keys);
+
+    for _ in 0..MAX_ALLOWLIST_KEYS {
+        let key = Pubkey::new_unique().to_bytes();
+        env.send_as_authority(
+            "UpdateAllowlist",
+            env.update_allowlist(AllowlistAction::AddKey, &key),
+        )
+        .unwrap();
+    }
+
+    let key = Pubkey::new_unique().to_bytes();
+    assert_eq!(
+        env.send_as_authority(
+            "UpdateAllowlist",
+            env.update_allowlist(AllowlistAction::AddKey, &key)
+        ),
+        Err(InstructionError::AccountDataTooSmall)
+    );
+}
@@ -0,0 +1,257 @@
+//! `SetBatchWindow`, `PlaceBatchOrder`, `SettleBatch` and `ClaimBatchOrder`.
+
+use super::*;
+use pinocchio_amm::{
+    state::{BATCH_GRACE_SLOTS, BATCH_ORDER_DEPOSIT},
+    ClaimBatchOrder, PlaceBatchOrder, SetBatchWindow, SetWeights, SettleBatch,
+};
+
+const BATCH_WINDOW: u64 = 10;
+
+impl Env {
+    /// A funded pool in batch mode, whose first batch ends at `BATCH_WINDOW`.
+    fn batched() -> Self {
+        let mut env = Self::funded();
+        env.enable_batches();
+        env
+    }
+
+    /// Switch the pool to batch mode and create the token accounts of the first batch.
+    fn enable_batches(&mut self) {
+        self.send_as_authority(
+            "SetBatchWindow",
+            self.authority_instruction(*SetBatchWindow::DISCRIMINATOR, &BATCH_WINDOW.to_le_bytes()),
+        )
+        .unwrap();
+
+        let batch = self.batch().0;
+        for mint in [self.mint_x, self.mint_y] {
+            self.create_ata(&batch, &mint);
+        }
+    }
+
+    /// The batch of the first epoch, and its bump.
+    fn batch(&self) -> (Pubkey, u8) {
+        Pubkey::find_program_address(
+            &[b"batch", self.config.as_ref(), &0u64.to_le_bytes()],
+            &program_id(),
+        )
+    }
+
+    fn place_batch_order(&self, is_x: bool, amount: u64, min_out: u64) -> Instruction {
+        let (user, (batch, bump)) = (self.user.pubkey(), self.batch());
+        let mint = match is_x {
+            true => self.mint_x,
+            false => self.mint_y,
+        };
+
+        let mut data = vec![*PlaceBatchOrder::DISCRIMINATOR, is_x as u8];
+        data.extend_from_slice(&amount.to_le_bytes());
+        data.extend_from_slice(&min_out.to_le_bytes());
+        data.push(bump);
+
+        Instruction::new_with_bytes(
+            program_id(),
+            &data,
+            vec![
+                AccountMeta::new(user, true),
+                AccountMeta::new(self.config, false),
+                AccountMeta::new(batch, false),
+                AccountMeta::new(get_associated_token_address(&user, &mint), false),
+                AccountMeta::new(get_associated_token_address(&batch, &mint), false),
+                AccountMeta::new_readonly(solana_sdk::system_program::ID, false),
+                AccountMeta::new_readonly(spl_token::ID, false),
+            ],
+        )
+    }
+
+    fn settle_batch(&self) -> Instruction {
+        let batch = self.batch().0;
+
+        Instruction::new_with_bytes(
+            program_id(),
+            &[*SettleBatch::DISCRIMINATOR],
+            vec![
+                AccountMeta::new(self.config, false),
+                AccountMeta::new(batch, false),
+                AccountMeta::new(get_associated_token_address(&batch, &self.mint_x), false),
+                AccountMeta::new(get_associated_token_address(&batch, &self.mint_y), false),
+                AccountMeta::new(self.vault_x, false),
+                AccountMeta::new(self.vault_y, false),
+                AccountMeta::new_readonly(spl_token::ID, false),
+            ],
+        )
+    }
+
+    /// Claim the order at `index`, paid in x when `is_x`.
+    fn claim_batch_order(&self, index: u8, is_x: bool) -> Instruction {
+        let (user, batch) = (self.user.pubkey(), self.batch().0);
+        let mint = match is_x {
+            true => self.mint_x,
+            false => self.mint_y,
+        };
+
+        Instruction::new_with_bytes(
+            program_id(),
+            &[*ClaimBatchOrder::DISCRIMINATOR, index],
+            vec![
+                AccountMeta::new(user, true),
+                AccountMeta::new(self.config, false),
+                AccountMeta::new(batch, false),
+                AccountMeta::new(get_associated_token_address(&batch, &mint), false),
+                AccountMeta::new(get_associated_token_address(&user, &mint), false),
+                AccountMeta::new_readonly(spl_token::ID, false),
+                AccountMeta::new(user, false),
+            ],
+        )
+    }
+}
+
+#[test]
+fn place_batch_order_locks_the_order_and_its_deposit() {
+    let mut env = Env::batched();
+    let x = env.user_balance(&env.mint_x);
+
+    env.send_as_user("PlaceBatchOrder", env.place_batch_order(true, 1_000, 0))
+        .unwrap();
+    let batch = env.lamports(&env.batch().0);
+    env.send_as_user("PlaceBatchOrder", env.place_batch_order(false, 4_000, 0))
+        .unwrap();
+
+    assert_eq!(env.user_balance(&env.mint_x), x - 1_000);
+    assert_eq!(env.lamports(&env.batch().0), batch + BATCH_ORDER_DEPOSIT);
+    assert_eq!(env.pool(|pool| pool.open_batches()), 1);
+}
+
+#[test]
+fn place_batch_order_takes_two_orders_per_user() {
+    let mut env = Env::batched();
+
+    for is_x in [true, false] {
+        env.send_as_user("PlaceBatchOrder", env.place_batch_order(is_x, 1_000, 0))
+            .unwrap();
+    }
+
+    assert_eq!(
+        env.send_as_user("PlaceBatchOrder", env.place_batch_order(true, 1_000, 0)),
+        Err(InstructionError::InvalidArgument)
+    );
+}
+
+#[test]
+fn the_last_claim_closes_a_settled_batch() {
+    let mut env = Env::batched();
+    let lamports = env.lamports(&env.user.pubkey());
+
+    env.send_as_user("PlaceBatchOrder", env.place_batch_order(true, 1_000, 0))
+        .unwrap();
+    env.send_as_user("PlaceBatchOrder", env.place_batch_order(false, 4_000, 0))
+        .unwrap();
+
+    assert_eq!(
+        env.send_as_user("SettleBatch", env.settle_batch()),
+        Err(InstructionError::InvalidAccountData)
+    );
+    env.warp_past(BATCH_WINDOW);
+    env.send_as_user("SettleBatch", env.settle_batch()).unwrap();
+    assert_eq!(env.pool(|pool| pool.open_batches()), 0);
+
+    env.send_as_user("ClaimBatchOrder", env.claim_batch_order(0, false))
+        .unwrap();
+    assert_eq!(
+        env.send_as_user("ClaimBatchOrder", env.claim_batch_order(0, false)),
+        Err(InstructionError::InvalidAccountData)
+    );
+    env.send_as_user("ClaimBatchOrder", env.claim_batch_order(1, true))
+        .unwrap();
+
+    // Only the transaction fees are gone
+    assert_eq!(env.lamports(&env.batch().0), 0);
+    assert!(lamports - env.lamports(&env.user.pubkey()) < 100_000);
+}
+
+#[test]
+fn an_unsettled_batch_is_cancelled_after_the_grace_period() {
+    let mut env = Env::batched();
+    let x = env.user_balance(&env.mint_x);
+
+    env.send_as_user("PlaceBatchOrder", env.place_batch_order(true, 1_000, 0))
+        .unwrap();
+
+    env.warp_past(BATCH_WINDOW + BATCH_GRACE_SLOTS - 2);
+    assert_eq!(
+        env.send_as_user("ClaimBatchOrder", env.claim_batch_order(0, true)),
+        Err(InstructionError::InvalidAccountData)
+    );
+
+    env.warp_past(BATCH_WINDOW + BATCH_GRACE_SLOTS - 1);
+    env.send_as_user("ClaimBatchOrder", env.claim_batch_order(0, true))
+        .unwrap();
+
+    assert_eq!(env.user_balance(&env.mint_x), x);
+    assert_eq!(env.lamports(&env.batch().0), 0);
+    assert_eq!(env.pool(|pool| pool.open_batches()), 0);
+}
+
+#[test]
+fn an_open_batch_is_cancelled_once_the_pool_is_paused() {
+    let mut env = Env::batched();
+    let y = env.user_balance(&env.mint_y);
+
+    env.send_as_user("PlaceBatchOrder", env.place_batch_order(false, 4_000, 0))
+        .unwrap();
+    env.send_as_authority("SetState", env.set_state(AmmState::Disabled))
+        .unwrap();
+
+    env.send_as_user("ClaimBatchOrder", env.claim_batch_order(0, false))
+        .unwrap();
+
+    assert_eq!(env.user_balance(&env.mint_y), y);
+}
+
+#[test]
+fn claim_batch_order_pays_the_rent_back_to_the_payer_only() {
+    let mut env = Env::batched();
+
+    env.send_as_user("PlaceBatchOrder", env.place_batch_order(true, 1_000, 0))
+        .unwrap();
+    env.warp_past(BATCH_WINDOW);
+    env.send_as_user("SettleBatch", env.settle_batch()).unwrap();
+
+    let mut claim = env.claim_batch_order(0, false);
+    claim.accounts[6].pubkey = env.authority.pubkey();
+
+    assert_eq!(
+        env.send_as_user("ClaimBatchOrder", claim),
+        Err(InstructionError::InvalidAccountData)
+    );
+}
+
+#[test]
+fn set_weights_waits_for_open_batches() {
+    // The curve only changes before the first deposit
+    let mut env = Env::initialized();
+    env.enable_batches();
+
+    env.send_as_user("PlaceBatchOrder", env.place_batch_order(true, 1_000, 0))
+        .unwrap();
+    env.send_as_authority(
+        "SetBatchWindow",
+        env.authority_instruction(*SetBatchWindow::DISCRIMINATOR, &0u64.to_le_bytes()),
+    )
+    .unwrap();
+
+    let mut data = 8_000u16.to_le_bytes().to_vec();
+    data.extend_from_slice(&8_000u16.to_le_bytes());
+    data.extend_from_slice(&0i64.to_le_bytes());
+    data.extend_from_slice(&1i64.to_le_bytes());
+    let mut set_weights = env.authority_instruction(*SetWeights::DISCRIMINATOR, &data);
+    set_weights
+        .accounts
+        .push(AccountMeta::new_readonly(env.mint_lp, false));
+
+    assert_eq!(
+        env.send_as_authority("SetWeights", set_weights),
+        Err(InstructionError::InvalidAccountData)
+    );
+}
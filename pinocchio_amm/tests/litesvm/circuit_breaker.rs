@@ -0,0 +1,97 @@
+//! `SetCircuitBreaker` and the per-slot price move limit of `Swap`.
+
+use super::*;
+use pinocchio_amm::SetCircuitBreaker;
+
+const MAX_SLOT_MOVE_BPS: u16 = 100;
+
+impl Env {
+    fn set_circuit_breaker(&self, max_slot_move_bps: u16) -> Instruction {
+        self.authority_instruction(
+            *SetCircuitBreaker::DISCRIMINATOR,
+            &max_slot_move_bps.to_le_bytes(),
+        )
+    }
+
+    /// A funded pool whose price may move by `MAX_SLOT_MOVE_BPS` within a slot.
+    fn with_circuit_breaker() -> Self {
+        let mut env = Self::funded();
+
+        env.send_as_authority(
+            "SetCircuitBreaker",
+            env.set_circuit_breaker(MAX_SLOT_MOVE_BPS),
+        )
+        .unwrap();
+
+        env
+    }
+}
+
+#[test]
+fn set_circuit_breaker_requires_the_authority() {
+    let mut env = Env::funded();
+
+    let mut set_circuit_breaker = env.set_circuit_breaker(MAX_SLOT_MOVE_BPS);
+    set_circuit_breaker.accounts[0].pubkey = env.user.pubkey();
+
+    assert_eq!(
+        env.send_as_user("SetCircuitBreaker", set_circuit_breaker),
+        Err(InstructionError::IncorrectAuthority)
+    );
+}
+
+#[test]
+fn small_moves_stay_under_the_breaker() {
+    let mut env = Env::with_circuit_breaker();
+
+    // 0.1% of the reserves moves the price by about 0.2%
+    env.send_as_user("Swap", env.swap(true, 1_000, 1, Deadline::Slot(u64::MAX)))
+        .unwrap();
+
+    assert!(env.pool(|pool| pool.slot_move_bps()) <= 21);
+}
+
+#[test]
+fn a_large_move_trips_the_breaker() {
+    let mut env = Env::with_circuit_breaker();
+
+    // 1% of the reserves moves the price by about 2%
+    assert_eq!(
+        env.send_as_user("Swap", env.swap(true, 10_000, 1, Deadline::Slot(u64::MAX))),
+        Err(custom(AmmError::SlotMoveExceeded))
+    );
+}
+
+#[test]
+fn split_moves_trip_the_breaker_within_a_slot() {
+    let mut env = Env::with_circuit_breaker();
+
+    // Ten trades of about 0.2% each cross the 1% limit after the fifth, whatever their size
+    let results: Vec<_> = (0..10)
+        .map(|_| env.send_as_user("Swap", env.swap(true, 1_000, 1, Deadline::Slot(u64::MAX))))
+        .collect();
+
+    assert!(results[0].is_ok());
+    assert_eq!(
+        results.last().unwrap(),
+        &Err(custom(AmmError::SlotMoveExceeded))
+    );
+}
+
+#[test]
+fn the_breaker_resets_with_the_slot() {
+    let mut env = Env::with_circuit_breaker();
+
+    for _ in 0..4 {
+        env.send_as_user("Swap", env.swap(true, 1_000, 1, Deadline::Slot(u64::MAX)))
+            .unwrap();
+    }
+
+    let slot = env.svm.get_sysvar::<Clock>().slot;
+    env.warp_past(slot);
+
+    for _ in 0..4 {
+        env.send_as_user("Swap", env.swap(true, 1_000, 1, Deadline::Slot(u64::MAX)))
+            .unwrap();
+    }
+}
@@ -0,0 +1,147 @@
+//! `CommitSwap` and `RevealSwap`.
+
+use super::*;
+use pinocchio_amm::{state::MIN_REVEAL_DELAY, CommitSwap, RevealSwap};
+use solana_sdk::hash::hashv;
+
+const SALT: [u8; 32] = [7; 32];
+
+impl Env {
+    /// The commit of the user in the pool, and its bump.
+    fn swap_commit(&self) -> (Pubkey, u8) {
+        Pubkey::find_program_address(
+            &[b"commit", self.config.as_ref(), self.user.pubkey().as_ref()],
+            &program_id(),
+        )
+    }
+
+    /// Hash committing to `swap` with `SALT`, as `SwapCommit` documents it.
+    fn commitment(swap: &Instruction) -> [u8; 32] {
+        let (fixed, remaining) = swap.accounts.split_at(7);
+        let remaining = remaining.iter().fold([0; 32], |hash, meta| {
+            hashv(&[&hash, meta.pubkey.as_ref()]).to_bytes()
+        });
+
+        let mut values = vec![&swap.data[1..]];
+        values.extend(fixed.iter().map(|meta| meta.pubkey.as_ref()));
+        values.extend([&remaining[..], &SALT[..]]);
+        hashv(&values).to_bytes()
+    }
+
+    fn commit_swap(&self, swap: &Instruction) -> Instruction {
+        let (commit, bump) = self.swap_commit();
+
+        let mut data = vec![*CommitSwap::DISCRIMINATOR];
+        data.extend_from_slice(&Self::commitment(swap));
+        data.extend_from_slice(&MIN_REVEAL_DELAY.to_le_bytes());
+        data.push(bump);
+
+        Instruction::new_with_bytes(
+            program_id(),
+            &data,
+            vec![
+                AccountMeta::new(self.user.pubkey(), true),
+                AccountMeta::new_readonly(self.config, false),
+                AccountMeta::new(commit, false),
+                AccountMeta::new_readonly(solana_sdk::system_program::ID, false),
+            ],
+        )
+    }
+
+    fn reveal_swap(&self, swap: Instruction) -> Instruction {
+        let mut data = vec![*RevealSwap::DISCRIMINATOR];
+        data.extend_from_slice(&SALT);
+        data.extend_from_slice(&swap.data[1..]);
+
+        let mut accounts = vec![AccountMeta::new(self.swap_commit().0, false)];
+        accounts.extend(swap.accounts);
+
+        Instruction::new_with_bytes(program_id(), &data, accounts)
+    }
+
+    fn warp_reveal_delay(&mut self) {
+        let slot = self.svm.get_sysvar::<Clock>().slot;
+        self.warp_past(slot + MIN_REVEAL_DELAY);
+    }
+}
+
+#[test]
+fn reveal_swap_executes_the_commit_and_closes_it() {
+    let mut env = Env::funded();
+    let swap = env.swap(true, 1_000, 1, Deadline::Slot(u64::MAX));
+    let x = env.user_balance(&env.mint_x);
+
+    env.send_as_user("CommitSwap", env.commit_swap(&swap))
+        .unwrap();
+    env.warp_reveal_delay();
+    env.send_as_user("RevealSwap", env.reveal_swap(swap))
+        .unwrap();
+
+    assert_eq!(env.user_balance(&env.mint_x), x - 1_000);
+    assert!(env
+        .svm
+        .get_account(&env.swap_commit().0)
+        .is_none_or(|account| account.lamports == 0));
+}
+
+#[test]
+fn reveal_swap_waits_for_the_reveal_delay() {
+    let mut env = Env::funded();
+    let swap = env.swap(true, 1_000, 1, Deadline::Slot(u64::MAX));
+
+    env.send_as_user("CommitSwap", env.commit_swap(&swap))
+        .unwrap();
+
+    assert_eq!(
+        env.send_as_user("RevealSwap", env.reveal_swap(swap)),
+        Err(InstructionError::InvalidArgument)
+    );
+}
+
+#[test]
+fn reveal_swap_rejects_other_parameters() {
+    let mut env = Env::funded();
+    let swap = env.swap(true, 1_000, 1, Deadline::Slot(u64::MAX));
+
+    env.send_as_user("CommitSwap", env.commit_swap(&swap))
+        .unwrap();
+    env.warp_reveal_delay();
+
+    assert_eq!(
+        env.send_as_user(
+            "RevealSwap",
+            env.reveal_swap(env.swap(true, 2_000, 1, Deadline::Slot(u64::MAX)))
+        ),
+        Err(InstructionError::InvalidInstructionData)
+    );
+}
+
+#[test]
+fn reveal_swap_rejects_another_referrer() {
+    let mut env = Env::funded();
+    let (referrer, other) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let mint_x = env.mint_x;
+    env.create_ata(&referrer, &mint_x);
+    env.create_ata(&other, &mint_x);
+    env.send_as_authority("SetReferralFee", env.set_referral_fee(5_000))
+        .unwrap();
+
+    let swap = env.referred_swap(10_000, &referrer, 5_000);
+    env.send_as_user("CommitSwap", env.commit_swap(&swap))
+        .unwrap();
+    env.warp_reveal_delay();
+
+    // Whoever reveals cannot redirect the referral share
+    assert_eq!(
+        env.send_as_user(
+            "RevealSwap",
+            env.reveal_swap(env.referred_swap(10_000, &other, 5_000))
+        ),
+        Err(InstructionError::InvalidInstructionData)
+    );
+
+    env.send_as_user("RevealSwap", env.reveal_swap(swap))
+        .unwrap();
+    assert_eq!(env.balance(&referrer, &mint_x), 15);
+    assert_eq!(env.balance(&other, &mint_x), 0);
+}
@@ -0,0 +1,108 @@
+//! `SetDynamicFee`, and the fee `Swap` charges and `GetPoolState` reports with it.
+
+use super::*;
+use pinocchio_amm::SetDynamicFee;
+
+const MAX_FEE: u16 = 100;
+const VOLATILITY_FEE_FACTOR: u16 = 5_000;
+const HALF_LIFE: i64 = 60;
+
+impl Env {
+    fn set_dynamic_fee(&self, max_fee: u16, volatility_half_life: i64) -> Instruction {
+        let mut data = max_fee.to_le_bytes().to_vec();
+        data.extend_from_slice(&VOLATILITY_FEE_FACTOR.to_le_bytes());
+        data.extend_from_slice(&volatility_half_life.to_le_bytes());
+
+        self.authority_instruction(*SetDynamicFee::DISCRIMINATOR, &data)
+    }
+
+    /// A funded pool charging up to `MAX_FEE` as its price moves.
+    fn with_dynamic_fee() -> Self {
+        let mut env = Self::funded();
+
+        env.send_as_authority("SetDynamicFee", env.set_dynamic_fee(MAX_FEE, HALF_LIFE))
+            .unwrap();
+
+        env
+    }
+
+    /// Fee the pool reports through `GetPoolState`.
+    fn reported_fee(&mut self) -> u16 {
+        // `fee_bps` follows the state and the reserves and LP supply
+        u16::from_le_bytes(self.pool_state()[25..27].try_into().unwrap())
+    }
+
+    /// Fee the pool took on a swap selling `amount` of x.
+    fn swap_fee(&mut self, amount: u64) -> u128 {
+        let fees = self.pool(|pool| pool.cumulative_fees_x());
+
+        self.send_as_user("Swap", self.swap(true, amount, 1, Deadline::Slot(u64::MAX)))
+            .unwrap();
+
+        self.pool(|pool| pool.cumulative_fees_x()) - fees
+    }
+}
+
+#[test]
+fn set_dynamic_fee_requires_the_authority() {
+    let mut env = Env::funded();
+
+    let mut set_dynamic_fee = env.set_dynamic_fee(MAX_FEE, HALF_LIFE);
+    set_dynamic_fee.accounts[0].pubkey = env.user.pubkey();
+
+    assert_eq!(
+        env.send_as_user("SetDynamicFee", set_dynamic_fee),
+        Err(InstructionError::IncorrectAuthority)
+    );
+}
+
+#[test]
+fn set_dynamic_fee_rejects_a_cap_below_the_base_fee() {
+    let mut env = Env::funded();
+
+    assert_eq!(
+        env.send_as_authority("SetDynamicFee", env.set_dynamic_fee(FEE - 1, HALF_LIFE)),
+        Err(InstructionError::InvalidAccountData)
+    );
+    assert_eq!(
+        env.send_as_authority("SetDynamicFee", env.set_dynamic_fee(MAX_FEE, 0)),
+        Err(InstructionError::InvalidAccountData)
+    );
+}
+
+#[test]
+fn a_calm_pool_charges_the_base_fee() {
+    let mut env = Env::with_dynamic_fee();
+
+    assert_eq!(env.reported_fee(), FEE);
+    assert_eq!(env.swap_fee(10_000), 30);
+}
+
+#[test]
+fn price_moves_raise_the_fee_up_to_the_cap() {
+    let mut env = Env::with_dynamic_fee();
+
+    // 1% of the reserves moves the price by about 2%, half of which is 100 bps of fee
+    env.swap_fee(10_000);
+
+    assert!(env.pool(|pool| pool.volatility()) >= 140);
+    assert_eq!(env.reported_fee(), MAX_FEE);
+    assert_eq!(env.swap_fee(1_000), 10);
+}
+
+#[test]
+fn get_pool_state_reports_the_fee_decayed_to_now() {
+    let mut env = Env::with_dynamic_fee();
+    env.swap_fee(10_000);
+    let volatility = env.pool(|pool| pool.volatility());
+
+    env.warp_time(HALF_LIFE * 2);
+
+    // The view leaves the stored volatility as it is
+    let fee = FEE + (volatility / 4 * VOLATILITY_FEE_FACTOR as u64 / 10_000) as u16;
+    assert_eq!(env.reported_fee(), fee.min(MAX_FEE));
+    assert_eq!(env.pool(|pool| pool.volatility()), volatility);
+
+    env.warp_time(HALF_LIFE * 64);
+    assert_eq!(env.reported_fee(), FEE);
+}
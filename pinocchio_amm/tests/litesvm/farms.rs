@@ -0,0 +1,288 @@
+//! `InitializeFarm`, `Stake`, `Unstake` and `ClaimRewards`, and the locked LP staking of
+//! `StakeLocked` and `UnstakeLocked`.
+
+use super::*;
+use pinocchio_amm::{ClaimRewards, InitializeFarm, Stake, StakeLocked, Unstake, UnstakeLocked};
+
+const REWARD_RATE: u64 = 10;
+const FARM_DURATION: i64 = 1_000;
+const STAKED: u64 = 500_000;
+
+impl Env {
+    /// The farm of the pool at seed 0, and its bump.
+    fn farm(&self) -> (Pubkey, u8) {
+        Pubkey::find_program_address(
+            &[b"farm", self.config.as_ref(), &0u64.to_le_bytes()],
+            &program_id(),
+        )
+    }
+
+    /// Stake account of `owner` in the farm, and its bump.
+    fn farm_stake(&self, owner: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(
+            &[b"stake", self.farm().0.as_ref(), owner.as_ref()],
+            &program_id(),
+        )
+    }
+
+    fn initialize_farm(&self, authority: &Pubkey, reward_mint: &Pubkey) -> Instruction {
+        let (farm, bump) = self.farm();
+
+        let mut data = vec![*InitializeFarm::DISCRIMINATOR];
+        data.extend_from_slice(&0u64.to_le_bytes());
+        data.extend_from_slice(&REWARD_RATE.to_le_bytes());
+        data.extend_from_slice(&(self.now() + FARM_DURATION).to_le_bytes());
+        data.push(bump);
+
+        Instruction::new_with_bytes(
+            program_id(),
+            &data,
+            vec![
+                AccountMeta::new(*authority, true),
+                AccountMeta::new_readonly(self.config, false),
+                AccountMeta::new_readonly(self.mint_lp, false),
+                AccountMeta::new_readonly(*reward_mint, false),
+                AccountMeta::new(farm, false),
+                AccountMeta::new(get_associated_token_address(authority, reward_mint), false),
+                AccountMeta::new(get_associated_token_address(&farm, reward_mint), false),
+                AccountMeta::new_readonly(solana_sdk::system_program::ID, false),
+                AccountMeta::new_readonly(spl_token::ID, false),
+            ],
+        )
+    }
+
+    /// A funded pool with a farm paying `REWARD_RATE` of the returned reward mint.
+    fn farmed() -> (Self, Pubkey) {
+        let mut env = Self::funded();
+        let reward_mint = create_mint(&mut env.svm, &env.authority);
+
+        let (authority, user, farm) = (env.authority.pubkey(), env.user.pubkey(), env.farm().0);
+        for (owner, mint) in [
+            (authority, reward_mint),
+            (user, reward_mint),
+            (farm, reward_mint),
+            (farm, env.mint_lp),
+        ] {
+            env.create_ata(&owner, &mint);
+        }
+        env.mint_to(&authority, &reward_mint, REWARD_RATE * FARM_DURATION as u64);
+
+        env.send_as_authority(
+            "InitializeFarm",
+            env.initialize_farm(&authority, &reward_mint),
+        )
+        .unwrap();
+
+        (env, reward_mint)
+    }
+
+    fn stake(&self, amount: u64) -> Instruction {
+        let (user, farm) = (self.user.pubkey(), self.farm().0);
+        let (user_stake, bump) = self.farm_stake(&user);
+        let mint_lp = self.mint_lp;
+
+        let mut data = vec![*Stake::DISCRIMINATOR];
+        data.extend_from_slice(&amount.to_le_bytes());
+        data.push(bump);
+
+        Instruction::new_with_bytes(
+            program_id(),
+            &data,
+            vec![
+                AccountMeta::new(user, true),
+                AccountMeta::new(farm, false),
+                AccountMeta::new(user_stake, false),
+                AccountMeta::new(get_associated_token_address(&user, &mint_lp), false),
+                AccountMeta::new(get_associated_token_address(&farm, &mint_lp), false),
+                AccountMeta::new_readonly(solana_sdk::system_program::ID, false),
+                AccountMeta::new_readonly(spl_token::ID, false),
+            ],
+        )
+    }
+
+    fn unstake(&self, reward_mint: &Pubkey, amount: u64) -> Instruction {
+        let (user, farm) = (self.user.pubkey(), self.farm().0);
+        let mint_lp = self.mint_lp;
+
+        Instruction::new_with_bytes(
+            program_id(),
+            &[&[*Unstake::DISCRIMINATOR], &amount.to_le_bytes()[..]].concat(),
+            vec![
+                AccountMeta::new(user, true),
+                AccountMeta::new(farm, false),
+                AccountMeta::new(self.farm_stake(&user).0, false),
+                AccountMeta::new(get_associated_token_address(&user, &mint_lp), false),
+                AccountMeta::new(get_associated_token_address(&farm, &mint_lp), false),
+                AccountMeta::new(get_associated_token_address(&user, reward_mint), false),
+                AccountMeta::new(get_associated_token_address(&farm, reward_mint), false),
+                AccountMeta::new_readonly(spl_token::ID, false),
+            ],
+        )
+    }
+
+    /// Claim the rewards of the user's stake, paid to the account of `owner`.
+    fn claim_rewards(&self, owner: &Pubkey, reward_mint: &Pubkey) -> Instruction {
+        let farm = self.farm().0;
+        let user_stake = self.farm_stake(&self.user.pubkey()).0;
+
+        Instruction::new_with_bytes(
+            program_id(),
+            &[*ClaimRewards::DISCRIMINATOR],
+            vec![
+                AccountMeta::new(*owner, true),
+                AccountMeta::new(farm, false),
+                AccountMeta::new(user_stake, false),
+                AccountMeta::new(get_associated_token_address(owner, reward_mint), false),
+                AccountMeta::new(get_associated_token_address(&farm, reward_mint), false),
+                AccountMeta::new_readonly(spl_token::ID, false),
+            ],
+        )
+    }
+
+    /// Lock `STAKED` of the user's LP at seed 0, vesting over the farm duration.
+    fn lock_lp(&mut self) -> Pubkey {
+        let (lock, mint_lp) = (self.lock(0).0, self.mint_lp);
+        self.create_ata(&lock, &mint_lp);
+
+        let now = self.now();
+        self.send_as_user(
+            "LockLiquidity",
+            self.lock_liquidity(0, STAKED, now, now + FARM_DURATION),
+        )
+        .unwrap();
+
+        lock
+    }
+
+    /// Stake `amount` of the LP of the user's lock, signed by `owner`.
+    fn stake_locked(&self, owner: &Pubkey, amount: u64) -> Instruction {
+        let (lock, farm) = (self.lock(0).0, self.farm().0);
+        let (lock_stake, bump) = self.farm_stake(&lock);
+        let mint_lp = self.mint_lp;
+
+        let mut data = vec![*StakeLocked::DISCRIMINATOR];
+        data.extend_from_slice(&amount.to_le_bytes());
+        data.push(bump);
+
+        Instruction::new_with_bytes(
+            program_id(),
+            &data,
+            vec![
+                AccountMeta::new(*owner, true),
+                AccountMeta::new_readonly(lock, false),
+                AccountMeta::new(farm, false),
+                AccountMeta::new(lock_stake, false),
+                AccountMeta::new(get_associated_token_address(&lock, &mint_lp), false),
+                AccountMeta::new(get_associated_token_address(&farm, &mint_lp), false),
+                AccountMeta::new_readonly(solana_sdk::system_program::ID, false),
+                AccountMeta::new_readonly(spl_token::ID, false),
+            ],
+        )
+    }
+
+    fn unstake_locked(&self, reward_mint: &Pubkey, amount: u64) -> Instruction {
+        let (user, lock, farm) = (self.user.pubkey(), self.lock(0).0, self.farm().0);
+        let mint_lp = self.mint_lp;
+
+        Instruction::new_with_bytes(
+            program_id(),
+            &[&[*UnstakeLocked::DISCRIMINATOR], &amount.to_le_bytes()[..]].concat(),
+            vec![
+                AccountMeta::new(user, true),
+                AccountMeta::new_readonly(lock, false),
+                AccountMeta::new(farm, false),
+                AccountMeta::new(self.farm_stake(&lock).0, false),
+                AccountMeta::new(get_associated_token_address(&lock, &mint_lp), false),
+                AccountMeta::new(get_associated_token_address(&farm, &mint_lp), false),
+                AccountMeta::new(get_associated_token_address(&user, reward_mint), false),
+                AccountMeta::new(get_associated_token_address(&farm, reward_mint), false),
+                AccountMeta::new_readonly(spl_token::ID, false),
+            ],
+        )
+    }
+}
+
+#[test]
+fn staked_lp_earns_the_reward_rate() {
+    let (mut env, reward_mint) = Env::farmed();
+
+    env.send_as_user("Stake", env.stake(STAKED)).unwrap();
+    assert_eq!(env.user_balance(&env.mint_lp), 1_000_000 - STAKED);
+
+    // The only staker earns the whole rate
+    env.warp_time(100);
+    env.send_as_user(
+        "ClaimRewards",
+        env.claim_rewards(&env.user.pubkey(), &reward_mint),
+    )
+    .unwrap();
+    assert_eq!(env.user_balance(&reward_mint), 100 * REWARD_RATE);
+
+    env.warp_time(100);
+    env.send_as_user("Unstake", env.unstake(&reward_mint, STAKED))
+        .unwrap();
+    assert_eq!(env.user_balance(&reward_mint), 200 * REWARD_RATE);
+    assert_eq!(env.user_balance(&env.mint_lp), 1_000_000);
+}
+
+#[test]
+fn initialize_farm_requires_the_authority() {
+    let mut env = Env::funded();
+    let reward_mint = create_mint(&mut env.svm, &env.authority);
+
+    let user = env.user.pubkey();
+    assert_eq!(
+        env.send_as_user("InitializeFarm", env.initialize_farm(&user, &reward_mint)),
+        Err(InstructionError::IncorrectAuthority)
+    );
+}
+
+#[test]
+fn stakes_only_pay_out_to_their_owner() {
+    let (mut env, reward_mint) = Env::farmed();
+    env.send_as_user("Stake", env.stake(STAKED)).unwrap();
+    env.warp_time(100);
+
+    assert_eq!(
+        env.send_as_user("Unstake", env.unstake(&reward_mint, STAKED + 1)),
+        Err(InstructionError::InsufficientFunds)
+    );
+
+    let authority = env.authority.pubkey();
+    assert_eq!(
+        env.send_as_authority("ClaimRewards", env.claim_rewards(&authority, &reward_mint)),
+        Err(InstructionError::InvalidAccountData)
+    );
+}
+
+#[test]
+fn locked_lp_earns_rewards_without_leaving_the_lock() {
+    let (mut env, reward_mint) = Env::farmed();
+    let (lock, mint_lp) = (env.lock_lp(), env.mint_lp);
+
+    env.send_as_user("StakeLocked", env.stake_locked(&env.user.pubkey(), STAKED))
+        .unwrap();
+    assert_eq!(env.balance(&lock, &mint_lp), 0);
+
+    // An amount of 0 only claims the rewards, paid to the lock owner
+    env.warp_time(100);
+    env.send_as_user("UnstakeLocked", env.unstake_locked(&reward_mint, 0))
+        .unwrap();
+    assert_eq!(env.user_balance(&reward_mint), 100 * REWARD_RATE);
+
+    env.send_as_user("UnstakeLocked", env.unstake_locked(&reward_mint, STAKED))
+        .unwrap();
+    assert_eq!(env.balance(&lock, &mint_lp), STAKED);
+}
+
+#[test]
+fn stake_locked_requires_the_lock_owner() {
+    let (mut env, _) = Env::farmed();
+    env.lock_lp();
+
+    let authority = env.authority.pubkey();
+    assert_eq!(
+        env.send_as_authority("StakeLocked", env.stake_locked(&authority, STAKED)),
+        Err(InstructionError::IncorrectAuthority)
+    );
+}
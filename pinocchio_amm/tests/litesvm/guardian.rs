@@ -0,0 +1,96 @@
+//! `SetGuardian` and `Pause`.
+
+use super::*;
+use pinocchio_amm::{Pause, SetGuardian};
+
+impl Env {
+    /// A funded pool whose guardian is a fresh keypair.
+    fn guarded_by_guardian() -> (Self, Keypair) {
+        let mut env = Self::funded();
+        let guardian = Keypair::new();
+        env.svm.airdrop(&guardian.pubkey(), 1_000_000_000).unwrap();
+
+        env.send_as_authority(
+            "SetGuardian",
+            env.authority_instruction(*SetGuardian::DISCRIMINATOR, guardian.pubkey().as_ref()),
+        )
+        .unwrap();
+
+        (env, guardian)
+    }
+
+    fn pause(&self, signer: &Pubkey, state: AmmState) -> Instruction {
+        Instruction::new_with_bytes(
+            program_id(),
+            &[*Pause::DISCRIMINATOR, state as u8],
+            vec![
+                AccountMeta::new_readonly(*signer, true),
+                AccountMeta::new(self.config, false),
+            ],
+        )
+    }
+}
+
+#[test]
+fn set_guardian_requires_the_authority() {
+    let mut env = Env::funded();
+
+    let mut set_guardian =
+        env.authority_instruction(*SetGuardian::DISCRIMINATOR, env.user.pubkey().as_ref());
+    set_guardian.accounts[0].pubkey = env.user.pubkey();
+
+    assert_eq!(
+        env.send_as_user("SetGuardian", set_guardian),
+        Err(InstructionError::IncorrectAuthority)
+    );
+}
+
+#[test]
+fn the_guardian_pauses_the_pool_step_by_step() {
+    let (mut env, guardian) = Env::guarded_by_guardian();
+
+    let pause = env.pause(&guardian.pubkey(), AmmState::WithdrawOnly);
+    env.send("Pause", pause, &guardian).unwrap();
+    assert_eq!(env.pool(|pool| pool.state()), AmmState::WithdrawOnly as u8);
+
+    let pause = env.pause(&guardian.pubkey(), AmmState::Disabled);
+    env.send("Pause", pause, &guardian).unwrap();
+    assert_eq!(env.pool(|pool| pool.state()), AmmState::Disabled as u8);
+}
+
+#[test]
+fn pause_never_loosens_the_pool_state() {
+    let (mut env, guardian) = Env::guarded_by_guardian();
+
+    let pause = env.pause(&guardian.pubkey(), AmmState::Disabled);
+    env.send("Pause", pause, &guardian).unwrap();
+
+    let pause = env.pause(&guardian.pubkey(), AmmState::WithdrawOnly);
+    assert_eq!(
+        env.send("Pause", pause, &guardian),
+        Err(InstructionError::InvalidAccountData)
+    );
+    assert_eq!(
+        env.send_as_user(
+            "Pause",
+            env.pause(&env.user.pubkey(), AmmState::Initialized)
+        ),
+        Err(InstructionError::InvalidInstructionData)
+    );
+}
+
+#[test]
+fn pause_requires_the_guardian_or_the_authority() {
+    let (mut env, _) = Env::guarded_by_guardian();
+
+    assert_eq!(
+        env.send_as_user("Pause", env.pause(&env.user.pubkey(), AmmState::Disabled)),
+        Err(InstructionError::IncorrectAuthority)
+    );
+
+    env.send_as_authority(
+        "Pause",
+        env.pause(&env.authority.pubkey(), AmmState::Disabled),
+    )
+    .unwrap();
+}
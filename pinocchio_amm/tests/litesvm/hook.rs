@@ -0,0 +1,133 @@
+//! `SetSwapHook` and the hook call of `Swap`, against the stand-in hook program.
+
+use super::*;
+use pinocchio_amm::{SetSwapHook, SwapEvent};
+
+/// Input amount of the swaps the stand-in hook fails, `test_swap_hook::REJECTED_AMOUNT`.
+const REJECTED_AMOUNT: u64 = 4_242;
+
+impl Env {
+    fn set_swap_hook(&self, swap_hook: &Pubkey) -> Instruction {
+        self.authority_instruction(*SetSwapHook::DISCRIMINATOR, swap_hook.as_ref())
+    }
+
+    /// A funded pool calling the stand-in hook after every swap.
+    fn hooked() -> (Self, Pubkey) {
+        let mut env = Self::funded();
+        let hook = env.load_program("test_swap_hook");
+
+        env.send_as_authority("SetSwapHook", env.set_swap_hook(&hook))
+            .unwrap();
+
+        (env, hook)
+    }
+
+    /// A swap selling `amount` of x passing `hook` and `authority` as the hook accounts.
+    fn hooked_swap(&self, amount: u64, hook: &Pubkey, authority: &Pubkey) -> Instruction {
+        let mut swap = self.swap(true, amount, 1, Deadline::Slot(u64::MAX));
+        swap.accounts.extend([
+            AccountMeta::new_readonly(*hook, false),
+            AccountMeta::new_readonly(*authority, false),
+        ]);
+        swap
+    }
+
+    fn hook_authority(&self) -> Pubkey {
+        Pubkey::find_program_address(&[b"hook", self.config.as_ref()], &program_id()).0
+    }
+}
+
+#[test]
+fn set_swap_hook_requires_the_authority() {
+    let mut env = Env::funded();
+
+    let mut set_swap_hook = env.set_swap_hook(&Pubkey::new_unique());
+    set_swap_hook.accounts[0].pubkey = env.user.pubkey();
+
+    assert_eq!(
+        env.send_as_user("SetSwapHook", set_swap_hook),
+        Err(InstructionError::IncorrectAuthority)
+    );
+}
+
+#[test]
+fn set_swap_hook_rejects_the_pool_program() {
+    let mut env = Env::funded();
+
+    assert_eq!(
+        env.send_as_authority("SetSwapHook", env.set_swap_hook(&program_id())),
+        Err(InstructionError::InvalidInstructionData)
+    );
+}
+
+#[test]
+fn swap_calls_the_hook_with_the_swap_event() {
+    let (mut env, hook) = Env::hooked();
+    let y = env.user_balance(&env.mint_y);
+
+    let swap = env.hooked_swap(1_000, &hook, &env.hook_authority());
+    let transaction = Transaction::new_signed_with_payer(
+        &[swap],
+        Some(&env.user.pubkey()),
+        &[&env.user],
+        env.svm.latest_blockhash(),
+    );
+    let meta = env.svm.send_transaction(transaction).unwrap();
+
+    // The stand-in hook checked the layout and the signer, and echoes the event
+    let event = &meta.return_data.data;
+    assert_eq!(meta.return_data.program_id, hook);
+    assert_eq!(event.len(), SwapEvent::LEN);
+    assert_eq!(&event[0..32], env.config.as_ref());
+    assert_eq!(&event[32..64], env.user.pubkey().as_ref());
+    assert_eq!(event[64], 1);
+    assert_eq!(event[65..73], 1_000u64.to_le_bytes());
+    assert_eq!(
+        event[73..81],
+        (env.user_balance(&env.mint_y) - y).to_le_bytes()
+    );
+    assert_eq!(event[105..107], FEE.to_le_bytes());
+}
+
+#[test]
+fn swap_without_the_hook_is_rejected() {
+    let (mut env, _) = Env::hooked();
+
+    assert_eq!(
+        env.send_as_user("Swap", env.swap(true, 1_000, 1, Deadline::Slot(u64::MAX))),
+        Err(InstructionError::NotEnoughAccountKeys)
+    );
+}
+
+#[test]
+fn swap_with_another_hook_or_signer_is_rejected() {
+    let (mut env, hook) = Env::hooked();
+    let other = env.load_program("test_swap_hook");
+
+    assert_eq!(
+        env.send_as_user(
+            "Swap",
+            env.hooked_swap(1_000, &other, &env.hook_authority())
+        ),
+        Err(InstructionError::InvalidAccountData)
+    );
+    assert_eq!(
+        env.send_as_user("Swap", env.hooked_swap(1_000, &hook, &Pubkey::new_unique())),
+        Err(InstructionError::InvalidAccountData)
+    );
+}
+
+#[test]
+fn a_failing_hook_reverts_the_swap() {
+    let (mut env, hook) = Env::hooked();
+    let reserves = env.reserves();
+
+    assert_eq!(
+        env.send_as_user(
+            "Swap",
+            env.hooked_swap(REJECTED_AMOUNT, &hook, &env.hook_authority())
+        ),
+        Err(InstructionError::Custom(0))
+    );
+    assert_eq!(env.reserves(), reserves);
+}
@@ -0,0 +1,190 @@
+//! `BuyLaunch` and `SellLaunch` along the launch curve.
+
+use super::*;
+use pinocchio_amm::SellLaunch;
+
+const VIRTUAL_QUOTE: u64 = 1_000_000;
+const VIRTUAL_TOKEN: u64 = 10_000_000;
+const SALE_SUPPLY: u64 = 5_000_000;
+const TARGET_RAISE: u64 = 1_000_000;
+
+impl Env {
+    /// The launch of x, and its bump.
+    fn launch(&self) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[b"launch", self.mint_x.as_ref()], &program_id())
+    }
+
+    /// A launch of x by the authority, raising `TARGET_RAISE` of y.
+    fn launched() -> Self {
+        let mut env = Self::new();
+        let (creator, (launch, bump)) = (env.authority.pubkey(), env.launch());
+        let (mint_x, mint_y) = (env.mint_x, env.mint_y);
+
+        let creator_ata = env.create_ata(&creator, &mint_x);
+        env.mint_to(&creator, &mint_x, SALE_SUPPLY * 2);
+        let token_vault = env.create_ata(&launch, &mint_x);
+        env.create_ata(&launch, &mint_y);
+
+        let mut data = vec![*InitializeLaunch::DISCRIMINATOR];
+        for amount in [
+            VIRTUAL_QUOTE,
+            VIRTUAL_TOKEN,
+            SALE_SUPPLY,
+            SALE_SUPPLY,
+            TARGET_RAISE,
+        ] {
+            data.extend_from_slice(&amount.to_le_bytes());
+        }
+        data.extend_from_slice(&3_600i64.to_le_bytes());
+        data.extend_from_slice(&FEE.to_le_bytes());
+        data.push(bump);
+        let initialize_launch = Instruction::new_with_bytes(
+            program_id(),
+            &data,
+            vec![
+                AccountMeta::new(creator, true),
+                AccountMeta::new(launch, false),
+                AccountMeta::new_readonly(mint_x, false),
+                AccountMeta::new_readonly(mint_y, false),
+                AccountMeta::new(creator_ata, false),
+                AccountMeta::new(token_vault, false),
+                AccountMeta::new_readonly(solana_sdk::system_program::ID, false),
+                AccountMeta::new_readonly(spl_token::ID, false),
+            ],
+        );
+        env.send_as_authority("InitializeLaunch", initialize_launch)
+            .unwrap();
+
+        env
+    }
+
+    /// `BuyLaunch` or `SellLaunch` of `amount` by the user.
+    fn launch_trade(&self, discriminator: u8, amount: u64, min: u64) -> Instruction {
+        let (user, launch) = (self.user.pubkey(), self.launch().0);
+
+        let mut data = vec![discriminator];
+        data.extend_from_slice(&amount.to_le_bytes());
+        data.extend_from_slice(&min.to_le_bytes());
+
+        Instruction::new_with_bytes(
+            program_id(),
+            &data,
+            vec![
+                AccountMeta::new(user, true),
+                AccountMeta::new(launch, false),
+                AccountMeta::new(get_associated_token_address(&user, &self.mint_y), false),
+                AccountMeta::new(get_associated_token_address(&user, &self.mint_x), false),
+                AccountMeta::new(get_associated_token_address(&launch, &self.mint_y), false),
+                AccountMeta::new(get_associated_token_address(&launch, &self.mint_x), false),
+                AccountMeta::new_readonly(spl_token::ID, false),
+            ],
+        )
+    }
+}
+
+#[test]
+fn sell_launch_buys_tokens_back_along_the_curve() {
+    let mut env = Env::launched();
+    let (x, y) = (env.user_balance(&env.mint_x), env.user_balance(&env.mint_y));
+
+    let bought = constant_product::swap(VIRTUAL_QUOTE, VIRTUAL_TOKEN, 100_000, 0, 1).unwrap();
+    env.send_as_user(
+        "BuyLaunch",
+        env.launch_trade(*BuyLaunch::DISCRIMINATOR, 100_000, 1),
+    )
+    .unwrap();
+    assert_eq!(env.user_balance(&env.mint_x), x + bought.withdraw);
+
+    let sold = constant_product::swap(
+        VIRTUAL_TOKEN - bought.withdraw,
+        VIRTUAL_QUOTE + 100_000,
+        bought.withdraw,
+        0,
+        1,
+    )
+    .unwrap();
+    env.send_as_user(
+        "SellLaunch",
+        env.launch_trade(*SellLaunch::DISCRIMINATOR, bought.withdraw, 1),
+    )
+    .unwrap();
+
+    // Rounding keeps the dust with the launch
+    assert_eq!(env.user_balance(&env.mint_x), x);
+    assert_eq!(env.user_balance(&env.mint_y), y - 100_000 + sold.withdraw);
+    assert!(sold.withdraw <= 100_000);
+}
+
+#[test]
+fn launch_trades_reject_slippage() {
+    let mut env = Env::launched();
+    let bought = constant_product::swap(VIRTUAL_QUOTE, VIRTUAL_TOKEN, 100_000, 0, 1).unwrap();
+
+    assert_eq!(
+        env.send_as_user(
+            "BuyLaunch",
+            env.launch_trade(*BuyLaunch::DISCRIMINATOR, 100_000, bought.withdraw + 1)
+        ),
+        Err(custom(AmmError::SlippageExceeded))
+    );
+
+    env.send_as_user(
+        "BuyLaunch",
+        env.launch_trade(*BuyLaunch::DISCRIMINATOR, 100_000, 1),
+    )
+    .unwrap();
+    assert_eq!(
+        env.send_as_user(
+            "SellLaunch",
+            env.launch_trade(*SellLaunch::DISCRIMINATOR, bought.withdraw, 100_001)
+        ),
+        Err(custom(AmmError::SlippageExceeded))
+    );
+}
+
+#[test]
+fn sell_launch_takes_back_no_more_than_was_sold() {
+    let mut env = Env::launched();
+
+    // The user holds x from elsewhere, which the curve never sold
+    let bought = constant_product::swap(VIRTUAL_QUOTE, VIRTUAL_TOKEN, 100_000, 0, 1).unwrap();
+    env.send_as_user(
+        "BuyLaunch",
+        env.launch_trade(*BuyLaunch::DISCRIMINATOR, 100_000, 1),
+    )
+    .unwrap();
+
+    assert_eq!(
+        env.send_as_user(
+            "SellLaunch",
+            env.launch_trade(*SellLaunch::DISCRIMINATOR, bought.withdraw + 1, 1)
+        ),
+        Err(InstructionError::InsufficientFunds)
+    );
+}
+
+#[test]
+fn a_completed_launch_stops_trading() {
+    let mut env = Env::launched();
+
+    env.send_as_user(
+        "BuyLaunch",
+        env.launch_trade(*BuyLaunch::DISCRIMINATOR, TARGET_RAISE, 1),
+    )
+    .unwrap();
+
+    assert_eq!(
+        env.send_as_user(
+            "BuyLaunch",
+            env.launch_trade(*BuyLaunch::DISCRIMINATOR, 1_000, 1)
+        ),
+        Err(InstructionError::InvalidAccountData)
+    );
+    assert_eq!(
+        env.send_as_user(
+            "SellLaunch",
+            env.launch_trade(*SellLaunch::DISCRIMINATOR, 1_000, 1)
+        ),
+        Err(InstructionError::InvalidAccountData)
+    );
+}
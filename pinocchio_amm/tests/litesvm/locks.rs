@@ -0,0 +1,102 @@
+//! `LockLiquidity` and `UnlockLiquidity`.
+
+use super::*;
+use pinocchio_amm::UnlockLiquidity;
+
+const LOCKED: u64 = 100_000;
+const CLIFF: i64 = 100;
+const DURATION: i64 = 1_000;
+
+impl Env {
+    /// A funded pool where the user locked `LOCKED` of LP at seed 0, vesting over
+    /// `DURATION` seconds after a `CLIFF`.
+    fn locked() -> Self {
+        let mut env = Self::funded();
+        let (lock, mint_lp) = (env.lock(0).0, env.mint_lp);
+        env.create_ata(&lock, &mint_lp);
+
+        let now = env.now();
+        env.send_as_user(
+            "LockLiquidity",
+            env.lock_liquidity(0, LOCKED, now + CLIFF, now + DURATION),
+        )
+        .unwrap();
+
+        env
+    }
+
+    fn unlock_liquidity(&self, owner: &Pubkey, amount: u64) -> Instruction {
+        let lock = self.lock(0).0;
+
+        Instruction::new_with_bytes(
+            program_id(),
+            &[
+                &[*UnlockLiquidity::DISCRIMINATOR],
+                &amount.to_le_bytes()[..],
+            ]
+            .concat(),
+            vec![
+                AccountMeta::new_readonly(*owner, true),
+                AccountMeta::new(lock, false),
+                AccountMeta::new(get_associated_token_address(owner, &self.mint_lp), false),
+                AccountMeta::new(get_associated_token_address(&lock, &self.mint_lp), false),
+                AccountMeta::new_readonly(spl_token::ID, false),
+            ],
+        )
+    }
+}
+
+#[test]
+fn unlock_liquidity_releases_what_vested() {
+    let mut env = Env::locked();
+    let user = env.user.pubkey();
+    assert_eq!(env.user_balance(&env.mint_lp), 1_000_000 - LOCKED);
+
+    assert_eq!(
+        env.send_as_user("UnlockLiquidity", env.unlock_liquidity(&user, 1)),
+        Err(InstructionError::InsufficientFunds)
+    );
+
+    // Vesting runs linearly from the start, once past the cliff
+    env.warp_time(DURATION / 2);
+    env.send_as_user("UnlockLiquidity", env.unlock_liquidity(&user, LOCKED / 2))
+        .unwrap();
+    assert_eq!(env.user_balance(&env.mint_lp), 1_000_000 - LOCKED / 2);
+    assert_eq!(
+        env.send_as_user("UnlockLiquidity", env.unlock_liquidity(&user, 1)),
+        Err(InstructionError::InsufficientFunds)
+    );
+
+    env.warp_time(DURATION);
+    env.send_as_user("UnlockLiquidity", env.unlock_liquidity(&user, LOCKED / 2))
+        .unwrap();
+    assert_eq!(env.user_balance(&env.mint_lp), 1_000_000);
+}
+
+#[test]
+fn unlock_liquidity_requires_the_owner() {
+    let mut env = Env::locked();
+    env.warp_time(DURATION);
+
+    let authority = env.authority.pubkey();
+    assert_eq!(
+        env.send_as_authority("UnlockLiquidity", env.unlock_liquidity(&authority, LOCKED)),
+        Err(InstructionError::IncorrectAuthority)
+    );
+}
+
+#[test]
+fn lock_liquidity_rejects_an_end_before_the_cliff() {
+    let mut env = Env::funded();
+    let (lock, mint_lp) = (env.lock(0).0, env.mint_lp);
+    env.create_ata(&lock, &mint_lp);
+
+    let now = env.now();
+    assert_eq!(
+        env.send_as_user(
+            "LockLiquidity",
+            env.lock_liquidity(0, LOCKED, now + DURATION, now + CLIFF)
+        ),
+        Err(InstructionError::InvalidInstructionData)
+    );
+}
@@ -0,0 +1,1040 @@
+//! Integration tests running the compiled program in LiteSVM.
+//!
+//! The first test to load the program builds it with `cargo build-sbf`, so `cargo test`
+//! always runs against the current sources and fails when the Solana toolchain is
+//! missing. Each instruction prints the compute units it consumed, run with
+//! `--nocapture` to see them.
+
+use litesvm::LiteSVM;
+use pinocchio_amm::{
+    math::constant_product,
+    state::{AmmState, Config, LaunchState, Lock},
+    AmmError, BuyLaunch, Deadline, Deposit, GetPoolState, Graduate, Initialize, InitializeLaunch,
+    LockLiquidity, SetReferralFee, SetState, Swap, Withdraw,
+};
+use solana_sdk::{
+    clock::Clock,
+    instruction::{AccountMeta, Instruction, InstructionError},
+    program_pack::Pack,
+    pubkey::Pubkey,
+    rent::Rent,
+    signature::Keypair,
+    signer::Signer,
+    system_instruction,
+    transaction::{Transaction, TransactionError},
+};
+use spl_associated_token_account::{
+    get_associated_token_address, instruction::create_associated_token_account,
+};
+use std::{
+    path::{Path, PathBuf},
+    process::Command,
+    sync::OnceLock,
+};
+
+mod allowlist;
+mod batch;
+mod circuit_breaker;
+mod commit_reveal;
+mod dynamic_fee;
+mod farms;
+mod guardian;
+mod hook;
+mod launch;
+mod locks;
+mod migrate;
+mod multi_pool;
+mod native;
+mod oracle;
+mod positions;
+mod range_orders;
+mod referral;
+mod weights;
+
+const SEED: u64 = 42;
+const FEE: u16 = 30;
+const MINTED: u64 = 1_000_000_000;
+
+fn program_id() -> Pubkey {
+    Pubkey::new_from_array(pinocchio_amm::ID)
+}
+
+/// Manifests of the program and of the stand-in programs the tests load next to it.
+const MANIFESTS: [&str; 3] = [
+    "Cargo.toml",
+    "test-programs/price_feed/Cargo.toml",
+    "test-programs/swap_hook/Cargo.toml",
+];
+
+/// Directory of the compiled programs, built once per test run.
+fn deploy_dir() -> &'static Path {
+    static DEPLOY_DIR: OnceLock<PathBuf> = OnceLock::new();
+
+    DEPLOY_DIR.get_or_init(|| {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+        for manifest in MANIFESTS {
+            build_sbf(&root.join(manifest));
+        }
+
+        root.join("target/deploy")
+    })
+}
+
+/// Build the program of `manifest` for SBF into `target/deploy`.
+fn build_sbf(manifest: &Path) {
+    let status = Command::new("cargo")
+        .arg("build-sbf")
+        .arg("--manifest-path")
+        .arg(manifest)
+        .status()
+        .unwrap_or_else(|error| panic!("cannot run `cargo build-sbf`: {error}"));
+
+    assert!(
+        status.success(),
+        "`cargo build-sbf` failed for {}",
+        manifest.display()
+    );
+}
+
+fn custom(error: AmmError) -> InstructionError {
+    InstructionError::Custom(error as u32)
+}
+
+/// A pool of two fresh mints, with a user holding `MINTED` of each.
+struct Env {
+    svm: LiteSVM,
+    authority: Keypair,
+    user: Keypair,
+    mint_x: Pubkey,
+    mint_y: Pubkey,
+    config: Pubkey,
+    config_bump: u8,
+    mint_lp: Pubkey,
+    lp_bump: u8,
+    vault_x: Pubkey,
+    vault_y: Pubkey,
+}
+
+impl Env {
+    /// Load the program, building it first when no other test did.
+    fn new() -> Self {
+        let mut svm = LiteSVM::new();
+        svm.add_program_from_file(program_id(), deploy_dir().join("pinocchio_amm.so"))
+            .unwrap();
+
+        let authority = Keypair::new();
+        let user = Keypair::new();
+        svm.airdrop(&authority.pubkey(), 10_000_000_000).unwrap();
+        svm.airdrop(&user.pubkey(), 10_000_000_000).unwrap();
+
+        let mint_x = create_mint(&mut svm, &authority);
+        let mint_y = create_mint(&mut svm, &authority);
+
+        let (config, config_bump) = Pubkey::find_program_address(
+            &[
+                b"config",
+                &SEED.to_le_bytes(),
+                mint_x.as_ref(),
+                mint_y.as_ref(),
+            ],
+            &program_id(),
+        );
+        let (mint_lp, lp_bump) =
+            Pubkey::find_program_address(&[b"mint_lp", config.as_ref()], &program_id());
+
+        let mut env = Self {
+            svm,
+            authority,
+            user,
+            mint_x,
+            mint_y,
+            config,
+            config_bump,
+            mint_lp,
+            lp_bump,
+            vault_x: get_associated_token_address(&config, &mint_x),
+            vault_y: get_associated_token_address(&config, &mint_y),
+        };
+
+        for mint in [mint_x, mint_y] {
+            let user = env.user.pubkey();
+            env.create_ata(&user, &mint);
+            env.mint_to(&user, &mint, MINTED);
+        }
+
+        env
+    }
+
+    /// An initialized pool, with its vaults and the user LP account created.
+    fn initialized() -> Self {
+        let mut env = Self::new();
+
+        env.send_as_authority("Initialize", env.initialize())
+            .unwrap();
+        env.create_pool_accounts();
+
+        env
+    }
+
+    /// Create the vaults and the user LP account, which `Initialize` leaves to clients.
+    fn create_pool_accounts(&mut self) {
+        let (config, user) = (self.config, self.user.pubkey());
+
+        for (owner, mint) in [
+            (config, self.mint_x),
+            (config, self.mint_y),
+            (user, self.mint_lp),
+        ] {
+            self.create_ata(&owner, &mint);
+        }
+    }
+
+    /// An initialized pool holding the user's first deposit.
+    fn funded() -> Self {
+        let mut env = Self::initialized();
+
+        env.send_as_user(
+            "Deposit",
+            env.deposit(1_000_000, 1_000_000, 4_000_000, Deadline::Slot(u64::MAX)),
+        )
+        .unwrap();
+
+        env
+    }
+
+    fn create_ata(&mut self, owner: &Pubkey, mint: &Pubkey) -> Pubkey {
+        let create =
+            create_associated_token_account(&self.authority.pubkey(), owner, mint, &spl_token::ID);
+        self.send_as_authority("CreateAssociatedTokenAccount", create)
+            .unwrap();
+
+        get_associated_token_address(owner, mint)
+    }
+
+    fn send_as_authority(
+        &mut self,
+        name: &str,
+        instruction: Instruction,
+    ) -> Result<u64, InstructionError> {
+        let authority = self.authority.insecure_clone();
+        self.send(name, instruction, &authority)
+    }
+
+    fn send_as_user(
+        &mut self,
+        name: &str,
+        instruction: Instruction,
+    ) -> Result<u64, InstructionError> {
+        let user = self.user.insecure_clone();
+        self.send(name, instruction, &user)
+    }
+
+    /// Send `instruction` alone, paid and signed by `signer`, and return the compute
+    /// units it consumed.
+    fn send(
+        &mut self,
+        name: &str,
+        instruction: Instruction,
+        signer: &Keypair,
+    ) -> Result<u64, InstructionError> {
+        let transaction = Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&signer.pubkey()),
+            &[signer],
+            self.svm.latest_blockhash(),
+        );
+
+        // Identical transactions sent twice would be rejected as already processed
+        let result = self.svm.send_transaction(transaction);
+        self.svm.expire_blockhash();
+
+        match result {
+            Ok(meta) => {
+                println!("{name}: {} compute units", meta.compute_units_consumed);
+                Ok(meta.compute_units_consumed)
+            }
+            Err(failed) => match failed.err {
+                TransactionError::InstructionError(0, error) => Err(error),
+                error => panic!("{name} failed outside of the instruction: {error:?}"),
+            },
+        }
+    }
+
+    fn initialize(&self) -> Instruction {
+        let mut data = vec![*Initialize::DISCRIMINATOR];
+        data.extend_from_slice(&SEED.to_le_bytes());
+        data.extend_from_slice(&FEE.to_le_bytes());
+        data.extend_from_slice(self.mint_x.as_ref());
+        data.extend_from_slice(self.mint_y.as_ref());
+        data.extend_from_slice(&[self.config_bump, self.lp_bump, 6]);
+        data.extend_from_slice(self.authority.pubkey().as_ref());
+
+        Instruction::new_with_bytes(
+            program_id(),
+            &data,
+            vec![
+                AccountMeta::new(self.authority.pubkey(), true),
+                AccountMeta::new(self.mint_lp, false),
+                AccountMeta::new(self.config, false),
+                AccountMeta::new_readonly(solana_sdk::system_program::ID, false),
+                AccountMeta::new_readonly(spl_token::ID, false),
+            ],
+        )
+    }
+
+    fn liquidity(
+        &self,
+        discriminator: u8,
+        amount: u64,
+        x: u64,
+        y: u64,
+        deadline: Deadline,
+    ) -> Instruction {
+        let user = self.user.pubkey();
+
+        let mut data = vec![discriminator];
+        data.extend_from_slice(&amount.to_le_bytes());
+        data.extend_from_slice(&x.to_le_bytes());
+        data.extend_from_slice(&y.to_le_bytes());
+        data.extend_from_slice(&deadline.to_bytes());
+
+        Instruction::new_with_bytes(
+            program_id(),
+            &data,
+            vec![
+                AccountMeta::new(user, true),
+                AccountMeta::new(self.mint_lp, false),
+                AccountMeta::new(self.vault_x, false),
+                AccountMeta::new(self.vault_y, false),
+                AccountMeta::new(get_associated_token_address(&user, &self.mint_x), false),
+                AccountMeta::new(get_associated_token_address(&user, &self.mint_y), false),
+                AccountMeta::new(get_associated_token_address(&user, &self.mint_lp), false),
+                AccountMeta::new(self.config, false),
+                AccountMeta::new_readonly(spl_token::ID, false),
+            ],
+        )
+    }
+
+    fn deposit(&self, amount: u64, max_x: u64, max_y: u64, deadline: Deadline) -> Instruction {
+        self.liquidity(*Deposit::DISCRIMINATOR_V2, amount, max_x, max_y, deadline)
+    }
+
+    fn withdraw(&self, amount: u64, min_x: u64, min_y: u64, deadline: Deadline) -> Instruction {
+        self.liquidity(*Withdraw::DISCRIMINATOR_V2, amount, min_x, min_y, deadline)
+    }
+
+    fn swap(&self, is_x: bool, amount: u64, min: u64, deadline: Deadline) -> Instruction {
+        let user = self.user.pubkey();
+
+        let mut data = vec![*Swap::DISCRIMINATOR_V2, is_x as u8];
+        data.extend_from_slice(&amount.to_le_bytes());
+        data.extend_from_slice(&min.to_le_bytes());
+        data.extend_from_slice(&deadline.to_bytes());
+
+        Instruction::new_with_bytes(
+            program_id(),
+            &data,
+            vec![
+                AccountMeta::new(user, true),
+                AccountMeta::new(get_associated_token_address(&user, &self.mint_x), false),
+                AccountMeta::new(get_associated_token_address(&user, &self.mint_y), false),
+                AccountMeta::new(self.vault_x, false),
+                AccountMeta::new(self.vault_y, false),
+                AccountMeta::new(self.config, false),
+                AccountMeta::new_readonly(spl_token::ID, false),
+            ],
+        )
+    }
+
+    /// A swap selling `amount` of x paying `referral_bps` of the fee to the x account
+    /// of `referrer`.
+    fn referred_swap(&self, amount: u64, referrer: &Pubkey, referral_bps: u16) -> Instruction {
+        let mut swap = self.swap(true, amount, 1, Deadline::Slot(u64::MAX));
+        swap.data.extend_from_slice(&referral_bps.to_le_bytes());
+        swap.accounts.push(AccountMeta::new(
+            get_associated_token_address(referrer, &self.mint_x),
+            false,
+        ));
+        swap
+    }
+
+    /// Instruction of the pool authority over the config alone.
+    fn authority_instruction(&self, discriminator: u8, data: &[u8]) -> Instruction {
+        Instruction::new_with_bytes(
+            program_id(),
+            &[&[discriminator], data].concat(),
+            vec![
+                AccountMeta::new_readonly(self.authority.pubkey(), true),
+                AccountMeta::new(self.config, false),
+            ],
+        )
+    }
+
+    fn set_state(&self, state: AmmState) -> Instruction {
+        self.authority_instruction(*SetState::DISCRIMINATOR, &[state as u8])
+    }
+
+    fn set_referral_fee(&self, max_referral_bps: u16) -> Instruction {
+        self.authority_instruction(
+            *SetReferralFee::DISCRIMINATOR,
+            &max_referral_bps.to_le_bytes(),
+        )
+    }
+
+    /// The lock of the user at `seed`, and its bump.
+    fn lock(&self, seed: u64) -> (Pubkey, u8) {
+        Pubkey::find_program_address(
+            &[
+                b"lock",
+                self.config.as_ref(),
+                self.user.pubkey().as_ref(),
+                &seed.to_le_bytes(),
+            ],
+            &program_id(),
+        )
+    }
+
+    /// Lock `amount` of the user's LP at `seed`, vesting from now until `end_time`
+    /// after `cliff_time`. The lock vault has to exist first.
+    fn lock_liquidity(
+        &self,
+        seed: u64,
+        amount: u64,
+        cliff_time: i64,
+        end_time: i64,
+    ) -> Instruction {
+        let (user, (lock, bump)) = (self.user.pubkey(), self.lock(seed));
+
+        let mut data = vec![*LockLiquidity::DISCRIMINATOR];
+        data.extend_from_slice(&seed.to_le_bytes());
+        data.extend_from_slice(&amount.to_le_bytes());
+        data.extend_from_slice(&cliff_time.to_le_bytes());
+        data.extend_from_slice(&end_time.to_le_bytes());
+        data.push(bump);
+
+        Instruction::new_with_bytes(
+            program_id(),
+            &data,
+            vec![
+                AccountMeta::new(user, true),
+                AccountMeta::new_readonly(self.config, false),
+                AccountMeta::new_readonly(self.mint_lp, false),
+                AccountMeta::new(lock, false),
+                AccountMeta::new(get_associated_token_address(&user, &self.mint_lp), false),
+                AccountMeta::new(get_associated_token_address(&lock, &self.mint_lp), false),
+                AccountMeta::new_readonly(solana_sdk::system_program::ID, false),
+                AccountMeta::new_readonly(spl_token::ID, false),
+            ],
+        )
+    }
+
+    /// Pool state `GetPoolState` returns, in the `PoolState` layout.
+    fn pool_state(&mut self) -> Vec<u8> {
+        let get_pool_state = Instruction::new_with_bytes(
+            program_id(),
+            &[*GetPoolState::DISCRIMINATOR],
+            vec![
+                AccountMeta::new_readonly(self.config, false),
+                AccountMeta::new_readonly(self.vault_x, false),
+                AccountMeta::new_readonly(self.vault_y, false),
+                AccountMeta::new_readonly(spl_token::ID, false),
+            ],
+        );
+        let transaction = Transaction::new_signed_with_payer(
+            &[get_pool_state],
+            Some(&self.user.pubkey()),
+            &[&self.user],
+            self.svm.latest_blockhash(),
+        );
+
+        let meta = self.svm.send_transaction(transaction).unwrap();
+        self.svm.expire_blockhash();
+
+        meta.return_data.data
+    }
+
+    fn balance(&self, owner: &Pubkey, mint: &Pubkey) -> u64 {
+        let account = self
+            .svm
+            .get_account(&get_associated_token_address(owner, mint))
+            .unwrap();
+
+        spl_token::state::Account::unpack(&account.data)
+            .unwrap()
+            .amount
+    }
+
+    fn user_balance(&self, mint: &Pubkey) -> u64 {
+        self.balance(&self.user.pubkey(), mint)
+    }
+
+    /// Lamports of `account`, 0 once it is closed.
+    fn lamports(&self, account: &Pubkey) -> u64 {
+        self.svm
+            .get_account(account)
+            .map_or(0, |account| account.lamports)
+    }
+
+    fn reserves(&self) -> (u64, u64) {
+        (
+            self.balance(&self.config, &self.mint_x),
+            self.balance(&self.config, &self.mint_y),
+        )
+    }
+
+    /// Load the stand-in program `name` of `test-programs` at a fresh address.
+    fn load_program(&mut self, name: &str) -> Pubkey {
+        let program = Pubkey::new_unique();
+        self.svm
+            .add_program_from_file(program, deploy_dir().join(format!("{name}.so")))
+            .unwrap();
+
+        program
+    }
+
+    /// Mint `amount` of `mint` to the associated token account of `owner`.
+    fn mint_to(&mut self, owner: &Pubkey, mint: &Pubkey, amount: u64) {
+        let mint_to = spl_token::instruction::mint_to(
+            &spl_token::ID,
+            mint,
+            &get_associated_token_address(owner, mint),
+            &self.authority.pubkey(),
+            &[],
+            amount,
+        )
+        .unwrap();
+        self.send_as_authority("MintTo", mint_to).unwrap();
+    }
+
+    /// Read the pool config.
+    fn pool<T>(&self, read: impl FnOnce(&Config) -> T) -> T {
+        let account = self.svm.get_account(&self.config).unwrap();
+        read(unsafe { Config::from_bytes_unchecked(&account.data) })
+    }
+
+    fn now(&self) -> i64 {
+        self.svm.get_sysvar::<Clock>().unix_timestamp
+    }
+
+    /// Move the clock `seconds` forward, along with one slot per 400 ms.
+    fn warp_time(&mut self, seconds: i64) {
+        let mut clock: Clock = self.svm.get_sysvar();
+        clock.unix_timestamp += seconds;
+        clock.slot += seconds as u64 * 5 / 2;
+        self.svm.set_sysvar(&clock);
+    }
+
+    /// Move the clock past `slot`, so that deadlines at `slot` are expired.
+    fn warp_past(&mut self, slot: u64) {
+        let mut clock: Clock = self.svm.get_sysvar();
+        clock.slot = slot + 1;
+        self.svm.set_sysvar(&clock);
+    }
+}
+
+fn create_mint(svm: &mut LiteSVM, authority: &Keypair) -> Pubkey {
+    let mint = Keypair::new();
+    let space = spl_token::state::Mint::LEN;
+    let lamports = svm.get_sysvar::<Rent>().minimum_balance(space);
+
+    let instructions = [
+        system_instruction::create_account(
+            &authority.pubkey(),
+            &mint.pubkey(),
+            lamports,
+            space as u64,
+            &spl_token::ID,
+        ),
+        spl_token::instruction::initialize_mint2(
+            &spl_token::ID,
+            &mint.pubkey(),
+            &authority.pubkey(),
+            None,
+            6,
+        )
+        .unwrap(),
+    ];
+    let transaction = Transaction::new_signed_with_payer(
+        &instructions,
+        Some(&authority.pubkey()),
+        &[authority, &mint],
+        svm.latest_blockhash(),
+    );
+    svm.send_transaction(transaction).unwrap();
+
+    mint.pubkey()
+}
+
+#[test]
+fn initialize_creates_the_pool() {
+    let env = Env::initialized();
+
+    let account = env.svm.get_account(&env.config).unwrap();
+    assert_eq!(account.owner, program_id());
+    assert_eq!(account.data.len(), Config::LEN);
+
+    let config = unsafe { Config::from_bytes_unchecked(&account.data) };
+    assert_eq!(config.state(), AmmState::Initialized as u8);
+    assert_eq!(config.seed(), SEED);
+    assert_eq!(config.fee(), FEE);
+    assert_eq!(config.mint_x(), &env.mint_x.to_bytes());
+    assert_eq!(config.mint_y(), &env.mint_y.to_bytes());
+    assert_eq!(config.authority(), &env.authority.pubkey().to_bytes());
+
+    let mint_lp = env.svm.get_account(&env.mint_lp).unwrap();
+    let mint_lp = spl_token::state::Mint::unpack(&mint_lp.data).unwrap();
+    assert_eq!(mint_lp.mint_authority, Some(env.config).into());
+    assert_eq!(mint_lp.supply, 0);
+}
+
+#[test]
+fn initialize_rejects_an_existing_pool() {
+    let mut env = Env::initialized();
+
+    assert!(env
+        .send_as_authority("Initialize", env.initialize())
+        .is_err());
+}
+
+#[test]
+fn initialize_rejects_a_wrong_bump() {
+    let mut env = Env::new();
+
+    let mut initialize = env.initialize();
+    // The config bump sits after the discriminator, seed, fee and both mints
+    initialize.data[1 + 8 + 2 + 32 + 32] = env.config_bump.wrapping_sub(1);
+
+    assert!(env.send_as_authority("Initialize", initialize).is_err());
+}
+
+#[test]
+fn deposit_seeds_the_pool() {
+    let env = Env::funded();
+
+    assert_eq!(env.reserves(), (1_000_000, 4_000_000));
+    assert_eq!(env.user_balance(&env.mint_x), MINTED - 1_000_000);
+    assert_eq!(env.user_balance(&env.mint_y), MINTED - 4_000_000);
+    assert_eq!(env.user_balance(&env.mint_lp), 1_000_000);
+}
+
+#[test]
+fn deposit_follows_the_pool_ratio() {
+    let mut env = Env::funded();
+
+    env.send_as_user(
+        "Deposit",
+        env.deposit(500_000, 500_000, 2_000_000, Deadline::Slot(u64::MAX)),
+    )
+    .unwrap();
+
+    assert_eq!(env.reserves(), (1_500_000, 6_000_000));
+    assert_eq!(env.user_balance(&env.mint_lp), 1_500_000);
+}
+
+#[test]
+fn deposit_rejects_slippage() {
+    let mut env = Env::funded();
+
+    let deposit = env.deposit(500_000, 500_000, 1_999_999, Deadline::Slot(u64::MAX));
+
+    assert_eq!(
+        env.send_as_user("Deposit", deposit),
+        Err(custom(AmmError::SlippageExceeded))
+    );
+}
+
+#[test]
+fn deposit_rejects_wrong_vaults() {
+    let mut env = Env::funded();
+
+    let mut deposit = env.deposit(500_000, 500_000, 2_000_000, Deadline::Slot(u64::MAX));
+    deposit.accounts.swap(2, 3);
+
+    assert_eq!(
+        env.send_as_user("Deposit", deposit),
+        Err(InstructionError::InvalidAccountData)
+    );
+}
+
+#[test]
+fn deposit_rejects_an_expired_deadline() {
+    let mut env = Env::funded();
+
+    env.warp_past(100);
+    let deposit = env.deposit(500_000, 500_000, 2_000_000, Deadline::Slot(100));
+
+    assert_eq!(
+        env.send_as_user("Deposit", deposit),
+        Err(InstructionError::InvalidInstructionData)
+    );
+}
+
+#[test]
+fn withdraw_returns_the_reserves() {
+    let mut env = Env::funded();
+
+    env.send_as_user(
+        "Withdraw",
+        env.withdraw(1_000_000, 1_000_000, 4_000_000, Deadline::Slot(u64::MAX)),
+    )
+    .unwrap();
+
+    assert_eq!(env.reserves(), (0, 0));
+    assert_eq!(env.user_balance(&env.mint_x), MINTED);
+    assert_eq!(env.user_balance(&env.mint_y), MINTED);
+    assert_eq!(env.user_balance(&env.mint_lp), 0);
+}
+
+#[test]
+fn withdraw_rejects_slippage() {
+    let mut env = Env::funded();
+
+    let withdraw = env.withdraw(500_000, 500_001, 2_000_000, Deadline::Slot(u64::MAX));
+
+    assert_eq!(
+        env.send_as_user("Withdraw", withdraw),
+        Err(custom(AmmError::SlippageExceeded))
+    );
+}
+
+#[test]
+fn withdraw_rejects_wrong_vaults() {
+    let mut env = Env::funded();
+
+    let mut withdraw = env.withdraw(500_000, 1, 1, Deadline::Slot(u64::MAX));
+    withdraw.accounts.swap(2, 3);
+
+    assert_eq!(
+        env.send_as_user("Withdraw", withdraw),
+        Err(InstructionError::InvalidAccountData)
+    );
+}
+
+#[test]
+fn withdraw_rejects_an_expired_deadline() {
+    let mut env = Env::funded();
+
+    env.warp_past(100);
+    let withdraw = env.withdraw(500_000, 1, 1, Deadline::Slot(100));
+
+    assert_eq!(
+        env.send_as_user("Withdraw", withdraw),
+        Err(InstructionError::InvalidInstructionData)
+    );
+}
+
+#[test]
+fn swap_pays_out_the_curve_amount() {
+    let mut env = Env::funded();
+
+    for is_x in [true, false] {
+        let (reserve_x, reserve_y) = env.reserves();
+        let (reserve_in, reserve_out) = match is_x {
+            true => (reserve_x, reserve_y),
+            false => (reserve_y, reserve_x),
+        };
+        let expected = constant_product::swap(reserve_in, reserve_out, 10_000, FEE, 1).unwrap();
+
+        let (mint_in, mint_out) = match is_x {
+            true => (env.mint_x, env.mint_y),
+            false => (env.mint_y, env.mint_x),
+        };
+        let (before_in, before_out) = (env.user_balance(&mint_in), env.user_balance(&mint_out));
+
+        env.send_as_user("Swap", env.swap(is_x, 10_000, 1, Deadline::Slot(u64::MAX)))
+            .unwrap();
+
+        assert_eq!(env.user_balance(&mint_in), before_in - expected.deposit);
+        assert_eq!(env.user_balance(&mint_out), before_out + expected.withdraw);
+    }
+}
+
+#[test]
+fn swap_rejects_slippage() {
+    let mut env = Env::funded();
+
+    let (reserve_x, reserve_y) = env.reserves();
+    let expected = constant_product::swap(reserve_x, reserve_y, 10_000, FEE, 1).unwrap();
+    let swap = env.swap(
+        true,
+        10_000,
+        expected.withdraw + 1,
+        Deadline::Slot(u64::MAX),
+    );
+
+    assert_eq!(
+        env.send_as_user("Swap", swap),
+        Err(custom(AmmError::SlippageExceeded))
+    );
+}
+
+#[test]
+fn swap_rejects_wrong_vaults() {
+    let mut env = Env::funded();
+
+    let mut swap = env.swap(true, 10_000, 1, Deadline::Slot(u64::MAX));
+    swap.accounts.swap(3, 4);
+
+    assert_eq!(
+        env.send_as_user("Swap", swap),
+        Err(InstructionError::InvalidAccountData)
+    );
+}
+
+#[test]
+fn swap_rejects_an_expired_deadline() {
+    let mut env = Env::funded();
+
+    env.warp_past(100);
+    let swap = env.swap(true, 10_000, 1, Deadline::Slot(100));
+
+    assert_eq!(
+        env.send_as_user("Swap", swap),
+        Err(InstructionError::InvalidInstructionData)
+    );
+}
+
+#[test]
+fn disabled_pool_rejects_every_operation() {
+    let mut env = Env::funded();
+
+    env.send_as_authority("SetState", env.set_state(AmmState::Disabled))
+        .unwrap();
+
+    let deadline = Deadline::Slot(u64::MAX);
+    let deposit = env.deposit(500_000, 500_000, 2_000_000, deadline);
+    let withdraw = env.withdraw(500_000, 1, 1, deadline);
+    let swap = env.swap(true, 10_000, 1, deadline);
+
+    for (name, instruction) in [("Deposit", deposit), ("Withdraw", withdraw), ("Swap", swap)] {
+        assert_eq!(
+            env.send_as_user(name, instruction),
+            Err(InstructionError::InvalidAccountData)
+        );
+    }
+}
+
+#[test]
+fn withdraw_only_pool_still_lets_liquidity_out() {
+    let mut env = Env::funded();
+
+    env.send_as_authority("SetState", env.set_state(AmmState::WithdrawOnly))
+        .unwrap();
+
+    let deadline = Deadline::Slot(u64::MAX);
+    let deposit = env.deposit(500_000, 500_000, 2_000_000, deadline);
+    let swap = env.swap(true, 10_000, 1, deadline);
+
+    for (name, instruction) in [("Deposit", deposit), ("Swap", swap)] {
+        assert_eq!(
+            env.send_as_user(name, instruction),
+            Err(InstructionError::InvalidAccountData)
+        );
+    }
+
+    env.send_as_user("Withdraw", env.withdraw(500_000, 1, 1, deadline))
+        .unwrap();
+    assert_eq!(env.reserves(), (500_000, 2_000_000));
+}
+
+#[test]
+fn set_state_requires_the_authority() {
+    let mut env = Env::funded();
+
+    let mut set_state = env.set_state(AmmState::Disabled);
+    set_state.accounts[0].pubkey = env.user.pubkey();
+
+    assert_eq!(
+        env.send_as_user("SetState", set_state),
+        Err(InstructionError::IncorrectAuthority)
+    );
+}
+
+#[test]
+fn graduate_seeds_a_locked_pool() {
+    let mut env = Env::new();
+
+    // The authority launches x against y, selling half of the supply for the raise
+    let (virtual_quote, virtual_token) = (1_000_000u64, 10_000_000u64);
+    let (sale_supply, pool_supply) = (5_000_000u64, 5_000_000u64);
+    let target_raise = 1_000_000u64;
+    let (creator, user) = (env.authority.pubkey(), env.user.pubkey());
+    let (mint_x, mint_y) = (env.mint_x, env.mint_y);
+    let (launch, launch_bump) =
+        Pubkey::find_program_address(&[b"launch", mint_x.as_ref()], &program_id());
+
+    let creator_ata = env.create_ata(&creator, &mint_x);
+    let mint_to = spl_token::instruction::mint_to(
+        &spl_token::ID,
+        &mint_x,
+        &creator_ata,
+        &creator,
+        &[],
+        sale_supply + pool_supply,
+    )
+    .unwrap();
+    env.send_as_authority("MintTo", mint_to).unwrap();
+
+    let token_vault = env.create_ata(&launch, &mint_x);
+    let quote_vault = env.create_ata(&launch, &mint_y);
+
+    let mut data = vec![*InitializeLaunch::DISCRIMINATOR];
+    for amount in [
+        virtual_quote,
+        virtual_token,
+        sale_supply,
+        pool_supply,
+        target_raise,
+    ] {
+        data.extend_from_slice(&amount.to_le_bytes());
+    }
+    data.extend_from_slice(&3_600i64.to_le_bytes());
+    data.extend_from_slice(&FEE.to_le_bytes());
+    data.push(launch_bump);
+    let initialize_launch = Instruction::new_with_bytes(
+        program_id(),
+        &data,
+        vec![
+            AccountMeta::new(creator, true),
+            AccountMeta::new(launch, false),
+            AccountMeta::new_readonly(mint_x, false),
+            AccountMeta::new_readonly(mint_y, false),
+            AccountMeta::new(creator_ata, false),
+            AccountMeta::new(token_vault, false),
+            AccountMeta::new_readonly(solana_sdk::system_program::ID, false),
+            AccountMeta::new_readonly(spl_token::ID, false),
+        ],
+    );
+    env.send_as_authority("InitializeLaunch", initialize_launch)
+        .unwrap();
+
+    // The user buys out the raise, which completes the launch
+    let mut data = vec![*BuyLaunch::DISCRIMINATOR];
+    data.extend_from_slice(&target_raise.to_le_bytes());
+    data.extend_from_slice(&1u64.to_le_bytes());
+    let buy_launch = Instruction::new_with_bytes(
+        program_id(),
+        &data,
+        vec![
+            AccountMeta::new(user, true),
+            AccountMeta::new(launch, false),
+            AccountMeta::new(get_associated_token_address(&user, &mint_y), false),
+            AccountMeta::new(get_associated_token_address(&user, &mint_x), false),
+            AccountMeta::new(quote_vault, false),
+            AccountMeta::new(token_vault, false),
+            AccountMeta::new_readonly(spl_token::ID, false),
+        ],
+    );
+    env.send_as_user("BuyLaunch", buy_launch).unwrap();
+
+    let launch_account = env.svm.get_account(&launch).unwrap();
+    assert_eq!(launch_account.data[0], LaunchState::Completed as u8);
+
+    // Only the pool vaults can be created up front, the LP mint does not exist yet
+    let config = env.config;
+    env.create_ata(&config, &mint_x);
+    env.create_ata(&config, &mint_y);
+
+    let (lock, lock_bump) = Pubkey::find_program_address(
+        &[
+            b"lock",
+            config.as_ref(),
+            creator.as_ref(),
+            &0u64.to_le_bytes(),
+        ],
+        &program_id(),
+    );
+    let lock_vault = get_associated_token_address(&lock, &env.mint_lp);
+    let (x, y) = (env.balance(&launch, &mint_x), env.balance(&launch, &mint_y));
+
+    let mut data = vec![*Graduate::DISCRIMINATOR];
+    data.extend_from_slice(&SEED.to_le_bytes());
+    data.extend_from_slice(&[env.config_bump, env.lp_bump, 6, lock_bump]);
+    let graduate = Instruction::new_with_bytes(
+        program_id(),
+        &data,
+        vec![
+            AccountMeta::new(creator, true),
+            AccountMeta::new(launch, false),
+            AccountMeta::new(token_vault, false),
+            AccountMeta::new(quote_vault, false),
+            AccountMeta::new(config, false),
+            AccountMeta::new(env.mint_lp, false),
+            AccountMeta::new(env.vault_x, false),
+            AccountMeta::new(env.vault_y, false),
+            AccountMeta::new(lock, false),
+            AccountMeta::new(lock_vault, false),
+            AccountMeta::new_readonly(solana_sdk::system_program::ID, false),
+            AccountMeta::new_readonly(spl_token::ID, false),
+            AccountMeta::new_readonly(spl_associated_token_account::ID, false),
+        ],
+    );
+    env.send_as_authority("Graduate", graduate).unwrap();
+
+    // The raise and the unsold supply move into the pool, its LP into the lock
+    let lp = (x as u128 * y as u128).isqrt() as u64;
+    assert_eq!(y, target_raise);
+    assert_eq!(env.reserves(), (x, y));
+    assert_eq!(env.balance(&launch, &mint_x), 0);
+    assert_eq!(env.balance(&launch, &mint_y), 0);
+    assert_eq!(env.balance(&lock, &env.mint_lp), lp);
+
+    let account = env.svm.get_account(&config).unwrap();
+    let pool = unsafe { Config::from_bytes_unchecked(&account.data) };
+    assert_eq!(pool.state(), AmmState::Initialized as u8);
+    assert_eq!(pool.authority(), &[0u8; 32]);
+    assert_eq!(pool.lp_supply(), lp);
+
+    let lock_account = env.svm.get_account(&lock).unwrap();
+    assert_eq!(lock_account.owner, program_id());
+    assert_eq!(lock_account.data.len(), Lock::LEN);
+
+    let launch_account = env.svm.get_account(&launch).unwrap();
+    assert_eq!(launch_account.data[0], LaunchState::Graduated as u8);
+}
+
+/// Print the compute units of each core instruction on a fresh pool, so that
+/// regressions show up in the test output.
+#[test]
+fn report_compute_units() {
+    let mut env = Env::new();
+
+    let deadline = Deadline::Slot(u64::MAX);
+    let mut report = vec![(
+        "Initialize",
+        env.send_as_authority("Initialize", env.initialize()),
+    )];
+    env.create_pool_accounts();
+
+    report.push((
+        "Deposit (first)",
+        env.send_as_user(
+            "Deposit",
+            env.deposit(1_000_000, 1_000_000, 4_000_000, deadline),
+        ),
+    ));
+    report.push((
+        "Deposit",
+        env.send_as_user(
+            "Deposit",
+            env.deposit(500_000, 500_000, 2_000_000, deadline),
+        ),
+    ));
+    report.push((
+        "Swap x for y",
+        env.send_as_user("Swap", env.swap(true, 10_000, 1, deadline)),
+    ));
+    report.push((
+        "Swap y for x",
+        env.send_as_user("Swap", env.swap(false, 10_000, 1, deadline)),
+    ));
+    report.push((
+        "Withdraw",
+        env.send_as_user("Withdraw", env.withdraw(500_000, 1, 1, deadline)),
+    ));
+
+    println!("{:<16} {:>14}", "instruction", "compute units");
+    for (name, compute_units) in report {
+        println!("{name:<16} {:>14}", compute_units.unwrap());
+    }
+}
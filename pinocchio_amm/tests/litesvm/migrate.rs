@@ -0,0 +1,141 @@
+//! `MigrateLiquidity` between two pools of the same pair.
+
+use super::*;
+use pinocchio_amm::MigrateLiquidity;
+
+const TARGET_SEED: u64 = SEED + 1;
+
+impl Env {
+    /// The config of the pool at `TARGET_SEED` and its LP mint, with their bumps.
+    fn target_pool(&self) -> ((Pubkey, u8), (Pubkey, u8)) {
+        let target = Pubkey::find_program_address(
+            &[
+                b"config",
+                &TARGET_SEED.to_le_bytes(),
+                self.mint_x.as_ref(),
+                self.mint_y.as_ref(),
+            ],
+            &program_id(),
+        );
+        let mint_lp = Pubkey::find_program_address(&[b"mint_lp", target.0.as_ref()], &program_id());
+
+        (target, mint_lp)
+    }
+
+    /// A funded pool next to an empty one of the same pair at `TARGET_SEED`.
+    fn with_target_pool() -> Self {
+        let mut env = Self::funded();
+        let ((target, target_bump), (mint_lp, lp_bump)) = env.target_pool();
+
+        // Same as `initialize`, at the target seed and its addresses
+        let mut initialize = env.initialize();
+        initialize.data[1..9].copy_from_slice(&TARGET_SEED.to_le_bytes());
+        initialize.data[75..77].copy_from_slice(&[target_bump, lp_bump]);
+        initialize.accounts[1].pubkey = mint_lp;
+        initialize.accounts[2].pubkey = target;
+        env.send_as_authority("Initialize", initialize).unwrap();
+
+        let user = env.user.pubkey();
+        for (owner, mint) in [(target, env.mint_x), (target, env.mint_y), (user, mint_lp)] {
+            env.create_ata(&owner, &mint);
+        }
+
+        env
+    }
+
+    /// Move `amount` of the user's LP from the funded pool into `target`.
+    fn migrate_liquidity(&self, target: &Pubkey, amount: u64, min_lp: u64) -> Instruction {
+        let user = self.user.pubkey();
+        let target_mint_lp =
+            Pubkey::find_program_address(&[b"mint_lp", target.as_ref()], &program_id()).0;
+
+        let mut data = vec![*MigrateLiquidity::DISCRIMINATOR];
+        data.extend_from_slice(&amount.to_le_bytes());
+        data.extend_from_slice(&min_lp.to_le_bytes());
+        data.extend_from_slice(&Deadline::Slot(u64::MAX).to_bytes());
+        data.push(0);
+
+        Instruction::new_with_bytes(
+            program_id(),
+            &data,
+            vec![
+                AccountMeta::new(user, true),
+                AccountMeta::new(self.config, false),
+                AccountMeta::new(self.mint_lp, false),
+                AccountMeta::new(self.vault_x, false),
+                AccountMeta::new(self.vault_y, false),
+                AccountMeta::new(get_associated_token_address(&user, &self.mint_lp), false),
+                AccountMeta::new(*target, false),
+                AccountMeta::new(target_mint_lp, false),
+                AccountMeta::new(get_associated_token_address(target, &self.mint_x), false),
+                AccountMeta::new(get_associated_token_address(target, &self.mint_y), false),
+                AccountMeta::new(get_associated_token_address(&user, &target_mint_lp), false),
+                AccountMeta::new(get_associated_token_address(&user, &self.mint_x), false),
+                AccountMeta::new(get_associated_token_address(&user, &self.mint_y), false),
+                AccountMeta::new_readonly(spl_token::ID, false),
+            ],
+        )
+    }
+}
+
+#[test]
+fn migrate_liquidity_moves_the_share_into_an_empty_pool() {
+    let mut env = Env::with_target_pool();
+    let ((target, _), (target_mint_lp, _)) = env.target_pool();
+    let (x, y) = (env.user_balance(&env.mint_x), env.user_balance(&env.mint_y));
+
+    env.send_as_user(
+        "MigrateLiquidity",
+        env.migrate_liquidity(&target, 500_000, 500_000),
+    )
+    .unwrap();
+
+    // The empty target takes both halves and mints as much LP as was burned
+    assert_eq!(env.reserves(), (500_000, 2_000_000));
+    assert_eq!(env.balance(&target, &env.mint_x), 500_000);
+    assert_eq!(env.balance(&target, &env.mint_y), 2_000_000);
+    assert_eq!(env.user_balance(&env.mint_lp), 500_000);
+    assert_eq!(env.user_balance(&target_mint_lp), 500_000);
+    assert_eq!(env.user_balance(&env.mint_x), x);
+    assert_eq!(env.user_balance(&env.mint_y), y);
+}
+
+#[test]
+fn migrate_liquidity_rejects_slippage() {
+    let mut env = Env::with_target_pool();
+    let target = env.target_pool().0 .0;
+
+    assert_eq!(
+        env.send_as_user(
+            "MigrateLiquidity",
+            env.migrate_liquidity(&target, 500_000, 500_001)
+        ),
+        Err(custom(AmmError::SlippageExceeded))
+    );
+}
+
+#[test]
+fn migrate_liquidity_requires_another_open_pool() {
+    let mut env = Env::with_target_pool();
+    let (config, target) = (env.config, env.target_pool().0 .0);
+
+    assert_eq!(
+        env.send_as_user(
+            "MigrateLiquidity",
+            env.migrate_liquidity(&config, 500_000, 1)
+        ),
+        Err(InstructionError::InvalidArgument)
+    );
+
+    let mut set_state = env.set_state(AmmState::Disabled);
+    set_state.accounts[1].pubkey = target;
+    env.send_as_authority("SetState", set_state).unwrap();
+
+    assert_eq!(
+        env.send_as_user(
+            "MigrateLiquidity",
+            env.migrate_liquidity(&target, 500_000, 1)
+        ),
+        Err(InstructionError::InvalidAccountData)
+    );
+}
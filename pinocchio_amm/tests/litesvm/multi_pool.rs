@@ -0,0 +1,342 @@
+//! `InitializeMultiPool`, `MultiDeposit`, `MultiWithdraw`, `MultiSwap`, `MultiJoin` and
+//! `MultiExit` on a pool of three tokens.
+
+use super::*;
+use pinocchio_amm::{
+    math::{lp_out_given_in, out_given_in, out_given_lp_in, single_asset_fee},
+    InitializeMultiPool, MultiDeposit, MultiExit, MultiJoin, MultiSwap, MultiWithdraw,
+};
+
+const MULTI_SEED: u64 = 7;
+const WEIGHTS: [u16; 3] = [5_000, 3_000, 2_000];
+/// Seed balances, each token worth as much as its weight.
+const BALANCES: [u64; 3] = [1_000_000, 600_000, 400_000];
+
+impl Env {
+    /// The multi-asset pool at `MULTI_SEED` and its LP mint, with their bumps.
+    fn multi_pool(&self) -> ((Pubkey, u8), (Pubkey, u8)) {
+        let pool = Pubkey::find_program_address(
+            &[b"multi_pool", &MULTI_SEED.to_le_bytes()],
+            &program_id(),
+        );
+        let mint_lp = Pubkey::find_program_address(&[b"mint_lp", pool.0.as_ref()], &program_id());
+
+        (pool, mint_lp)
+    }
+
+    fn initialize_multi_pool(&self, mints: &[Pubkey], weights: &[u16]) -> Instruction {
+        let ((pool, pool_bump), (mint_lp, lp_bump)) = self.multi_pool();
+        let authority = self.authority.pubkey();
+
+        let mut data = vec![*InitializeMultiPool::DISCRIMINATOR];
+        data.extend_from_slice(&MULTI_SEED.to_le_bytes());
+        data.extend_from_slice(&FEE.to_le_bytes());
+        data.extend_from_slice(&[pool_bump, lp_bump]);
+        data.extend_from_slice(authority.as_ref());
+        for weight in weights {
+            data.extend_from_slice(&weight.to_le_bytes());
+        }
+
+        let mut accounts = vec![
+            AccountMeta::new(authority, true),
+            AccountMeta::new(mint_lp, false),
+            AccountMeta::new(pool, false),
+            AccountMeta::new_readonly(solana_sdk::system_program::ID, false),
+            AccountMeta::new_readonly(spl_token::ID, false),
+        ];
+        accounts.extend(
+            mints
+                .iter()
+                .map(|mint| AccountMeta::new_readonly(*mint, false)),
+        );
+
+        Instruction::new_with_bytes(program_id(), &data, accounts)
+    }
+
+    /// A pool of x, y and a third mint, seeded by the user with `BALANCES`.
+    fn multi_pooled() -> (Self, [Pubkey; 3]) {
+        let mut env = Self::new();
+        let mint_z = create_mint(&mut env.svm, &env.authority);
+        let mints = [env.mint_x, env.mint_y, mint_z];
+        let (user, ((pool, _), (mint_lp, _))) = (env.user.pubkey(), env.multi_pool());
+
+        env.create_ata(&user, &mint_z);
+        env.mint_to(&user, &mint_z, MINTED);
+
+        env.send_as_authority(
+            "InitializeMultiPool",
+            env.initialize_multi_pool(&mints, &WEIGHTS),
+        )
+        .unwrap();
+        for mint in mints {
+            env.create_ata(&pool, &mint);
+        }
+        env.create_ata(&user, &mint_lp);
+
+        env.send_as_user(
+            "MultiDeposit",
+            env.multi_liquidity(*MultiDeposit::DISCRIMINATOR, &mints, 1_000_000, BALANCES),
+        )
+        .unwrap();
+
+        (env, mints)
+    }
+
+    /// `MultiDeposit` or `MultiWithdraw` of `amount` LP, bounded by `limits`.
+    fn multi_liquidity(
+        &self,
+        discriminator: u8,
+        mints: &[Pubkey; 3],
+        amount: u64,
+        limits: [u64; 3],
+    ) -> Instruction {
+        let (user, ((pool, _), (mint_lp, _))) = (self.user.pubkey(), self.multi_pool());
+
+        let mut data = vec![discriminator];
+        data.extend_from_slice(&amount.to_le_bytes());
+        data.extend_from_slice(&(self.now() + 60).to_le_bytes());
+        for limit in limits {
+            data.extend_from_slice(&limit.to_le_bytes());
+        }
+
+        let mut accounts = vec![
+            AccountMeta::new(user, true),
+            AccountMeta::new_readonly(pool, false),
+            AccountMeta::new(mint_lp, false),
+            AccountMeta::new(get_associated_token_address(&user, &mint_lp), false),
+            AccountMeta::new_readonly(spl_token::ID, false),
+        ];
+        for owner in [pool, user] {
+            accounts.extend(
+                mints.iter().map(|mint| {
+                    AccountMeta::new(get_associated_token_address(&owner, mint), false)
+                }),
+            );
+        }
+
+        Instruction::new_with_bytes(program_id(), &data, accounts)
+    }
+
+    fn multi_swap(
+        &self,
+        mints: &[Pubkey; 3],
+        index_in: u8,
+        index_out: u8,
+        amount: u64,
+        min: u64,
+    ) -> Instruction {
+        let (user, pool) = (self.user.pubkey(), self.multi_pool().0 .0);
+
+        let mut data = vec![*MultiSwap::DISCRIMINATOR, index_in, index_out];
+        data.extend_from_slice(&amount.to_le_bytes());
+        data.extend_from_slice(&min.to_le_bytes());
+        data.extend_from_slice(&(self.now() + 60).to_le_bytes());
+
+        let mut accounts = vec![
+            AccountMeta::new(user, true),
+            AccountMeta::new_readonly(pool, false),
+            AccountMeta::new(
+                get_associated_token_address(&user, &mints[index_in as usize]),
+                false,
+            ),
+            AccountMeta::new(
+                get_associated_token_address(&user, &mints[index_out as usize]),
+                false,
+            ),
+            AccountMeta::new_readonly(spl_token::ID, false),
+        ];
+        accounts.extend(
+            mints
+                .iter()
+                .map(|mint| AccountMeta::new(get_associated_token_address(&pool, mint), false)),
+        );
+
+        Instruction::new_with_bytes(program_id(), &data, accounts)
+    }
+
+    /// `MultiJoin` of `amount` of the token at `index`, or `MultiExit` of `amount` LP
+    /// into it.
+    fn multi_single(
+        &self,
+        discriminator: u8,
+        mints: &[Pubkey; 3],
+        index: u8,
+        amount: u64,
+        min: u64,
+    ) -> Instruction {
+        let (user, ((pool, _), (mint_lp, _))) = (self.user.pubkey(), self.multi_pool());
+
+        let mut data = vec![discriminator, index];
+        data.extend_from_slice(&amount.to_le_bytes());
+        data.extend_from_slice(&min.to_le_bytes());
+        data.extend_from_slice(&(self.now() + 60).to_le_bytes());
+
+        let mut accounts = vec![
+            AccountMeta::new(user, true),
+            AccountMeta::new_readonly(pool, false),
+            AccountMeta::new(mint_lp, false),
+            AccountMeta::new(
+                get_associated_token_address(&user, &mints[index as usize]),
+                false,
+            ),
+            AccountMeta::new(get_associated_token_address(&user, &mint_lp), false),
+            AccountMeta::new_readonly(spl_token::ID, false),
+        ];
+        accounts.extend(
+            mints
+                .iter()
+                .map(|mint| AccountMeta::new(get_associated_token_address(&pool, mint), false)),
+        );
+
+        Instruction::new_with_bytes(program_id(), &data, accounts)
+    }
+}
+
+#[test]
+fn multi_withdraw_returns_the_share_of_every_token() {
+    let (mut env, mints) = Env::multi_pooled();
+    let pool = env.multi_pool().0 .0;
+    for (mint, balance) in mints.iter().zip(BALANCES) {
+        assert_eq!(env.balance(&pool, mint), balance);
+    }
+
+    env.send_as_user(
+        "MultiWithdraw",
+        env.multi_liquidity(
+            *MultiWithdraw::DISCRIMINATOR,
+            &mints,
+            500_000,
+            BALANCES.map(|balance| balance / 2),
+        ),
+    )
+    .unwrap();
+
+    for (mint, balance) in mints.iter().zip(BALANCES) {
+        assert_eq!(env.balance(&pool, mint), balance / 2);
+        assert_eq!(env.user_balance(mint), MINTED - balance / 2);
+    }
+    assert_eq!(env.user_balance(&env.multi_pool().1 .0), 500_000);
+}
+
+#[test]
+fn multi_deposit_and_withdraw_reject_slippage() {
+    let (mut env, mints) = Env::multi_pooled();
+
+    // Half the LP again needs half of every balance, rounded up
+    assert_eq!(
+        env.send_as_user(
+            "MultiDeposit",
+            env.multi_liquidity(
+                *MultiDeposit::DISCRIMINATOR,
+                &mints,
+                500_000,
+                [500_000, 300_000, 199_999],
+            )
+        ),
+        Err(InstructionError::InvalidArgument)
+    );
+    assert_eq!(
+        env.send_as_user(
+            "MultiWithdraw",
+            env.multi_liquidity(
+                *MultiWithdraw::DISCRIMINATOR,
+                &mints,
+                500_000,
+                [500_000, 300_000, 200_001],
+            )
+        ),
+        Err(InstructionError::InvalidArgument)
+    );
+}
+
+#[test]
+fn multi_swap_pays_out_the_weighted_curve() {
+    let (mut env, mints) = Env::multi_pooled();
+    let (x, z) = (env.user_balance(&mints[0]), env.user_balance(&mints[2]));
+
+    let amount_after_fee = 10_000 * (10_000 - FEE as u64) / 10_000;
+    let expected = out_given_in(
+        BALANCES[0],
+        WEIGHTS[0],
+        BALANCES[2],
+        WEIGHTS[2],
+        amount_after_fee,
+    )
+    .unwrap();
+
+    assert_eq!(
+        env.send_as_user(
+            "MultiSwap",
+            env.multi_swap(&mints, 0, 2, 10_000, expected + 1)
+        ),
+        Err(custom(AmmError::SlippageExceeded))
+    );
+    env.send_as_user("MultiSwap", env.multi_swap(&mints, 0, 2, 10_000, expected))
+        .unwrap();
+
+    assert_eq!(env.user_balance(&mints[0]), x - 10_000);
+    assert_eq!(env.user_balance(&mints[2]), z + expected);
+}
+
+#[test]
+fn multi_pool_instructions_reject_vaults_out_of_order() {
+    let (mut env, mints) = Env::multi_pooled();
+
+    let mut swap = env.multi_swap(&mints, 0, 2, 10_000, 1);
+    swap.accounts.swap(5, 7);
+
+    assert_eq!(
+        env.send_as_user("MultiSwap", swap),
+        Err(InstructionError::InvalidAccountData)
+    );
+}
+
+#[test]
+fn multi_join_and_exit_price_on_the_weighted_invariant() {
+    let (mut env, mints) = Env::multi_pooled();
+    let mint_lp = env.multi_pool().1 .0;
+
+    let fee = single_asset_fee(10_000, WEIGHTS[1], FEE);
+    let lp = lp_out_given_in(BALANCES[1], WEIGHTS[1], 1_000_000, 10_000 - fee).unwrap();
+    env.send_as_user(
+        "MultiJoin",
+        env.multi_single(*MultiJoin::DISCRIMINATOR, &mints, 1, 10_000, lp),
+    )
+    .unwrap();
+    assert_eq!(env.user_balance(&mint_lp), 1_000_000 + lp);
+
+    let out = out_given_lp_in(BALANCES[1] + 10_000, WEIGHTS[1], 1_000_000 + lp, lp).unwrap();
+    let out = out - single_asset_fee(out, WEIGHTS[1], FEE);
+    assert_eq!(
+        env.send_as_user(
+            "MultiExit",
+            env.multi_single(*MultiExit::DISCRIMINATOR, &mints, 1, lp, out + 1)
+        ),
+        Err(custom(AmmError::SlippageExceeded))
+    );
+
+    let y = env.user_balance(&mints[1]);
+    env.send_as_user(
+        "MultiExit",
+        env.multi_single(*MultiExit::DISCRIMINATOR, &mints, 1, lp, out),
+    )
+    .unwrap();
+
+    // Both ways pay the fee, so the round trip loses some of the 10_000
+    assert_eq!(env.user_balance(&mints[1]), y + out);
+    assert!(out < 10_000);
+}
+
+#[test]
+fn initialize_multi_pool_requires_weights_summing_to_one() {
+    let mut env = Env::new();
+    let mints = [env.mint_x, env.mint_y];
+
+    assert_eq!(
+        env.send_as_authority(
+            "InitializeMultiPool",
+            env.initialize_multi_pool(&mints, &[5_000, 4_000])
+        ),
+        Err(InstructionError::InvalidAccountData)
+    );
+}
@@ -0,0 +1,166 @@
+//! Swaps paying and receiving native SOL through the temporary wSOL account.
+
+use super::*;
+use pinocchio_amm::NATIVE_MINT;
+use solana_sdk::account::Account;
+
+/// LiteSVM charges 5_000 lamports per signature by default.
+const TRANSACTION_FEE: u64 = 5_000;
+
+impl Env {
+    /// A pool of native SOL as x against y, holding the user's first deposit of wSOL.
+    fn native() -> Self {
+        let mut env = Self::new();
+        let native_mint = Pubkey::new_from_array(NATIVE_MINT);
+
+        // LiteSVM starts without the native mint
+        let mut data = vec![0; spl_token::state::Mint::LEN];
+        spl_token::state::Mint {
+            decimals: 9,
+            is_initialized: true,
+            ..Default::default()
+        }
+        .pack_into_slice(&mut data);
+        let lamports = env.svm.get_sysvar::<Rent>().minimum_balance(data.len());
+        env.svm
+            .set_account(
+                native_mint,
+                Account {
+                    lamports,
+                    data,
+                    owner: spl_token::ID,
+                    executable: false,
+                    rent_epoch: 0,
+                },
+            )
+            .unwrap();
+
+        (env.config, env.config_bump) = Pubkey::find_program_address(
+            &[
+                b"config",
+                &SEED.to_le_bytes(),
+                native_mint.as_ref(),
+                env.mint_y.as_ref(),
+            ],
+            &program_id(),
+        );
+        (env.mint_lp, env.lp_bump) =
+            Pubkey::find_program_address(&[b"mint_lp", env.config.as_ref()], &program_id());
+        env.mint_x = native_mint;
+        env.vault_x = get_associated_token_address(&env.config, &native_mint);
+        env.vault_y = get_associated_token_address(&env.config, &env.mint_y);
+
+        env.send_as_authority("Initialize", env.initialize())
+            .unwrap();
+        env.create_pool_accounts();
+
+        // Wrap the SOL of the first deposit into the user's wSOL account
+        let user = env.user.pubkey();
+        let user_x = env.create_ata(&user, &native_mint);
+        env.send_as_user(
+            "Transfer",
+            system_instruction::transfer(&user, &user_x, 1_000_000),
+        )
+        .unwrap();
+        env.send_as_user(
+            "SyncNative",
+            spl_token::instruction::sync_native(&spl_token::ID, &user_x).unwrap(),
+        )
+        .unwrap();
+
+        env.send_as_user(
+            "Deposit",
+            env.deposit(1_000_000, 1_000_000, 4_000_000, Deadline::Slot(u64::MAX)),
+        )
+        .unwrap();
+
+        env
+    }
+
+    /// The temporary wSOL account of the user.
+    fn wsol(&self) -> Pubkey {
+        Pubkey::find_program_address(&[b"wsol", self.user.pubkey().as_ref()], &program_id()).0
+    }
+
+    /// A swap paying or receiving x through the user's wallet rather than a token account.
+    fn native_swap(&self, is_x: bool, amount: u64) -> Instruction {
+        let user = self.user.pubkey();
+
+        let mut swap = self.swap(is_x, amount, 1, Deadline::Slot(u64::MAX));
+        swap.accounts[1] = AccountMeta::new(user, false);
+        swap.accounts.extend([
+            AccountMeta::new(self.wsol(), false),
+            AccountMeta::new_readonly(Pubkey::new_from_array(NATIVE_MINT), false),
+            AccountMeta::new_readonly(solana_sdk::system_program::ID, false),
+        ]);
+        swap
+    }
+}
+
+#[test]
+fn swap_pays_native_sol_from_the_wallet() {
+    let mut env = Env::native();
+    let user = env.user.pubkey();
+
+    let (reserve_x, reserve_y) = env.reserves();
+    let expected = constant_product::swap(reserve_x, reserve_y, 10_000, FEE, 1).unwrap();
+    let (lamports, y) = (env.lamports(&user), env.user_balance(&env.mint_y));
+
+    env.send_as_user("Swap", env.native_swap(true, 10_000))
+        .unwrap();
+
+    // The wSOL account is closed again, its rent back with the user
+    assert_eq!(env.reserves().0, reserve_x + 10_000);
+    assert_eq!(env.lamports(&user), lamports - 10_000 - TRANSACTION_FEE);
+    assert_eq!(env.user_balance(&env.mint_y), y + expected.withdraw);
+    assert_eq!(env.lamports(&env.wsol()), 0);
+}
+
+#[test]
+fn swap_unwraps_native_sol_to_the_wallet() {
+    let mut env = Env::native();
+    let user = env.user.pubkey();
+
+    let (reserve_x, reserve_y) = env.reserves();
+    let expected = constant_product::swap(reserve_y, reserve_x, 40_000, FEE, 1).unwrap();
+    let lamports = env.lamports(&user);
+
+    env.send_as_user("Swap", env.native_swap(false, 40_000))
+        .unwrap();
+
+    assert_eq!(env.reserves().0, reserve_x - expected.withdraw);
+    assert_eq!(
+        env.lamports(&user),
+        lamports + expected.withdraw - TRANSACTION_FEE
+    );
+    assert_eq!(env.lamports(&env.wsol()), 0);
+}
+
+#[test]
+fn swap_pays_from_the_wallet_only_in_native_sol() {
+    let mut env = Env::funded();
+
+    assert_eq!(
+        env.send_as_user("Swap", env.native_swap(true, 10_000)),
+        Err(InstructionError::InvalidAccountData)
+    );
+}
+
+#[test]
+fn swap_requires_the_wsol_account_of_the_user() {
+    let mut env = Env::native();
+
+    let mut swap = env.native_swap(true, 10_000);
+    swap.accounts.truncate(7);
+    assert_eq!(
+        env.send_as_user("Swap", swap),
+        Err(InstructionError::NotEnoughAccountKeys)
+    );
+
+    let mut swap = env.native_swap(true, 10_000);
+    swap.accounts[7].pubkey = Pubkey::new_unique();
+    assert_eq!(
+        env.send_as_user("Swap", swap),
+        Err(InstructionError::InvalidAccountData)
+    );
+}
@@ -0,0 +1,173 @@
+//! `SetOracle` and the oracle guard of `Swap`, against the stand-in price feed.
+
+use super::*;
+use pinocchio_amm::{PriceFeed, SetOracle, PRICE_SCALE};
+use solana_sdk::account::Account;
+
+const MAX_DEVIATION_BPS: u16 = 100;
+const MAX_AGE: i64 = 60;
+
+impl Env {
+    /// A feed of the stand-in price feed program publishing `price`.
+    fn price_feed(&mut self, price: u128) -> (Pubkey, Pubkey) {
+        let program = self.load_program("test_price_feed");
+        let feed = Pubkey::new_unique();
+        let lamports = self
+            .svm
+            .get_sysvar::<Rent>()
+            .minimum_balance(PriceFeed::LEN);
+
+        self.svm
+            .set_account(
+                feed,
+                Account {
+                    lamports,
+                    data: vec![0; PriceFeed::LEN],
+                    owner: program,
+                    executable: false,
+                    rent_epoch: 0,
+                },
+            )
+            .unwrap();
+        self.publish(program, feed, price);
+
+        (program, feed)
+    }
+
+    fn publish(&mut self, program: Pubkey, feed: Pubkey, price: u128) {
+        let publish = Instruction::new_with_bytes(
+            program,
+            &price.to_le_bytes(),
+            vec![AccountMeta::new(feed, false)],
+        );
+        self.send_as_authority("Publish", publish).unwrap();
+    }
+
+    fn set_oracle(&self, oracle: &Pubkey, max_deviation_bps: u16, max_age: i64) -> Instruction {
+        let mut data = oracle.to_bytes().to_vec();
+        data.extend_from_slice(&max_deviation_bps.to_le_bytes());
+        data.extend_from_slice(&max_age.to_le_bytes());
+
+        self.authority_instruction(*SetOracle::DISCRIMINATOR, &data)
+    }
+
+    /// Guard the pool with a feed publishing its current price.
+    fn guarded(&mut self) -> Pubkey {
+        let (x, y) = self.reserves();
+        let (_, feed) = self.price_feed(y as u128 * PRICE_SCALE / x as u128);
+
+        self.send_as_authority(
+            "SetOracle",
+            self.set_oracle(&feed, MAX_DEVIATION_BPS, MAX_AGE),
+        )
+        .unwrap();
+
+        feed
+    }
+
+    fn guarded_swap(&self, feed: &Pubkey, is_x: bool, amount: u64) -> Instruction {
+        let mut swap = self.swap(is_x, amount, 1, Deadline::Slot(u64::MAX));
+        swap.accounts.push(AccountMeta::new_readonly(*feed, false));
+        swap
+    }
+}
+
+#[test]
+fn set_oracle_stores_the_guard() {
+    let mut env = Env::funded();
+
+    let feed = env.guarded();
+
+    env.pool(|pool| {
+        assert_eq!(pool.has_oracle(), Some(feed.to_bytes()));
+        assert_eq!(pool.oracle_max_deviation_bps(), MAX_DEVIATION_BPS);
+        assert_eq!(pool.oracle_max_age(), MAX_AGE);
+    });
+}
+
+#[test]
+fn set_oracle_requires_the_authority() {
+    let mut env = Env::funded();
+
+    let mut set_oracle = env.set_oracle(&Pubkey::new_unique(), MAX_DEVIATION_BPS, MAX_AGE);
+    set_oracle.accounts[0].pubkey = env.user.pubkey();
+
+    assert_eq!(
+        env.send_as_user("SetOracle", set_oracle),
+        Err(InstructionError::IncorrectAuthority)
+    );
+}
+
+#[test]
+fn swap_within_the_oracle_bound_goes_through() {
+    let mut env = Env::funded();
+    let feed = env.guarded();
+
+    // 0.1% of the reserves moves the price by about 0.2%
+    env.send_as_user("Swap", env.guarded_swap(&feed, true, 1_000))
+        .unwrap();
+}
+
+#[test]
+fn swap_beyond_the_oracle_bound_is_rejected() {
+    let mut env = Env::funded();
+    let feed = env.guarded();
+
+    // 1% of the reserves moves the price by about 2%
+    assert_eq!(
+        env.send_as_user("Swap", env.guarded_swap(&feed, true, 10_000)),
+        Err(custom(AmmError::OracleDeviation))
+    );
+}
+
+#[test]
+fn swap_against_a_stale_feed_is_rejected() {
+    let mut env = Env::funded();
+    let feed = env.guarded();
+
+    env.warp_time(MAX_AGE + 1);
+
+    assert_eq!(
+        env.send_as_user("Swap", env.guarded_swap(&feed, true, 1_000)),
+        Err(custom(AmmError::StaleOracle))
+    );
+}
+
+#[test]
+fn swap_against_another_feed_is_rejected() {
+    let mut env = Env::funded();
+    env.guarded();
+
+    let (x, y) = env.reserves();
+    let (_, other) = env.price_feed(y as u128 * PRICE_SCALE / x as u128);
+
+    assert_eq!(
+        env.send_as_user("Swap", env.guarded_swap(&other, true, 1_000)),
+        Err(custom(AmmError::InvalidOracle))
+    );
+}
+
+#[test]
+fn oracle_guards_pairs_priced_far_below_one_base_unit() {
+    let mut env = Env::initialized();
+
+    // One base unit of x is worth 1e-12 of y, which a 1e9 price scale rounds to zero
+    let (x, y) = (1_000_000_000_000_000u64, 1_000u64);
+    let (user, mint_x) = (env.user.pubkey(), env.mint_x);
+    env.mint_to(&user, &mint_x, 2 * x);
+    env.send_as_user(
+        "Deposit",
+        env.deposit(1_000_000, x, y, Deadline::Slot(u64::MAX)),
+    )
+    .unwrap();
+
+    let feed = env.guarded();
+
+    // 0.2% of the reserves moves the price by about 0.4%, 10% by about 17%
+    env.send_as_user("Swap", env.guarded_swap(&feed, true, x / 500))
+        .unwrap();
+    assert_eq!(
+        env.send_as_user("Swap", env.guarded_swap(&feed, true, x / 10)),
+        Err(custom(AmmError::OracleDeviation))
+    );
+}
@@ -0,0 +1,166 @@
+//! `IncreasePosition`, `DecreasePosition` and `ClaimFees`.
+
+use super::*;
+use pinocchio_amm::{ClaimFees, DecreasePosition, IncreasePosition};
+
+impl Env {
+    /// The fee position of the user in the pool, and its bump.
+    fn position(&self) -> (Pubkey, u8) {
+        Pubkey::find_program_address(
+            &[
+                b"position",
+                self.config.as_ref(),
+                self.user.pubkey().as_ref(),
+            ],
+            &program_id(),
+        )
+    }
+
+    /// Escrow `amount` of LP in the position at `position`.
+    fn increase_position(&self, position: &Pubkey, amount: u64) -> Instruction {
+        let user = self.user.pubkey();
+
+        Instruction::new_with_bytes(
+            program_id(),
+            &[
+                &[*IncreasePosition::DISCRIMINATOR],
+                &amount.to_le_bytes()[..],
+            ]
+            .concat(),
+            vec![
+                AccountMeta::new(user, true),
+                AccountMeta::new(self.config, false),
+                AccountMeta::new(*position, false),
+                AccountMeta::new(get_associated_token_address(&user, &self.mint_lp), false),
+                AccountMeta::new(get_associated_token_address(position, &self.mint_lp), false),
+                AccountMeta::new_readonly(solana_sdk::system_program::ID, false),
+                AccountMeta::new_readonly(spl_token::ID, false),
+            ],
+        )
+    }
+
+    fn decrease_position(&self, amount: u64) -> Instruction {
+        let (user, position) = (self.user.pubkey(), self.position().0);
+
+        Instruction::new_with_bytes(
+            program_id(),
+            &[
+                &[*DecreasePosition::DISCRIMINATOR],
+                &amount.to_le_bytes()[..],
+            ]
+            .concat(),
+            vec![
+                AccountMeta::new(user, true),
+                AccountMeta::new(self.config, false),
+                AccountMeta::new(position, false),
+                AccountMeta::new(get_associated_token_address(&user, &self.mint_lp), false),
+                AccountMeta::new(
+                    get_associated_token_address(&position, &self.mint_lp),
+                    false,
+                ),
+                AccountMeta::new_readonly(spl_token::ID, false),
+            ],
+        )
+    }
+
+    /// Claim the fees of the user's position, paid to the accounts of `owner`.
+    fn claim_fees(&self, owner: &Pubkey) -> Instruction {
+        Instruction::new_with_bytes(
+            program_id(),
+            &[*ClaimFees::DISCRIMINATOR],
+            vec![
+                AccountMeta::new(*owner, true),
+                AccountMeta::new(self.config, false),
+                AccountMeta::new(self.position().0, false),
+                AccountMeta::new(get_associated_token_address(owner, &self.mint_x), false),
+                AccountMeta::new(get_associated_token_address(owner, &self.mint_y), false),
+                AccountMeta::new(self.vault_x, false),
+                AccountMeta::new(self.vault_y, false),
+                AccountMeta::new_readonly(spl_token::ID, false),
+            ],
+        )
+    }
+
+    /// A funded pool where the user escrowed half of their LP.
+    fn positioned() -> Self {
+        let mut env = Self::funded();
+        let (position, mint_lp) = (env.position().0, env.mint_lp);
+        env.create_ata(&position, &mint_lp);
+
+        env.send_as_user(
+            "IncreasePosition",
+            env.increase_position(&position, 500_000),
+        )
+        .unwrap();
+
+        env
+    }
+}
+
+#[test]
+fn position_earns_its_share_of_the_fees() {
+    let mut env = Env::positioned();
+    assert_eq!(env.user_balance(&env.mint_lp), 500_000);
+    assert_eq!(env.pool(|pool| pool.positioned_lp()), 500_000);
+
+    env.send_as_user("Swap", env.swap(true, 10_000, 1, Deadline::Slot(u64::MAX)))
+        .unwrap();
+    let x = env.user_balance(&env.mint_x);
+
+    // Half of the LP is positioned, so half of the fee of 30 is set aside
+    env.send_as_user("ClaimFees", env.claim_fees(&env.user.pubkey()))
+        .unwrap();
+    assert_eq!(env.user_balance(&env.mint_x), x + 15);
+    assert_eq!(env.pool(|pool| pool.fees_x()), 0);
+
+    env.send_as_user("DecreasePosition", env.decrease_position(500_000))
+        .unwrap();
+    assert_eq!(env.user_balance(&env.mint_lp), 1_000_000);
+    assert_eq!(env.pool(|pool| pool.positioned_lp()), 0);
+}
+
+#[test]
+fn increase_position_requires_the_canonical_address() {
+    let mut env = Env::funded();
+    let (config, user) = (env.config, env.user.pubkey());
+
+    // Same seeds, a lower bump
+    let position = (0..env.position().1)
+        .rev()
+        .find_map(|bump| {
+            Pubkey::create_program_address(
+                &[b"position", config.as_ref(), user.as_ref(), &[bump]],
+                &program_id(),
+            )
+            .ok()
+        })
+        .unwrap();
+    let mint_lp = env.mint_lp;
+    env.create_ata(&position, &mint_lp);
+
+    assert_eq!(
+        env.send_as_user(
+            "IncreasePosition",
+            env.increase_position(&position, 500_000)
+        ),
+        Err(InstructionError::InvalidAccountData)
+    );
+}
+
+#[test]
+fn positions_reject_other_owners_and_overdrafts() {
+    let mut env = Env::positioned();
+    env.send_as_user("Swap", env.swap(true, 10_000, 1, Deadline::Slot(u64::MAX)))
+        .unwrap();
+
+    let authority = env.authority.pubkey();
+    assert_eq!(
+        env.send_as_authority("ClaimFees", env.claim_fees(&authority)),
+        Err(InstructionError::InvalidAccountData)
+    );
+
+    assert_eq!(
+        env.send_as_user("DecreasePosition", env.decrease_position(500_001)),
+        Err(InstructionError::InsufficientFunds)
+    );
+}
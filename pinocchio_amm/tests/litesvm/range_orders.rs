@@ -0,0 +1,171 @@
+//! `PlaceRangeOrder`, `ClaimRangeOrder` and `CancelRangeOrder`, and the fills of `Swap`.
+
+use super::*;
+use pinocchio_amm::{CancelRangeOrder, ClaimRangeOrder, PlaceRangeOrder, PRICE_SCALE};
+
+/// The funded pool prices x at 4 y, the order buys x once it falls to 3.95 y.
+const ORDER_PRICE: u128 = 395 * PRICE_SCALE / 100;
+const ORDER_AMOUNT: u64 = 10_000;
+
+impl Env {
+    /// The range order of the user at seed 0, and its bump.
+    fn range_order(&self) -> (Pubkey, u8) {
+        Pubkey::find_program_address(
+            &[
+                b"range_order",
+                self.config.as_ref(),
+                self.user.pubkey().as_ref(),
+                &0u64.to_le_bytes(),
+            ],
+            &program_id(),
+        )
+    }
+
+    /// Rest `ORDER_AMOUNT` of y, selling it for x at `price`.
+    fn place_range_order(&self, price: u128) -> Instruction {
+        let (user, (order, bump)) = (self.user.pubkey(), self.range_order());
+
+        let mut data = vec![*PlaceRangeOrder::DISCRIMINATOR];
+        data.extend_from_slice(&0u64.to_le_bytes());
+        data.push(false as u8);
+        data.extend_from_slice(&ORDER_AMOUNT.to_le_bytes());
+        data.extend_from_slice(&price.to_le_bytes());
+        data.push(bump);
+
+        Instruction::new_with_bytes(
+            program_id(),
+            &data,
+            vec![
+                AccountMeta::new(user, true),
+                AccountMeta::new(self.config, false),
+                AccountMeta::new(order, false),
+                AccountMeta::new(get_associated_token_address(&user, &self.mint_y), false),
+                AccountMeta::new(self.vault_x, false),
+                AccountMeta::new(self.vault_y, false),
+                AccountMeta::new_readonly(solana_sdk::system_program::ID, false),
+                AccountMeta::new_readonly(spl_token::ID, false),
+            ],
+        )
+    }
+
+    /// Claim or cancel the user's order, signed by `owner` and paid in x when `is_x`.
+    fn range_order_instruction(
+        &self,
+        discriminator: u8,
+        owner: &Pubkey,
+        is_x: bool,
+    ) -> Instruction {
+        let (mint, vault) = match is_x {
+            true => (self.mint_x, self.vault_x),
+            false => (self.mint_y, self.vault_y),
+        };
+
+        Instruction::new_with_bytes(
+            program_id(),
+            &[discriminator],
+            vec![
+                AccountMeta::new(*owner, true),
+                AccountMeta::new(self.config, false),
+                AccountMeta::new(self.range_order().0, false),
+                AccountMeta::new(get_associated_token_address(owner, &mint), false),
+                AccountMeta::new(vault, false),
+                AccountMeta::new_readonly(spl_token::ID, false),
+            ],
+        )
+    }
+
+    /// A funded pool where the user rests an order at `ORDER_PRICE`.
+    fn with_range_order() -> Self {
+        let mut env = Self::funded();
+
+        env.send_as_user("PlaceRangeOrder", env.place_range_order(ORDER_PRICE))
+            .unwrap();
+
+        env
+    }
+}
+
+#[test]
+fn a_crossed_range_order_fills_at_its_price() {
+    let mut env = Env::with_range_order();
+    let user = env.user.pubkey();
+    assert_eq!(env.pool(|pool| pool.orders_y()), ORDER_AMOUNT);
+
+    // Selling 1% of the reserves takes the price to about 3.92
+    let mut swap = env.swap(true, 10_000, 1, Deadline::Slot(u64::MAX));
+    swap.accounts
+        .push(AccountMeta::new(env.range_order().0, false));
+    env.send_as_user("Swap", swap).unwrap();
+    let x = env.user_balance(&env.mint_x);
+
+    // The whole order fits before the price is back at 3.95
+    let proceeds = (ORDER_AMOUNT as u128 * PRICE_SCALE / ORDER_PRICE) as u64;
+    assert_eq!(
+        env.pool(|pool| (pool.orders_x(), pool.orders_y())),
+        (proceeds, 0)
+    );
+
+    env.send_as_user(
+        "ClaimRangeOrder",
+        env.range_order_instruction(*ClaimRangeOrder::DISCRIMINATOR, &user, true),
+    )
+    .unwrap();
+
+    assert_eq!(env.user_balance(&env.mint_x), x + proceeds);
+    assert_eq!(env.pool(|pool| pool.orders_x()), 0);
+    assert!(env
+        .svm
+        .get_account(&env.range_order().0)
+        .is_none_or(|account| account.lamports == 0));
+}
+
+#[test]
+fn cancel_range_order_returns_the_deposit() {
+    let mut env = Env::with_range_order();
+    let (user, y) = (env.user.pubkey(), env.user_balance(&env.mint_y));
+
+    // Nothing filled yet, so nothing to claim
+    assert_eq!(
+        env.send_as_user(
+            "ClaimRangeOrder",
+            env.range_order_instruction(*ClaimRangeOrder::DISCRIMINATOR, &user, true)
+        ),
+        Err(InstructionError::InvalidAccountData)
+    );
+
+    env.send_as_user(
+        "CancelRangeOrder",
+        env.range_order_instruction(*CancelRangeOrder::DISCRIMINATOR, &user, false),
+    )
+    .unwrap();
+
+    assert_eq!(env.user_balance(&env.mint_y), y + ORDER_AMOUNT);
+    assert_eq!(env.pool(|pool| pool.orders_y()), 0);
+}
+
+#[test]
+fn place_range_order_rejects_a_crossed_price() {
+    let mut env = Env::funded();
+
+    assert_eq!(
+        env.send_as_user(
+            "PlaceRangeOrder",
+            env.place_range_order(41 * PRICE_SCALE / 10)
+        ),
+        Err(InstructionError::InvalidInstructionData)
+    );
+}
+
+#[test]
+fn range_orders_only_pay_out_to_their_owner() {
+    let mut env = Env::with_range_order();
+    let authority = env.authority.pubkey();
+
+    assert_eq!(
+        env.send_as_authority(
+            "CancelRangeOrder",
+            env.range_order_instruction(*CancelRangeOrder::DISCRIMINATOR, &authority, false)
+        ),
+        Err(InstructionError::InvalidAccountData)
+    );
+}
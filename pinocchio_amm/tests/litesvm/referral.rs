@@ -0,0 +1,71 @@
+//! `SetReferralFee` and the referral share of `Swap`.
+
+use super::*;
+
+impl Env {
+    /// A funded pool paying referrers up to `max_referral_bps` of the fee, and a
+    /// referrer holding an x account.
+    fn with_referrer(max_referral_bps: u16) -> (Self, Pubkey) {
+        let mut env = Self::funded();
+        let (referrer, mint_x) = (Pubkey::new_unique(), env.mint_x);
+        env.create_ata(&referrer, &mint_x);
+
+        env.send_as_authority("SetReferralFee", env.set_referral_fee(max_referral_bps))
+            .unwrap();
+
+        (env, referrer)
+    }
+}
+
+#[test]
+fn set_referral_fee_requires_the_authority() {
+    let mut env = Env::funded();
+
+    let mut set_referral_fee = env.set_referral_fee(1_000);
+    set_referral_fee.accounts[0].pubkey = env.user.pubkey();
+
+    assert_eq!(
+        env.send_as_user("SetReferralFee", set_referral_fee),
+        Err(InstructionError::IncorrectAuthority)
+    );
+    assert_eq!(
+        env.send_as_authority("SetReferralFee", env.set_referral_fee(10_001)),
+        Err(InstructionError::InvalidAccountData)
+    );
+}
+
+#[test]
+fn swap_pays_the_referrer_out_of_the_fee() {
+    let (mut env, referrer) = Env::with_referrer(5_000);
+    let (x, _) = env.reserves();
+
+    env.send_as_user("Swap", env.referred_swap(10_000, &referrer, 5_000))
+        .unwrap();
+
+    // Half of the fee of 30 goes to the referrer instead of the pool
+    assert_eq!(env.balance(&referrer, &env.mint_x), 15);
+    assert_eq!(env.reserves().0, x + 10_000 - 15);
+}
+
+#[test]
+fn swap_caps_the_referral_share() {
+    let (mut env, referrer) = Env::with_referrer(1_000);
+
+    env.send_as_user("Swap", env.referred_swap(10_000, &referrer, 5_000))
+        .unwrap();
+
+    assert_eq!(env.balance(&referrer, &env.mint_x), 3);
+}
+
+#[test]
+fn swap_with_a_referral_share_requires_the_referrer() {
+    let (mut env, referrer) = Env::with_referrer(5_000);
+
+    let mut swap = env.referred_swap(10_000, &referrer, 5_000);
+    swap.accounts.pop();
+
+    assert_eq!(
+        env.send_as_user("Swap", swap),
+        Err(InstructionError::NotEnoughAccountKeys)
+    );
+}
@@ -0,0 +1,127 @@
+//! `SetWeights`, and the weights of a pool shifting from `start_time` to `end_time`.
+
+use super::*;
+use pinocchio_amm::{math::spot_price, SetWeights, PRICE_SCALE};
+
+const START_WEIGHT_X: u16 = 8_000;
+const END_WEIGHT_X: u16 = 2_000;
+const DURATION: i64 = 1_000;
+
+impl Env {
+    fn set_weights(
+        &self,
+        start_weight_x: u16,
+        end_weight_x: u16,
+        start: i64,
+        end: i64,
+    ) -> Instruction {
+        let mut data = start_weight_x.to_le_bytes().to_vec();
+        data.extend_from_slice(&end_weight_x.to_le_bytes());
+        data.extend_from_slice(&start.to_le_bytes());
+        data.extend_from_slice(&end.to_le_bytes());
+
+        let mut set_weights = self.authority_instruction(*SetWeights::DISCRIMINATOR, &data);
+        set_weights
+            .accounts
+            .push(AccountMeta::new_readonly(self.mint_lp, false));
+        set_weights
+    }
+
+    /// A pool funded like `Env::funded`, whose weight of x falls from `START_WEIGHT_X` to
+    /// `END_WEIGHT_X` over `DURATION` seconds from now.
+    fn shifting() -> Self {
+        let mut env = Self::initialized();
+        let now = env.svm.get_sysvar::<Clock>().unix_timestamp;
+
+        env.send_as_authority(
+            "SetWeights",
+            env.set_weights(START_WEIGHT_X, END_WEIGHT_X, now, now + DURATION),
+        )
+        .unwrap();
+        env.send_as_user(
+            "Deposit",
+            env.deposit(1_000_000, 1_000_000, 4_000_000, Deadline::Slot(u64::MAX)),
+        )
+        .unwrap();
+
+        env
+    }
+
+    /// Price `GetPoolState` reports.
+    fn reported_price(&mut self) -> u128 {
+        // `price` follows the state, the reserves, the LP supply and the fee
+        u128::from_le_bytes(self.pool_state()[27..43].try_into().unwrap())
+    }
+
+    /// Price of the pool reserves at a weight of x of `weight_x`.
+    fn price_at(&self, weight_x: u16) -> u128 {
+        let (x, y) = self.reserves();
+        spot_price(x, weight_x, y, 10_000 - weight_x, PRICE_SCALE).unwrap()
+    }
+}
+
+#[test]
+fn set_weights_requires_the_authority() {
+    let mut env = Env::initialized();
+
+    let mut set_weights = env.set_weights(START_WEIGHT_X, END_WEIGHT_X, 0, DURATION);
+    set_weights.accounts[0].pubkey = env.user.pubkey();
+
+    assert_eq!(
+        env.send_as_user("SetWeights", set_weights),
+        Err(InstructionError::IncorrectAuthority)
+    );
+}
+
+#[test]
+fn set_weights_is_rejected_once_the_pool_holds_liquidity() {
+    let mut env = Env::funded();
+
+    assert_eq!(
+        env.send_as_authority(
+            "SetWeights",
+            env.set_weights(START_WEIGHT_X, END_WEIGHT_X, 0, DURATION)
+        ),
+        Err(custom(AmmError::PoolNotEmpty))
+    );
+}
+
+#[test]
+fn weights_shift_from_start_to_end_time() {
+    let mut env = Env::shifting();
+
+    env.pool(|pool| assert_eq!(pool.start_weight_x(), START_WEIGHT_X));
+    assert_eq!(env.reported_price(), env.price_at(START_WEIGHT_X));
+
+    env.warp_time(DURATION / 4);
+    assert_eq!(env.reported_price(), env.price_at(6_500));
+
+    env.warp_time(DURATION / 4);
+    assert_eq!(env.reported_price(), env.price_at(5_000));
+
+    // The weights stay at the end ones past `end_time`
+    env.warp_time(DURATION);
+    assert_eq!(env.reported_price(), env.price_at(END_WEIGHT_X));
+}
+
+#[test]
+fn swaps_trade_at_the_current_weights() {
+    let mut env = Env::shifting();
+    env.warp_time(DURATION / 2);
+
+    let (x, y) = env.reserves();
+    let before = env.user_balance(&env.mint_y);
+    env.send_as_user("Swap", env.swap(true, 10_000, 1, Deadline::Slot(u64::MAX)))
+        .unwrap();
+    let bought = env.user_balance(&env.mint_y) - before;
+
+    // At equal weights the curve is the constant product one, up to the rounding of the
+    // weighted math in the pool's favour
+    let expected = constant_product::swap(x, y, 10_000, FEE, 0)
+        .unwrap()
+        .withdraw;
+    assert!(
+        bought <= expected && expected - bought <= 1,
+        "{bought} != {expected}"
+    );
+}